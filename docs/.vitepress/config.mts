@@ -38,11 +38,18 @@ export default defineConfig({
         text: "Features",
         items: [
           { text: "Temperature Bar", link: "/features/temperature-bar" },
+          { text: "Temperature Graphs", link: "/features/temperature-graphs" },
+          { text: "Bed Mesh", link: "/features/bed-mesh" },
           { text: "Position Bar", link: "/features/position-bar" },
+          { text: "Print Progress", link: "/features/print-progress" },
+          { text: "Print Control", link: "/features/print-control" },
+          { text: "File Browser", link: "/features/file-browser" },
           {
             text: "Job History Browser",
             link: "/features/job-history-browser",
           },
+          { text: "TUI Console", link: "/features/console" },
+          { text: "Macros", link: "/features/macros" },
           { text: "Tab Completion", link: "/features/tab-completion" },
           {
             text: "Syntax Highlighting",
@@ -0,0 +1,271 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+/// How deep aliases may reference other aliases before we assume a loop
+const MAX_EXPANSION_DEPTH: usize = 8;
+
+/// User-defined command aliases
+///
+/// An alias body is one or more comma-separated G-code lines (commas inside
+/// quotes don't separate). Bodies may use
+/// positional (`$1`, `${2}`) and named (`${temp}`) placeholders, filled from the
+/// words following the alias name (`pla 215` or `heat TEMP=215`).
+#[derive(Debug, Clone, Default)]
+pub struct Aliases {
+    aliases: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn new(aliases: BTreeMap<String, String>) -> Self {
+        let mut result = Self::default();
+        for (name, body) in aliases {
+            result.define(&name, &body);
+        }
+        result
+    }
+
+    /// Define or replace an alias
+    pub fn define(&mut self, name: &str, body: &str) {
+        self.remove(name);
+        self.aliases.insert(name.to_string(), body.trim().to_string());
+    }
+
+    /// Remove an alias, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.lookup(name) {
+            Some((key, _)) => {
+                let key = key.to_string();
+                self.aliases.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Look up an alias by name, ignoring case
+    pub fn lookup(&self, name: &str) -> Option<(&str, &str)> {
+        self.aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map(|(alias, body)| (alias.as_str(), body.as_str()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.aliases.keys().map(|k| k.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Expand a single command, which is passed through unless it names an alias
+    pub fn expand(&self, command: &str) -> Result<Vec<String>> {
+        self.expand_command(command.trim(), 0)
+    }
+
+    /// Split a comma-separated REPL or scripting line and expand every alias in it
+    pub fn expand_line(&self, line: &str) -> Result<Vec<String>> {
+        let mut commands = Vec::new();
+        for cmd in split_commands(line) {
+            commands.extend(self.expand_command(cmd, 0)?);
+        }
+        Ok(commands)
+    }
+
+    fn expand_command(&self, command: &str, depth: usize) -> Result<Vec<String>> {
+        let mut words = command.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(Vec::new());
+        };
+        let Some((alias, body)) = self.lookup(name) else {
+            return Ok(vec![command.to_string()]);
+        };
+
+        if depth >= MAX_EXPANSION_DEPTH {
+            return Err(anyhow!("Alias '{}' expands too deeply (recursive alias?)", alias));
+        }
+
+        let args: Vec<&str> = words.collect();
+        let body = substitute(alias, body, &args)?;
+
+        let mut commands = Vec::new();
+        for line in split_commands(&body) {
+            commands.extend(self.expand_command(line, depth + 1)?);
+        }
+        Ok(commands)
+    }
+}
+
+/// Split on commas outside quotes, dropping empty commands
+pub fn split_commands(line: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (',', None) => {
+                commands.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    commands.push(&line[start..]);
+    commands.into_iter().map(str::trim).filter(|c| !c.is_empty()).collect()
+}
+
+/// Replace `$N`, `${N}` and `${name}` placeholders in an alias body
+fn substitute(alias: &str, body: &str, args: &[&str]) -> Result<String> {
+    let named = |key: &str| {
+        args.iter().find_map(|arg| {
+            let (name, value) = arg.split_once('=')?;
+            name.eq_ignore_ascii_case(key).then_some(value)
+        })
+    };
+    let positional = |index: usize| index.checked_sub(1).and_then(|i| args.get(i).copied());
+
+    let mut result = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        let key: String = if chars.peek() == Some(&'{') {
+            chars.next();
+            let mut key = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => key.push(c),
+                    None => return Err(anyhow!("Alias '{}' has an unclosed '${{'", alias)),
+                }
+            }
+            key.trim().to_string()
+        } else {
+            let mut digits = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            if digits.is_empty() {
+                result.push('$');
+                continue;
+            }
+            digits
+        };
+
+        let value = match key.parse::<usize>() {
+            Ok(index) => positional(index),
+            Err(_) => named(&key),
+        };
+        match value {
+            Some(value) => result.push_str(value),
+            None => return Err(anyhow!("Alias '{}' is missing argument '{}'", alias, key)),
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn define(pairs: &[(&str, &str)]) -> Aliases {
+        Aliases::new(
+            pairs
+                .iter()
+                .map(|(name, body)| (name.to_string(), body.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn substitutes_positional_and_named_arguments() {
+        let aliases = define(&[("heat", "M104 S$1, M140 S${2}, M141 S${chamber}")]);
+        assert_eq!(
+            aliases.expand_line("heat 210 60 CHAMBER=40").unwrap(),
+            ["M104 S210", "M140 S60", "M141 S40"]
+        );
+    }
+
+    #[test]
+    fn missing_argument_is_an_error() {
+        let aliases = define(&[("pla", "M104 S$1 ; $2"), ("named", "M104 S${temp}")]);
+        let err = aliases.expand_line("pla 210").unwrap_err();
+        assert_eq!(err.to_string(), "Alias 'pla' is missing argument '2'");
+        let err = aliases.expand_line("named").unwrap_err();
+        assert_eq!(err.to_string(), "Alias 'named' is missing argument 'temp'");
+    }
+
+    #[test]
+    fn multi_digit_placeholders_are_read_greedily() {
+        let args: Vec<String> = (1..=10).map(|i| format!("a{}", i)).collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        assert_eq!(substitute("t", "$10", &args).unwrap(), "a10");
+        assert_eq!(substitute("t", "${1}0", &args).unwrap(), "a10");
+        assert_eq!(substitute("t", "${1}0", &["x"]).unwrap(), "x0");
+        assert!(substitute("t", "$10", &["x"]).is_err());
+    }
+
+    #[test]
+    fn dollar_without_placeholder_is_literal() {
+        assert_eq!(substitute("t", "M117 $ cost $x", &[]).unwrap(), "M117 $ cost $x");
+        assert_eq!(substitute("t", "M117 5$", &[]).unwrap(), "M117 5$");
+    }
+
+    #[test]
+    fn unknown_commands_pass_through() {
+        let aliases = define(&[("pla", "M104 S210")]);
+        assert_eq!(aliases.expand_line("G28, PLA").unwrap(), ["G28", "M104 S210"]);
+    }
+
+    #[test]
+    fn nested_aliases_expand() {
+        let aliases = define(&[("pla", "hot 210"), ("hot", "M104 S$1")]);
+        assert_eq!(aliases.expand_line("pla").unwrap(), ["M104 S210"]);
+    }
+
+    #[test]
+    fn recursive_aliases_hit_the_depth_limit() {
+        let aliases = define(&[("loop", "G4 P1, loop")]);
+        let err = aliases.expand_line("loop").unwrap_err();
+        assert!(err.to_string().contains("expands too deeply"), "{}", err);
+
+        let aliases = define(&[("ping", "pong"), ("pong", "ping")]);
+        assert!(aliases.expand_line("ping").is_err());
+    }
+
+    #[test]
+    fn single_commands_are_not_split() {
+        let aliases = define(&[("pla", "M104 S210, M140 S60")]);
+        assert_eq!(aliases.expand("M117 a, b").unwrap(), ["M117 a, b"]);
+        assert_eq!(aliases.expand("pla").unwrap(), ["M104 S210", "M140 S60"]);
+    }
+
+    #[test]
+    fn commas_inside_quotes_do_not_split() {
+        let aliases = define(&[("say", r#"RESPOND MSG="hi, $1", M400"#)]);
+        assert_eq!(
+            aliases.expand_line(r#"RESPOND MSG="a, b", G28"#).unwrap(),
+            [r#"RESPOND MSG="a, b""#, "G28"]
+        );
+        assert_eq!(aliases.expand("say bob").unwrap(), [r#"RESPOND MSG="hi, bob""#, "M400"]);
+        assert_eq!(split_commands("SET_GCODE_VARIABLE VALUE='{1, 2}' , ,M105"), ["SET_GCODE_VARIABLE VALUE='{1, 2}'", "M105"]);
+    }
+
+    #[test]
+    fn unclosed_placeholder_is_an_error() {
+        let err = substitute("t", "M104 S${temp", &["TEMP=200"]).unwrap_err();
+        assert_eq!(err.to_string(), "Alias 't' has an unclosed '${'");
+    }
+}
@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -28,4 +29,23 @@ pub struct Cli {
     /// Multiple commands can be separated by commas
     #[arg(long, short = 'c')]
     pub command: Option<String>,
+
+    /// Append a timestamped session transcript to this file
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub subcommand: Option<Commands>,
+}
+
+/// One-shot printer actions
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Heat the printer using a material preset from moonriver.toml
+    Preheat {
+        /// Preset name, e.g. PLA or PETG (case-insensitive)
+        preset: String,
+    },
+    /// Turn off all heaters
+    Cooldown,
 }
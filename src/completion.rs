@@ -0,0 +1,735 @@
+//! Command catalogue and completion shared by the REPL and the TUI console
+
+use crate::alias::Aliases;
+use crate::macros::{macros_from_config, MacroInfo};
+use crate::meta::{self, CONFIG_ACTIONS, META_COMMANDS, POWER_ACTIONS};
+use crate::moonraker::MoonrakerClient;
+use crate::printer_config;
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+
+/// A completion candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// Shown in the candidate list
+    pub display: String,
+    /// Inserted in place of the word being completed
+    pub replacement: String,
+}
+
+/// What a command name refers to, for syntax highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    /// Standard G-code such as `G28` or `M104`
+    Gcode,
+    /// Klipper extended command
+    Klipper,
+    /// `.`-prefixed REPL command
+    Meta,
+    /// User-defined alias
+    Alias,
+    /// `gcode_macro` from the printer's config
+    Macro,
+    Unknown,
+}
+
+/// Every command Moonriver knows about, for completion and highlighting
+///
+/// Built-in G-code and Klipper commands are filled in by `new`; the rest is
+/// loaded from the printer with `load`.
+#[derive(Debug, Clone)]
+pub struct CommandCatalog {
+    /// Upper-case names of the printer's `gcode_macro` objects
+    pub macros: HashSet<String>,
+    pub aliases: HashSet<String>,
+    pub gcode_commands: HashSet<String>,
+    pub klipper_commands: HashSet<String>,
+    /// Commands from `printer.gcode.help`, including ones from extras modules
+    /// missing from the built-in list
+    pub printer_commands: HashSet<String>,
+    /// Loaded printer objects from `printer.objects.list`
+    pub objects: Vec<String>,
+    /// Saved bed mesh profile names
+    pub mesh_profiles: Vec<String>,
+    /// Macros parsed from `configfile.config`, keyed by upper-case name
+    pub macro_info: BTreeMap<String, MacroInfo>,
+    /// Command descriptions from `printer.gcode.help`, keyed by upper-case name
+    pub descriptions: BTreeMap<String, String>,
+    /// G-code files for `.print`
+    pub files: Vec<String>,
+    /// Power device names for `.power`
+    pub power_devices: Vec<String>,
+    /// Klipper config files for `.config edit`
+    pub config_files: Vec<String>,
+}
+
+impl CommandCatalog {
+    pub fn new() -> Self {
+        let mut gcode_commands = HashSet::new();
+
+        // Standard G-code commands from Klipper documentation
+        // Basic movement and positioning
+        let commands = vec![
+            // G-codes (movement and basic commands)
+            "G0", "G1", "G2", "G3", "G4", "G10", "G11", "G17", "G18", "G19",
+            "G28", "G90", "G91", "G92",
+            
+            // M-codes (standard)
+            "M18", "M84", "M82", "M83", "M104", "M105", "M106", "M107", "M109",
+            "M112", "M114", "M115", "M117", "M118", "M119", "M140", "M190",
+            "M204", "M220", "M221", "M400",
+            
+            // Additional M-codes from broader RepRap compatibility
+            "M0", "M1", "M17", "M20", "M21", "M22", "M23", "M24", "M25", "M26", "M27",
+            "M73", "M110", "M111", "M113", "M120", "M121", "M141", "M143", "M191",
+            "M205", "M206", "M207", "M208", "M209", "M226", "M280", "M300", "M301",
+            "M302", "M303", "M304", "M305", "M401", "M402", "M403", "M404", "M405",
+            "M406", "M407", "M408", "M409", "M410", "M412", "M413", "M415", "M420",
+            "M421", "M422", "M425", "M428", "M500", "M501", "M502", "M503", "M504",
+            "M505", "M510", "M511", "M512", "M524", "M540", "M550", "M551", "M552",
+            "M553", "M554", "M555", "M556", "M557", "M558", "M559", "M560", "M561",
+            "M562", "M563", "M564", "M565", "M566", "M567", "M568", "M569", "M570",
+            "M571", "M572", "M573", "M574", "M575", "M576", "M577", "M578", "M579",
+            "M580", "M581", "M582", "M583", "M584", "M585", "M586", "M587", "M588",
+            "M589", "M591", "M592", "M593", "M594", "M595", "M596", "M597", "M598",
+            "M599", "M600", "M601", "M602", "M603", "M605", "M665", "M666", "M667",
+            "M668", "M669", "M670", "M671", "M672", "M673", "M674", "M675", "M700",
+            "M701", "M702", "M703", "M704", "M705", "M706", "M707", "M708", "M709",
+            "M710", "M850", "M851", "M852", "M860", "M861", "M862", "M863", "M864",
+            "M865", "M866", "M867", "M868", "M869", "M871", "M876", "M900", "M905",
+            "M906", "M907", "M908", "M909", "M910", "M911", "M912", "M913", "M914",
+            "M915", "M916", "M917", "M918", "M919", "M928", "M951", "M997", "M998",
+            "M999",
+        ];
+
+        for cmd in commands {
+            gcode_commands.insert(cmd.to_string());
+        }
+
+        // Klipper extended commands from documentation
+        let mut klipper_commands = HashSet::new();
+        let klipper_cmds = vec![
+            // Core commands
+            "HELP", "STATUS", "RESTART", "FIRMWARE_RESTART",
+            
+            // Movement and positioning
+            "GET_POSITION", "SET_GCODE_OFFSET", "SAVE_GCODE_STATE", "RESTORE_GCODE_STATE",
+            "SET_VELOCITY_LIMIT", "SET_KINEMATIC_POSITION",
+            
+            // Temperature
+            "SET_HEATER_TEMPERATURE", "TURN_OFF_HEATERS", "TEMPERATURE_WAIT",
+            "PID_CALIBRATE", "SET_TEMPERATURE_FAN_TARGET",
+            
+            // Bed mesh and calibration
+            "BED_MESH_CALIBRATE", "BED_MESH_PROFILE", "BED_MESH_OUTPUT", "BED_MESH_MAP",
+            "BED_MESH_CLEAR", "BED_MESH_OFFSET",
+            "BED_SCREWS_ADJUST", "BED_TILT_CALIBRATE",
+            "SCREWS_TILT_CALCULATE",
+            
+            // Probing
+            "PROBE", "QUERY_PROBE", "PROBE_ACCURACY", "PROBE_CALIBRATE",
+            "Z_OFFSET_APPLY_PROBE", "Z_OFFSET_APPLY_ENDSTOP",
+            "MANUAL_PROBE", "Z_ENDSTOP_CALIBRATE",
+            "ACTIVATE_PROBE", "DEACTIVATE_PROBE",
+            
+            // Delta calibration
+            "DELTA_CALIBRATE", "DELTA_ANALYZE",
+            
+            // Extruder
+            "ACTIVATE_EXTRUDER", "SET_PRESSURE_ADVANCE", "SET_EXTRUDER_ROTATION_DISTANCE",
+            "SYNC_EXTRUDER_MOTION",
+            
+            // Firmware retraction
+            "SET_RETRACTION", "GET_RETRACTION",
+            
+            // Stepper control
+            "SET_STEPPER_ENABLE", "STEPPER_BUZZ", "FORCE_MOVE",
+            
+            // TMC drivers
+            "DUMP_TMC", "INIT_TMC", "SET_TMC_CURRENT", "SET_TMC_FIELD",
+            
+            // Input shaper and resonance
+            "SET_INPUT_SHAPER", "MEASURE_AXES_NOISE", "TEST_RESONANCES", "SHAPER_CALIBRATE",
+            
+            // Accelerometer
+            "ACCELEROMETER_MEASURE", "ACCELEROMETER_QUERY",
+            "ACCELEROMETER_DEBUG_READ", "ACCELEROMETER_DEBUG_WRITE",
+            
+            // Endstops and limits
+            "QUERY_ENDSTOPS", "QUERY_ADC",
+            
+            // Print control
+            "PAUSE", "RESUME", "CANCEL_PRINT", "CLEAR_PAUSE",
+            "SDCARD_PRINT_FILE", "SDCARD_RESET_FILE",
+            
+            // Configuration
+            "SAVE_CONFIG", "SET_GCODE_VARIABLE", "SAVE_VARIABLE",
+            "SET_IDLE_TIMEOUT",
+            
+            // Display and output
+            "SET_DISPLAY_TEXT", "SET_DISPLAY_GROUP", "RESPOND",
+            
+            // Fans and pins
+            "SET_FAN_SPEED", "SET_PIN", "SET_LED", "SET_LED_TEMPLATE",
+            
+            // Gantry leveling
+            "QUAD_GANTRY_LEVEL", "Z_TILT_ADJUST",
+            
+            // Skew correction
+            "SET_SKEW", "GET_CURRENT_SKEW", "CALC_MEASURED_SKEW", "SKEW_PROFILE",
+            
+            // Manual stepper
+            "MANUAL_STEPPER",
+            
+            // Tuning tower
+            "TUNING_TOWER",
+            
+            // Filament sensors
+            "QUERY_FILAMENT_SENSOR", "SET_FILAMENT_SENSOR",
+            "QUERY_FILAMENT_WIDTH", "RESET_FILAMENT_WIDTH_SENSOR",
+            "ENABLE_FILAMENT_WIDTH_SENSOR", "DISABLE_FILAMENT_WIDTH_SENSOR",
+            "QUERY_RAW_FILAMENT_WIDTH", "ENABLE_FILAMENT_WIDTH_LOG",
+            "DISABLE_FILAMENT_WIDTH_LOG",
+            
+            // Exclude object
+            "EXCLUDE_OBJECT", "EXCLUDE_OBJECT_DEFINE", "EXCLUDE_OBJECT_START",
+            "EXCLUDE_OBJECT_END",
+            
+            // Dual carriage
+            "SET_DUAL_CARRIAGE", "SAVE_DUAL_CARRIAGE_STATE", "RESTORE_DUAL_CARRIAGE_STATE",
+            
+            // Palette 2
+            "PALETTE_CONNECT", "PALETTE_DISCONNECT", "PALETTE_CLEAR",
+            "PALETTE_CUT", "PALETTE_SMART_LOAD",
+            
+            // Smart effector
+            "SET_SMART_EFFECTOR", "RESET_SMART_EFFECTOR",
+            
+            // Servo
+            "SET_SERVO",
+            
+            // BLTouch
+            "BLTOUCH_DEBUG", "BLTOUCH_STORE",
+            
+            // Delayed gcode
+            "UPDATE_DELAYED_GCODE",
+            
+            // Angle calibration
+            "ANGLE_CALIBRATE", "ANGLE_CHIP_CALIBRATE", "ANGLE_DEBUG_READ", "ANGLE_DEBUG_WRITE",
+            
+            // Endstop phase
+            "ENDSTOP_PHASE_CALIBRATE",
+            
+            // Axis twist compensation
+            "AXIS_TWIST_COMPENSATION_CALIBRATE",
+            
+            // Generic cartesian
+            "SET_STEPPER_CARRIAGES",
+            
+            // Load cell
+            "LOAD_CELL_DIAGNOSTIC", "LOAD_CELL_CALIBRATE", "LOAD_CELL_TARE",
+            "LOAD_CELL_READ", "LOAD_CELL_TEST_TAP",
+            
+            // Probe eddy current
+            "PROBE_EDDY_CURRENT_CALIBRATE", "LDC_CALIBRATE_DRIVE_CURRENT",
+            
+            // PWM cycle time
+            "SET_DIGIPOT",
+            
+            // Print stats
+            "SET_PRINT_STATS_INFO",
+            
+            // SDCard loop
+            "SDCARD_LOOP_BEGIN", "SDCARD_LOOP_END", "SDCARD_LOOP_DESIST",
+            
+            // Temperature probe
+            "TEMPERATURE_PROBE_CALIBRATE", "TEMPERATURE_PROBE_NEXT",
+            "TEMPERATURE_PROBE_COMPLETE", "TEMPERATURE_PROBE_ENABLE",
+            
+            // Z thermal adjust
+            "SET_Z_THERMAL_ADJUST",
+        ];
+
+        for cmd in klipper_cmds {
+            klipper_commands.insert(cmd.to_string());
+        }
+
+        Self {
+            macros: HashSet::new(),
+            aliases: HashSet::new(),
+            gcode_commands,
+            klipper_commands,
+            printer_commands: HashSet::new(),
+            objects: Vec::new(),
+            mesh_profiles: Vec::new(),
+            macro_info: BTreeMap::new(),
+            descriptions: BTreeMap::new(),
+            files: Vec::new(),
+            power_devices: Vec::new(),
+            config_files: Vec::new(),
+        }
+    }
+
+    pub fn set_help(&mut self, help: BTreeMap<String, String>) {
+        self.printer_commands = help.keys().map(|name| name.to_uppercase()).collect();
+        self.descriptions = help
+            .into_iter()
+            .map(|(name, description)| (name.to_uppercase(), description))
+            .collect();
+    }
+
+    /// Set the loaded printer objects; `gcode_macro` objects become macros
+    pub fn set_objects(&mut self, objects: Vec<String>) {
+        self.macros = objects
+            .iter()
+            .filter_map(|o| o.strip_prefix("gcode_macro "))
+            .map(|name| name.to_uppercase())
+            .collect();
+        self.objects = objects;
+    }
+
+    pub fn set_aliases(&mut self, aliases: &Aliases) {
+        self.aliases = aliases.names().map(|name| name.to_string()).collect();
+    }
+
+    pub fn add_klipper_commands(&mut self, commands: Vec<String>) {
+        for cmd in commands {
+            self.klipper_commands.insert(cmd);
+        }
+    }
+
+    /// Complete the command name at the start of a command
+    fn complete_command(&self, prefix: &str) -> Vec<Completion> {
+        let mut candidates = Vec::new();
+        let input_upper = prefix.to_uppercase();
+
+        // Check for G-code command completion
+        for cmd in &self.gcode_commands {
+            if cmd.starts_with(&input_upper) {
+                candidates.push(Completion {
+                    display: cmd.clone(),
+                    replacement: cmd.clone(),
+                });
+            }
+        }
+
+        // Check for Klipper extended command completion
+        for cmd in &self.klipper_commands {
+            if cmd.starts_with(&input_upper) {
+                candidates.push(Completion {
+                    display: cmd.clone(),
+                    replacement: cmd.clone(),
+                });
+            }
+        }
+
+        // Check for commands only the printer knows about
+        for cmd in &self.printer_commands {
+            if cmd.starts_with(&input_upper) && !self.macros.contains(cmd) {
+                candidates.push(Completion {
+                    display: cmd.clone(),
+                    replacement: cmd.clone(),
+                });
+            }
+        }
+
+        // Check for alias completion
+        for alias in &self.aliases {
+            if alias.to_uppercase().starts_with(&input_upper) {
+                candidates.push(Completion {
+                    display: alias.clone(),
+                    replacement: alias.clone(),
+                });
+            }
+        }
+
+        // Check for macro completion
+        for macro_name in &self.macros {
+            if macro_name.starts_with(&input_upper) {
+                candidates.push(Completion {
+                    display: macro_name.clone(),
+                    replacement: macro_name.clone(),
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Complete a `.`-prefixed meta command and its arguments
+    pub fn complete_meta(&self, command: &str, word: &str) -> Vec<Completion> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let arg_index = if command.ends_with(char::is_whitespace) {
+            words.len()
+        } else {
+            words.len().saturating_sub(1)
+        };
+
+        let values: Vec<String> = match (arg_index, words.first().map(|w| w.to_lowercase())) {
+            (0, _) => META_COMMANDS.iter().map(|c| c.name.to_string()).collect(),
+            (1, Some(name)) if name == ".print" => self.files.clone(),
+            (1, Some(name)) if name == ".power" => self.power_devices.clone(),
+            (1, Some(name)) if name == ".source" => source_candidates(),
+            (2, Some(name)) if name == ".power" => POWER_ACTIONS.iter().map(|a| a.to_string()).collect(),
+            (1, Some(name)) if name == ".config" => CONFIG_ACTIONS.iter().map(|a| a.to_string()).collect(),
+            (2, Some(name)) if name == ".config" && words.get(1) == Some(&"edit") => self.config_files.clone(),
+            (1, Some(name)) if name == ".log" => vec!["on".to_string(), "off".to_string()],
+            (_, Some(name)) if name == ".query" => self.objects.clone(),
+            _ => Vec::new(),
+        };
+
+        let word_lower = word.to_lowercase();
+        values
+            .into_iter()
+            .filter(|value| value.to_lowercase().starts_with(&word_lower))
+            .map(|value| Completion {
+                display: value.clone(),
+                replacement: value,
+            })
+            .collect()
+    }
+
+    /// Parameter names accepted by a command, from the built-in table or macro definition
+    pub fn parameters_for(&self, command: &str) -> Vec<String> {
+        if let Some(info) = self.macro_info.get(command) {
+            return info.params.iter().map(|p| p.name.clone()).collect();
+        }
+        klipper_parameters(command)
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    /// Complete a `NAME=` parameter for a command
+    fn complete_parameter(&self, command: &str, prefix: &str) -> Vec<Completion> {
+        let prefix_upper = prefix.to_uppercase();
+        self.parameters_for(command)
+            .into_iter()
+            .filter(|param| param.starts_with(&prefix_upper))
+            .map(|param| Completion {
+                display: param.clone(),
+                replacement: format!("{}=", param),
+            })
+            .collect()
+    }
+
+    /// Complete the value of `PARAM=` from the printer's objects
+    fn complete_value(&self, command: &str, param: &str, prefix: &str) -> Vec<Completion> {
+        let values: Vec<String> = match (command, param) {
+            ("BED_MESH_PROFILE", "LOAD" | "SAVE" | "REMOVE") | ("BED_MESH_CALIBRATE", "PROFILE") => {
+                self.mesh_profiles.clone()
+            }
+            ("SET_GCODE_VARIABLE", "MACRO") => self.macro_info.keys().map(|m| m.to_lowercase()).collect(),
+            (_, "HEATER") => self.heater_names(),
+            (_, "EXTRUDER") => self.objects_named(&["extruder"]),
+            (_, "FAN") => self.object_suffixes(&["fan_generic"]),
+            (_, "TEMPERATURE_FAN") => self.object_suffixes(&["temperature_fan"]),
+            (_, "LED") => self.object_suffixes(&["led", "neopixel", "dotstar", "pca9533", "pca9632"]),
+            (_, "PIN") => self.object_suffixes(&["output_pin"]),
+            (_, "SERVO") => self.object_suffixes(&["servo"]),
+            ("TEMPERATURE_WAIT", "SENSOR") => {
+                let mut sensors = self.heater_names();
+                sensors.extend(
+                    self.objects
+                        .iter()
+                        .filter(|o| o.starts_with("temperature_sensor ") || o.starts_with("temperature_fan "))
+                        .map(|o| format!("\"{}\"", o)),
+                );
+                sensors
+            }
+            (_, "SENSOR") => self.object_suffixes(&["filament_switch_sensor", "filament_motion_sensor"]),
+            (_, "STEPPER") => {
+                let mut steppers = self.object_suffixes(&[
+                    "tmc2130", "tmc2208", "tmc2209", "tmc2240", "tmc2660", "tmc5160", "manual_stepper",
+                ]);
+                steppers.sort();
+                steppers.dedup();
+                steppers
+            }
+            _ => Vec::new(),
+        };
+
+        let prefix_lower = prefix.to_lowercase();
+        values
+            .into_iter()
+            .filter(|value| value.to_lowercase().starts_with(&prefix_lower))
+            .map(|value| Completion {
+                display: value.clone(),
+                replacement: value,
+            })
+            .collect()
+    }
+
+    /// Heater names as accepted by `SET_HEATER_TEMPERATURE HEATER=`
+    fn heater_names(&self) -> Vec<String> {
+        let mut heaters = self.objects_named(&["extruder", "heater_bed"]);
+        heaters.extend(self.object_suffixes(&["heater_generic"]));
+        heaters
+    }
+
+    /// Objects whose full name matches one of the given types (e.g. `extruder`, `extruder1`)
+    fn objects_named(&self, types: &[&str]) -> Vec<String> {
+        self.objects
+            .iter()
+            .filter(|o| {
+                !o.contains(' ')
+                    && types.iter().any(|t| {
+                        o.strip_prefix(t)
+                            .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Names of objects like `<type> <name>`, returning just `<name>`
+    fn object_suffixes(&self, types: &[&str]) -> Vec<String> {
+        self.objects
+            .iter()
+            .filter_map(|o| {
+                let (object_type, name) = o.split_once(' ')?;
+                types.contains(&object_type).then(|| name.to_string())
+            })
+            .collect()
+    }
+
+    /// What kind of command `name` is, ignoring case
+    pub fn kind(&self, name: &str) -> CommandKind {
+        let upper = name.to_uppercase();
+        if self.gcode_commands.contains(&upper) {
+            CommandKind::Gcode
+        } else if self.klipper_commands.contains(&upper) {
+            CommandKind::Klipper
+        } else if meta::find(name).is_some() {
+            CommandKind::Meta
+        } else if self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name)) {
+            CommandKind::Alias
+        } else if self.macros.contains(&upper) || self.macro_info.contains_key(&upper) {
+            CommandKind::Macro
+        } else if self.printer_commands.contains(&upper) {
+            CommandKind::Klipper
+        } else {
+            CommandKind::Unknown
+        }
+    }
+
+    /// Complete the word before the cursor; `input` is the line up to the cursor
+    ///
+    /// Returns where the replaced word starts and the candidates, sorted. Only
+    /// the command after the last `,` or newline is considered, so aliases and
+    /// multi-line blocks complete like single commands.
+    pub fn complete(&self, input: &str) -> (usize, Vec<Completion>) {
+        let command_start = input.rfind([',', '\n']).map(|i| i + 1).unwrap_or(0);
+        let command = &input[command_start..];
+        let name_start = command_start + command.len() - command.trim_start().len();
+        let word_start = command
+            .rfind(char::is_whitespace)
+            .map(|i| command_start + i + 1)
+            .unwrap_or(name_start);
+        let word = &input[word_start..];
+
+        let (start, mut candidates) = match command.split_whitespace().next() {
+            // Still typing the command name itself
+            Some(_) if word_start == name_start => (word_start, self.complete_command(word)),
+            None => (word_start, self.complete_command(word)),
+            Some(name) => {
+                let command_name = name.to_uppercase();
+                match word.split_once('=') {
+                    Some((param, value)) => (
+                        word_start + param.len() + 1,
+                        self.complete_value(&command_name, &param.to_uppercase(), value),
+                    ),
+                    None => (word_start, self.complete_parameter(&command_name, word)),
+                }
+            }
+        };
+        candidates.sort_by(|a, b| a.display.cmp(&b.display));
+        candidates.dedup_by(|a, b| a.replacement == b.replacement);
+        (start, candidates)
+    }
+
+    /// Load macros, descriptions, printer objects, files and power devices
+    ///
+    /// Anything the printer can't provide is left as it was.
+    pub async fn load(&mut self, client: &mut MoonrakerClient) {
+        if let Ok(help) = client.get_gcode_help().await {
+            self.set_help(help);
+        }
+        if let Ok(objects) = client.list_objects().await {
+            self.set_objects(objects);
+        }
+        if let Ok(status) = client
+            .query_objects(json!({ "configfile": ["config"], "bed_mesh": ["profiles"] }))
+            .await
+        {
+            if let Some(config) = status.get("configfile").and_then(|c| c.get("config")) {
+                self.macro_info = macros_from_config(config);
+            }
+            if let Some(profiles) = status
+                .get("bed_mesh")
+                .and_then(|b| b.get("profiles"))
+                .and_then(|p| p.as_object())
+            {
+                self.mesh_profiles = profiles.keys().cloned().collect();
+            }
+        }
+
+        // Files and power devices for `.print` / `.power`
+        if let Ok(files) = meta::list_files(client).await {
+            self.files = files;
+        }
+        if let Ok(devices) = meta::list_power_devices(client).await {
+            self.power_devices = devices.into_iter().map(|(name, _)| name).collect();
+        }
+        if let Ok(files) = printer_config::list_config_files(client).await {
+            self.config_files = files.into_iter().map(|file| file.path).collect();
+        }
+    }
+}
+
+/// Saved scripts plus G-code files in the current directory
+fn source_candidates() -> Vec<String> {
+    let mut candidates = meta::list_scripts();
+    if let Ok(entries) = std::fs::read_dir(".") {
+        candidates.extend(entries.filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let extension = name.rsplit_once('.')?.1.to_lowercase();
+            matches!(extension.as_str(), "gcode" | "gco" | "g").then_some(name)
+        }));
+    }
+    candidates
+}
+
+/// Parameters of common Klipper commands, used for completion
+fn klipper_parameters(command: &str) -> &'static [&'static str] {
+    match command {
+        "SET_HEATER_TEMPERATURE" => &["HEATER", "TARGET"],
+        "TEMPERATURE_WAIT" => &["SENSOR", "MINIMUM", "MAXIMUM"],
+        "PID_CALIBRATE" => &["HEATER", "TARGET", "WRITE_FILE"],
+        "SET_TEMPERATURE_FAN_TARGET" => &["TEMPERATURE_FAN", "TARGET", "MIN_SPEED", "MAX_SPEED"],
+        "SET_FAN_SPEED" => &["FAN", "SPEED"],
+        "SET_PIN" => &["PIN", "VALUE", "CYCLE_TIME"],
+        "SET_LED" => &["LED", "RED", "GREEN", "BLUE", "WHITE", "INDEX", "TRANSMIT", "SYNC"],
+        "SET_LED_TEMPLATE" => &["LED", "TEMPLATE", "INDEX"],
+        "SET_SERVO" => &["SERVO", "ANGLE", "WIDTH"],
+        "BED_MESH_PROFILE" => &["LOAD", "SAVE", "REMOVE"],
+        "BED_MESH_CALIBRATE" => &["PROFILE", "METHOD", "HORIZONTAL_MOVE_Z", "ADAPTIVE", "ADAPTIVE_MARGIN"],
+        "BED_MESH_OUTPUT" => &["PGP"],
+        "BED_MESH_OFFSET" => &["X", "Y", "ZFADE"],
+        "SET_GCODE_OFFSET" => &["X", "Y", "Z", "X_ADJUST", "Y_ADJUST", "Z_ADJUST", "MOVE", "MOVE_SPEED"],
+        "SAVE_GCODE_STATE" => &["NAME"],
+        "RESTORE_GCODE_STATE" => &["NAME", "MOVE", "MOVE_SPEED"],
+        "SET_VELOCITY_LIMIT" => &["VELOCITY", "ACCEL", "SQUARE_CORNER_VELOCITY", "MINIMUM_CRUISE_RATIO"],
+        "SET_KINEMATIC_POSITION" => &["X", "Y", "Z", "SET_HOMED", "CLEAR_HOMED"],
+        "ACTIVATE_EXTRUDER" => &["EXTRUDER"],
+        "SET_PRESSURE_ADVANCE" => &["EXTRUDER", "ADVANCE", "SMOOTH_TIME"],
+        "SET_EXTRUDER_ROTATION_DISTANCE" => &["EXTRUDER", "DISTANCE"],
+        "SYNC_EXTRUDER_MOTION" => &["EXTRUDER", "MOTION_QUEUE"],
+        "SET_RETRACTION" => &["RETRACT_LENGTH", "RETRACT_SPEED", "UNRETRACT_EXTRA_LENGTH", "UNRETRACT_SPEED"],
+        "SET_STEPPER_ENABLE" => &["STEPPER", "ENABLE"],
+        "STEPPER_BUZZ" => &["STEPPER"],
+        "FORCE_MOVE" => &["STEPPER", "DISTANCE", "VELOCITY", "ACCEL"],
+        "MANUAL_STEPPER" => &["STEPPER", "ENABLE", "SET_POSITION", "SPEED", "ACCEL", "MOVE", "STOP_ON_ENDSTOP", "SYNC"],
+        "DUMP_TMC" => &["STEPPER", "REGISTER"],
+        "INIT_TMC" => &["STEPPER"],
+        "SET_TMC_CURRENT" => &["STEPPER", "CURRENT", "HOLDCURRENT"],
+        "SET_TMC_FIELD" => &["STEPPER", "FIELD", "VALUE", "VELOCITY"],
+        "SET_INPUT_SHAPER" => &[
+            "SHAPER_FREQ_X", "SHAPER_FREQ_Y", "SHAPER_TYPE", "SHAPER_TYPE_X", "SHAPER_TYPE_Y",
+            "DAMPING_RATIO_X", "DAMPING_RATIO_Y",
+        ],
+        "SHAPER_CALIBRATE" => &["AXIS", "FREQ_START", "FREQ_END", "HZ_PER_SEC", "CHIPS", "MAX_SMOOTHING"],
+        "TEST_RESONANCES" => &["AXIS", "OUTPUT", "NAME", "FREQ_START", "FREQ_END", "HZ_PER_SEC", "CHIPS", "POINT", "INPUT_SHAPING"],
+        "PROBE" => &["PROBE_SPEED", "LIFT_SPEED", "SAMPLES", "SAMPLE_RETRACT_DIST", "SAMPLES_TOLERANCE", "SAMPLES_TOLERANCE_RETRIES", "SAMPLES_RESULT"],
+        "PROBE_ACCURACY" => &["PROBE_SPEED", "SAMPLES", "SAMPLE_RETRACT_DIST"],
+        "Z_TILT_ADJUST" | "QUAD_GANTRY_LEVEL" => &["RETRIES", "RETRY_TOLERANCE", "HORIZONTAL_MOVE_Z"],
+        "SET_GCODE_VARIABLE" => &["MACRO", "VARIABLE", "VALUE"],
+        "SAVE_VARIABLE" => &["VARIABLE", "VALUE"],
+        "SET_IDLE_TIMEOUT" => &["TIMEOUT"],
+        "SET_DISPLAY_TEXT" => &["MSG"],
+        "SET_DISPLAY_GROUP" => &["DISPLAY", "GROUP"],
+        "RESPOND" => &["TYPE", "PREFIX", "MSG"],
+        "SDCARD_PRINT_FILE" => &["FILENAME"],
+        "EXCLUDE_OBJECT" => &["NAME", "CURRENT", "RESET"],
+        "QUERY_FILAMENT_SENSOR" => &["SENSOR"],
+        "SET_FILAMENT_SENSOR" => &["SENSOR", "ENABLE"],
+        "UPDATE_DELAYED_GCODE" => &["ID", "DURATION"],
+        "SET_Z_THERMAL_ADJUST" => &["ENABLE", "TEMP_COEFF", "REF_TEMP"],
+        "SET_DUAL_CARRIAGE" => &["CARRIAGE", "MODE"],
+        "TUNING_TOWER" => &["COMMAND", "PARAMETER", "START", "SKIP", "FACTOR", "BAND", "STEP_DELTA", "STEP_HEIGHT"],
+        "SET_PRINT_STATS_INFO" => &["TOTAL_LAYER", "CURRENT_LAYER"],
+        "SET_SKEW" => &["XY", "XZ", "YZ", "CLEAR"],
+        "SKEW_PROFILE" => &["LOAD", "SAVE", "REMOVE"],
+        "ACCELEROMETER_MEASURE" => &["CHIP", "NAME"],
+        "ACCELEROMETER_QUERY" => &["CHIP", "RATE"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> CommandCatalog {
+        let mut catalog = CommandCatalog::new();
+        catalog.set_objects(
+            ["extruder", "heater_bed", "heater_generic chamber", "gcode_macro clean_nozzle"]
+                .iter()
+                .map(|o| o.to_string())
+                .collect(),
+        );
+        catalog.set_help(BTreeMap::from([
+            ("SET_HEATER_TEMPERATURE".to_string(), "Sets a heater temperature".to_string()),
+            ("AFC_STATUS".to_string(), "Show AFC status".to_string()),
+            ("CLEAN_NOZZLE".to_string(), "G-Code macro".to_string()),
+        ]));
+        catalog
+    }
+
+    fn replacements(candidates: &[Completion]) -> Vec<&str> {
+        candidates.iter().map(|c| c.replacement.as_str()).collect()
+    }
+
+    #[test]
+    fn completes_the_word_in_the_middle_of_a_command() {
+        let (start, candidates) = catalog().complete("SET_HEATER_TEMPERATURE HEA");
+        assert_eq!(start, 23);
+        assert_eq!(replacements(&candidates), ["HEATER="]);
+    }
+
+    #[test]
+    fn completes_the_command_after_a_separator() {
+        let (start, candidates) = catalog().complete("G28, SET_HEATER_T");
+        assert_eq!(start, 5);
+        assert_eq!(replacements(&candidates), ["SET_HEATER_TEMPERATURE"]);
+    }
+
+    #[test]
+    fn completes_parameter_values_after_the_equals_sign() {
+        let (start, candidates) = catalog().complete("G28, SET_HEATER_TEMPERATURE HEATER=ch");
+        assert_eq!(start, 35);
+        assert_eq!(replacements(&candidates), ["chamber"]);
+    }
+
+    #[test]
+    fn heater_parameter_completes_heater_names() {
+        let (_, candidates) = catalog().complete("SET_HEATER_TEMPERATURE HEATER=");
+        assert_eq!(replacements(&candidates), ["chamber", "extruder", "heater_bed"]);
+    }
+
+    #[test]
+    fn target_parameter_has_no_completions() {
+        let (_, candidates) = catalog().complete("SET_HEATER_TEMPERATURE TARGET=");
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn help_commands_are_not_macros() {
+        let catalog = catalog();
+        assert_eq!(catalog.kind("set_heater_temperature"), CommandKind::Klipper);
+        assert_eq!(catalog.kind("AFC_STATUS"), CommandKind::Klipper);
+        assert_eq!(catalog.kind("clean_nozzle"), CommandKind::Macro);
+        assert_eq!(replacements(&catalog.complete("AFC").1), ["AFC_STATUS"]);
+        assert_eq!(replacements(&catalog.complete("CLEAN").1), ["CLEAN_NOZZLE"]);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[allow(dead_code)]
@@ -7,6 +8,27 @@ pub struct Config {
     pub host: String,
     pub port: u16,
     pub api_key: Option<String>,
+    /// Named material presets, e.g. `[presets.PETG]`
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>,
+    /// Command aliases, e.g. `pla = "M104 S210, M140 S60"`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Session transcript settings (`[log]`)
+    #[serde(default)]
+    pub log: LogConfig,
+    /// Command history settings (`[history]`)
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Jog feed rates for the TUI Position tab (`[jog]`)
+    #[serde(default)]
+    pub jog: JogConfig,
+    /// Thumbnail previews on the TUI Files tab (`[thumbnails]`)
+    #[serde(default)]
+    pub thumbnails: ThumbnailConfig,
+    /// TUI console scrollback (`[console]`)
+    #[serde(default)]
+    pub console: ConsoleConfig,
 }
 
 impl Default for Config {
@@ -15,15 +37,255 @@ impl Default for Config {
             host: "localhost".to_string(),
             port: 7125,
             api_key: None,
+            presets: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            log: LogConfig::default(),
+            history: HistoryConfig::default(),
+            jog: JogConfig::default(),
+            thumbnails: ThumbnailConfig::default(),
+            console: ConsoleConfig::default(),
         }
     }
 }
 
+impl Config {
+    /// Look up a preset by name, ignoring case
+    pub fn find_preset(&self, name: &str) -> Option<(&String, &Preset)> {
+        self.presets
+            .iter()
+            .find(|(preset_name, _)| preset_name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Heater targets for a material preset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Preset {
+    pub extruder: Option<f64>,
+    pub bed: Option<f64>,
+    pub chamber: Option<f64>,
+}
+
+impl Preset {
+    /// Heater objects and targets this preset sets, resolved against Klipper's
+    /// `heaters.available_heaters`
+    ///
+    /// Roles the printer has no heater for (`"chamber"`, say) are returned
+    /// separately instead of producing a command Klipper would reject.
+    pub fn targets(&self, available_heaters: &[String]) -> (Vec<(String, f64)>, Vec<&'static str>) {
+        let mut targets = Vec::new();
+        let mut missing = Vec::new();
+        let roles = [
+            ("extruder", self.extruder),
+            ("bed", self.bed),
+            ("chamber", self.chamber),
+        ];
+        for (role, temp) in roles {
+            let Some(temp) = temp else {
+                continue;
+            };
+            match resolve_heater(role, available_heaters) {
+                Some(object) => targets.push((object.to_string(), temp)),
+                None => missing.push(role),
+            }
+        }
+        (targets, missing)
+    }
+
+    /// Build the G-code needed to apply this preset, skipping missing heaters
+    pub fn to_gcode(&self, available_heaters: &[String]) -> (Vec<String>, Vec<&'static str>) {
+        let (targets, missing) = self.targets(available_heaters);
+        let commands = targets
+            .iter()
+            .map(|(object, temp)| heater_gcode(object, *temp))
+            .collect();
+        (commands, missing)
+    }
+}
+
+/// Heater object for a preset role, if the printer has one
+///
+/// The chamber is `heater_generic chamber`, or else the first `heater_generic`
+/// with "chamber" in its name.
+fn resolve_heater<'a>(role: &str, available_heaters: &'a [String]) -> Option<&'a str> {
+    let find = |object: &str| available_heaters.iter().find(|h| *h == object);
+    let heater = match role {
+        "extruder" => find("extruder"),
+        "bed" => find("heater_bed"),
+        _ => find("heater_generic chamber").or_else(|| {
+            available_heaters.iter().find(|object| {
+                object
+                    .strip_prefix("heater_generic ")
+                    .is_some_and(|name| name.to_lowercase().contains("chamber"))
+            })
+        }),
+    };
+    heater.map(String::as_str)
+}
+
+/// `SET_HEATER_TEMPERATURE` for a heater object such as `heater_generic chamber`
+pub fn heater_gcode(object: &str, temp: f64) -> String {
+    let name = object.strip_prefix("heater_generic ").unwrap_or(object);
+    format!("SET_HEATER_TEMPERATURE HEATER={} TARGET={}", name, temp as u32)
+}
+
+/// Session transcript settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Transcript file; logging is off unless this or `--log-file` is set
+    pub file: Option<String>,
+    pub format: LogFormat,
+    /// Rotate once the file reaches this size (0 disables rotation)
+    pub max_size_mb: f64,
+    /// Number of rotated files to keep
+    pub keep: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            format: LogFormat::Text,
+            max_size_mb: 10.0,
+            keep: 3,
+        }
+    }
+}
+
+/// Transcript line format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `2024-01-01 12:00:00.000 [repl] [command] G28`
+    #[default]
+    Text,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Command history settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Keep a separate history for each `host:port`
+    pub per_printer: bool,
+    /// Oldest entries are dropped beyond this many
+    pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            per_printer: false,
+            max_entries: 1000,
+        }
+    }
+}
+
+/// Feed rates for jogging, in mm/min
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JogConfig {
+    pub feed_x: f64,
+    pub feed_y: f64,
+    pub feed_z: f64,
+}
+
+impl Default for JogConfig {
+    fn default() -> Self {
+        Self {
+            feed_x: 6000.0,
+            feed_y: 6000.0,
+            feed_z: 600.0,
+        }
+    }
+}
+
+impl JogConfig {
+    /// Feed rate for an axis (0 = X)
+    pub fn feed(&self, axis: usize) -> f64 {
+        match axis {
+            0 => self.feed_x,
+            1 => self.feed_y,
+            _ => self.feed_z,
+        }
+    }
+}
+
+/// Thumbnail preview settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailConfig {
+    pub protocol: ThumbnailProtocol,
+}
+
+/// How thumbnails are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailProtocol {
+    /// Pick kitty or sixel from the environment, otherwise half blocks
+    #[default]
+    Auto,
+    /// Coloured half-block characters; works in any true-colour terminal
+    Blocks,
+    Sixel,
+    Kitty,
+    /// Don't show thumbnails
+    Off,
+}
+
+/// TUI console settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsoleConfig {
+    /// Oldest messages are dropped beyond this many
+    pub max_messages: usize,
+    /// Show the time each message arrived
+    pub timestamps: bool,
+    /// Categories hidden when the TUI starts
+    pub hide: Vec<ConsoleCategory>,
+    /// Lines matching this regex are hidden; empty shows everything
+    pub filter: String,
+}
+
+impl Default for ConsoleConfig {
+    fn default() -> Self {
+        Self {
+            max_messages: 5000,
+            timestamps: true,
+            hide: Vec::new(),
+            filter: DEFAULT_CONSOLE_FILTER.to_string(),
+        }
+    }
+}
+
+/// Periodic temperature reports such as `B:60.0 /60.0 T0:210.0 /210.0`
+pub const DEFAULT_CONSOLE_FILTER: &str = r"^(ok\s+)?(B|T\d*):\s*-?[\d.]+\s*/";
+
+/// Kinds of console output, for filtering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleCategory {
+    /// Commands sent from the console
+    Command,
+    /// Plain printer responses
+    Response,
+    /// `// ` comments and Moonriver's own notices
+    Info,
+    /// `echo:` lines, which include `M118` and `RESPOND` output
+    Echo,
+    /// `!! ` errors and failed requests
+    Error,
+}
+
+/// G-code that switches every heater off
+pub const COOLDOWN_GCODE: &str = "TURN_OFF_HEATERS";
+
 /// Load configuration from file or return default
 pub fn load_config() -> anyhow::Result<Config> {
     // Try to load from moonriver.toml in current directory
     let config_path = PathBuf::from("moonriver.toml");
-    
+
     if config_path.exists() {
         let contents = std::fs::read_to_string(&config_path)?;
         let config: Config = toml::from_str(&contents)?;
@@ -33,3 +295,36 @@ pub fn load_config() -> anyhow::Result<Config> {
         Ok(Config::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heaters(objects: &[&str]) -> Vec<String> {
+        objects.iter().map(|o| o.to_string()).collect()
+    }
+
+    #[test]
+    fn presets_resolve_the_chamber_heater() {
+        let preset = Preset { extruder: Some(250.0), bed: Some(100.0), chamber: Some(50.0) };
+        let (commands, missing) =
+            preset.to_gcode(&heaters(&["extruder", "heater_bed", "heater_generic Chamber_Heater"]));
+        assert_eq!(
+            commands,
+            [
+                "SET_HEATER_TEMPERATURE HEATER=extruder TARGET=250",
+                "SET_HEATER_TEMPERATURE HEATER=heater_bed TARGET=100",
+                "SET_HEATER_TEMPERATURE HEATER=Chamber_Heater TARGET=50",
+            ]
+        );
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn presets_skip_missing_heaters() {
+        let preset = Preset { extruder: Some(210.0), bed: None, chamber: Some(50.0) };
+        let (targets, missing) = preset.targets(&heaters(&["extruder", "heater_generic hotbox"]));
+        assert_eq!(targets, [("extruder".to_string(), 210.0)]);
+        assert_eq!(missing, ["chamber"]);
+    }
+}
@@ -0,0 +1,17 @@
+//! Formatting helpers shared by the REPL and the TUI
+
+/// A duration as `1h 02m 03s`, `2m 05s` or `7s`
+pub fn format_duration(seconds: f64) -> String {
+    let secs = seconds as u64;
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    let seconds = secs % 60;
+
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
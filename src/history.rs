@@ -0,0 +1,239 @@
+use crate::config::HistoryConfig;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Header rustyline writes at the top of its history files
+const HEADER: &str = "#V2";
+
+/// Command history shared by the REPL and the TUI console
+///
+/// Entries are appended to the file as they are added, so several sessions can
+/// share one history. The file uses rustyline's format: one entry per line, with
+/// backslashes and newlines escaped so multi-line blocks survive.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+    max_entries: usize,
+}
+
+impl History {
+    /// Open the history file for `server` (`host:port`), importing the old
+    /// `~/.moonriver_history` the first time
+    pub fn open(config: &HistoryConfig, server: &str) -> Self {
+        let path = history_path(config, server);
+        let mut history = Self {
+            entries: Vec::new(),
+            path: path.clone(),
+            max_entries: config.max_entries,
+        };
+        let Some(path) = path else {
+            return history;
+        };
+
+        let legacy = dirs::home_dir().map(|home| home.join(".moonriver_history"));
+        let source = match legacy {
+            Some(legacy) if !path.exists() && legacy.exists() => legacy,
+            _ => path.clone(),
+        };
+        if let Ok(contents) = std::fs::read_to_string(&source) {
+            history.entries = parse(&contents);
+        }
+
+        // Compact the file when it has grown past the limit (or was imported)
+        if history.entries.len() > history.max_entries || source != path {
+            history.trim();
+            let _ = history.rewrite();
+        }
+        history
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|e| e.as_str())
+    }
+
+    /// Add an entry, skipping repeats of the previous one
+    pub fn add(&mut self, entry: &str) {
+        let entry = entry.trim();
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        self.trim();
+
+        if let Some(path) = &self.path {
+            let new_file = !path.exists();
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                if new_file {
+                    let _ = writeln!(file, "{}", HEADER);
+                }
+                let _ = writeln!(file, "{}", escape(entry));
+            }
+        }
+    }
+
+    /// Index of the newest entry before `before` that starts with `prefix`
+    pub fn search_prefix(&self, prefix: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.starts_with(prefix) && entry != prefix)
+    }
+
+    /// Index of the oldest entry after `after` that starts with `prefix`
+    pub fn search_prefix_forward(&self, prefix: &str, after: usize) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(after + 1)
+            .find(|(_, entry)| entry.starts_with(prefix) && entry.as_str() != prefix)
+            .map(|(index, _)| index)
+    }
+
+    /// Index of the newest entry before `before` containing `query`, ignoring case
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let query = query.to_lowercase();
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.to_lowercase().contains(&query))
+    }
+
+    fn trim(&mut self) {
+        if self.entries.len() > self.max_entries {
+            let excess = self.entries.len() - self.max_entries;
+            self.entries.drain(..excess);
+        }
+    }
+
+    fn rewrite(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = format!("{}\n", HEADER);
+        for entry in &self.entries {
+            contents.push_str(&escape(entry));
+            contents.push('\n');
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+/// History file in the XDG data directory, optionally one per printer
+fn history_path(config: &HistoryConfig, server: &str) -> Option<PathBuf> {
+    let dir = dirs::data_dir()?.join("moonriver");
+    std::fs::create_dir_all(&dir).ok()?;
+
+    if config.per_printer {
+        Some(dir.join(format!("history-{}", server_key(server))))
+    } else {
+        Some(dir.join("history"))
+    }
+}
+
+/// `host:port` made safe for a file name
+pub fn server_key(server: &str) -> String {
+    server
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Entries of a history file
+///
+/// Files starting with the `#V2` header are escaped; older rustyline files,
+/// like the first `~/.moonriver_history`, hold one entry per line verbatim.
+fn parse(contents: &str) -> Vec<String> {
+    let mut lines = contents.lines().peekable();
+    let escaped = lines.next_if_eq(&HEADER).is_some();
+    lines
+        .filter(|line| !line.is_empty())
+        .map(|line| if escaped { unescape(line) } else { line.to_string() })
+        .collect()
+}
+
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            entry.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some(other) => entry.push(other),
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRICKY: &[&str] = &[
+        r"M117 C:\gcode\part.gcode",
+        r"M117 literal \n stays",
+        "G28 \\\nM105",
+        "#V2",
+        "# leading hash",
+        "trailing backslash \\",
+    ];
+
+    #[test]
+    fn escape_round_trips() {
+        for entry in TRICKY {
+            assert_eq!(unescape(&escape(entry)), *entry);
+            assert!(!escape(entry).contains('\n'), "{:?}", entry);
+        }
+    }
+
+    #[test]
+    fn file_round_trips() {
+        let path = std::env::temp_dir().join(format!("moonriver-history-test-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut history = History {
+            path: Some(path.clone()),
+            max_entries: 100,
+            ..Default::default()
+        };
+        for entry in TRICKY {
+            history.add(entry);
+        }
+        let appended = std::fs::read_to_string(&path).unwrap();
+        history.rewrite().unwrap();
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(appended.starts_with("#V2\n"));
+        assert_eq!(appended, rewritten);
+        assert_eq!(parse(&appended), TRICKY);
+    }
+
+    #[test]
+    fn legacy_files_are_read_verbatim() {
+        let legacy = "G28\nM117 C:\\gcode\\a.gcode\n# note\n\nM105\n";
+        assert_eq!(parse(legacy), ["G28", r"M117 C:\gcode\a.gcode", "# note", "M105"]);
+    }
+
+    #[test]
+    fn only_the_first_line_is_a_header() {
+        assert_eq!(parse("#V2\nG28\n#V2\n"), ["G28", "#V2"]);
+    }
+}
@@ -0,0 +1,139 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A `gcode_macro` parsed from Klipper's `configfile.config`
+#[derive(Debug, Clone, Default)]
+pub struct MacroInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub params: Vec<MacroParam>,
+}
+
+/// A parameter referenced from a macro template as `params.NAME`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroParam {
+    /// Upper-case parameter name, as Klipper passes it
+    pub name: String,
+    /// Value from a `|default(...)` filter, if any
+    pub default: Option<String>,
+}
+
+/// Extract every `gcode_macro` section from `configfile.config`
+/// Keys are upper-case macro names
+pub fn macros_from_config(config: &Value) -> BTreeMap<String, MacroInfo> {
+    let mut macros = BTreeMap::new();
+    let Some(sections) = config.as_object() else {
+        return macros;
+    };
+
+    for (section, options) in sections {
+        let Some(name) = section.strip_prefix("gcode_macro ") else {
+            continue;
+        };
+        let name = name.trim().to_uppercase();
+        let template = options.get("gcode").and_then(|g| g.as_str()).unwrap_or("");
+        let description = options
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_string());
+
+        macros.insert(
+            name.clone(),
+            MacroInfo {
+                name,
+                description,
+                params: parse_macro_params(template),
+            },
+        );
+    }
+
+    macros
+}
+
+/// Find `params.NAME` / `params['NAME']` usages and their `|default(...)` values
+pub fn parse_macro_params(template: &str) -> Vec<MacroParam> {
+    let mut params: Vec<MacroParam> = Vec::new();
+    let mut rest = template;
+
+    while let Some(index) = rest.find("params") {
+        // Skip `rawparams` and identifiers that merely end in "params"
+        let preceded_by_ident = rest[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+        rest = &rest[index + "params".len()..];
+        if preceded_by_ident {
+            continue;
+        }
+
+        let (name, after) = if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after_dot.len());
+            (&after_dot[..end], &after_dot[end..])
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let quoted = after_bracket.trim_start();
+            let Some(quote) = quoted.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+                continue;
+            };
+            let Some(end) = quoted[1..].find(quote) else {
+                continue;
+            };
+            let after = quoted[1 + end + 1..].trim_start();
+            (&quoted[1..1 + end], after.strip_prefix(']').unwrap_or(after))
+        } else {
+            continue;
+        };
+
+        if name.is_empty() {
+            continue;
+        }
+        rest = after;
+
+        let default = parse_default(after);
+        let name = name.to_uppercase();
+        match params.iter_mut().find(|p| p.name == name) {
+            Some(existing) => {
+                if existing.default.is_none() {
+                    existing.default = default;
+                }
+            }
+            None => params.push(MacroParam { name, default }),
+        }
+    }
+
+    params
+}
+
+/// Parse the argument of a `|default(...)` filter directly following a parameter
+fn parse_default(after: &str) -> Option<String> {
+    let filter = after.trim_start().strip_prefix('|')?.trim_start();
+    let args = filter
+        .strip_prefix("default")
+        .or_else(|| filter.strip_prefix('d'))?
+        .trim_start()
+        .strip_prefix('(')?;
+
+    // Find the matching close paren, ignoring nested ones
+    let mut depth = 0;
+    let mut end = None;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                end = Some(i);
+                break;
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    let value = args[..end?].trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    Some(value.to_string())
+}
@@ -1,12 +1,21 @@
+mod alias;
 mod cli;
+mod completion;
 mod config;
+mod format;
+mod history;
+mod macros;
+mod meta;
 mod moonraker;
+mod printer_config;
 mod repl;
+mod transcript;
 mod tui;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Commands};
+use colored::Colorize;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,20 +28,71 @@ async fn main() -> Result<()> {
     let url = format!("ws://{}:{}/websocket", cli.host, cli.port);
     let server_url = format!("{}:{}", cli.host, cli.port);
 
+    // Session transcript for `--log-file` / `[log]`
+    let transcript = |source| {
+        let transcript = transcript::Transcript::new(&config.log, cli.log_file.as_deref(), source);
+        transcript.log(transcript::EntryKind::Info, &format!("Session started: {}", url));
+        transcript
+    };
+
+    // One-shot subcommands (preheat / cooldown)
+    if let Some(subcommand) = &cli.subcommand {
+        let mut client = moonraker::MoonrakerClient::connect(&url).await?;
+        client.set_transcript(transcript("script"));
+
+        let commands = match subcommand {
+            Commands::Preheat { preset } => {
+                let (name, preset) = config.find_preset(preset).ok_or_else(|| {
+                    let available: Vec<&str> = config.presets.keys().map(|k| k.as_str()).collect();
+                    if available.is_empty() {
+                        anyhow!("Unknown preset '{}' (no presets defined in moonriver.toml)", preset)
+                    } else {
+                        anyhow!("Unknown preset '{}'. Available: {}", preset, available.join(", "))
+                    }
+                })?;
+                let heaters = client.available_heaters().await?;
+                let (commands, missing) = preset.to_gcode(&heaters);
+                for role in missing {
+                    println!("{}", format!("Skipping {}: no such heater on this printer", role).yellow());
+                }
+                if commands.is_empty() {
+                    return Err(anyhow!("Preset '{}' does not set any available heaters", name));
+                }
+                println!("{}", format!("Preheating for {}", name).cyan());
+                commands
+            }
+            Commands::Cooldown => {
+                println!("{}", "Cooling down all heaters".cyan());
+                vec![config::COOLDOWN_GCODE.to_string()]
+            }
+        };
+
+        for cmd in commands {
+            client.send_gcode(&cmd).await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            // Drain responses so they reach the transcript
+            while client.try_receive_message().is_some() {}
+        }
+
+        client.disconnect().await?;
+        return Ok(());
+    }
+
     // If we have a command to execute (scripting mode)
     if let Some(command_str) = &cli.command {
         // Connect to Moonraker
         let mut client = moonraker::MoonrakerClient::connect(&url).await?;
-        
-        // Split by comma to support multiple commands
-        let commands: Vec<&str> = command_str.split(',').map(|s| s.trim()).collect();
+        client.set_transcript(transcript("script"));
+
+        // Split by comma to support multiple commands, expanding aliases
+        let aliases = alias::Aliases::new(config.aliases.clone());
+        let commands = aliases.expand_line(command_str)?;
 
         for cmd in commands {
-            if !cmd.is_empty() {
-                client.send_gcode(cmd).await?;
-                // Give some time for response
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            }
+            client.send_gcode(&cmd).await?;
+            // Give some time for response
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            while client.try_receive_message().is_some() {}
         }
 
         client.disconnect().await?;
@@ -41,8 +101,9 @@ async fn main() -> Result<()> {
 
     // If REPL mode is explicitly requested
     if cli.repl {
-        let client = moonraker::MoonrakerClient::connect(&url).await?;
-        repl::run_repl(client).await?;
+        let mut client = moonraker::MoonrakerClient::connect(&url).await?;
+        client.set_transcript(transcript("repl"));
+        repl::run_repl(client, &config).await?;
         return Ok(());
     }
 
@@ -51,7 +112,9 @@ async fn main() -> Result<()> {
     let mut terminal = tui::init()?;
     
     // Create app state
+    let transcript = transcript("tui");
     let mut app = tui::App::new(server_url, config);
+    app.transcript = transcript;
 
     // Connect to Moonraker in background
     match moonraker::MoonrakerClient::connect(&url).await {
@@ -61,6 +124,8 @@ async fn main() -> Result<()> {
             if let Err(e) = app.fetch_power_devices().await {
                 eprintln!("Warning: Failed to fetch power devices: {}", e);
             }
+            app.fetch_temperature_store().await;
+            app.fetch_command_catalog().await;
         }
         Err(e) => {
             eprintln!("Warning: Failed to connect to Moonraker: {}", e);
@@ -0,0 +1,448 @@
+use crate::format::format_duration;
+use crate::moonraker::MoonrakerClient;
+use crate::printer_config;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A local REPL command, run against the Moonraker API instead of sent as G-code
+pub struct MetaCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+/// Every `.`-prefixed command understood by the REPL
+pub const META_COMMANDS: &[MetaCommand] = &[
+    MetaCommand { name: ".status", usage: "", description: "Temperatures, position and print state" },
+    MetaCommand { name: ".files", usage: "", description: "List G-code files on the printer" },
+    MetaCommand { name: ".print", usage: "<file>", description: "Start printing a file" },
+    MetaCommand { name: ".pause", usage: "", description: "Pause the current print" },
+    MetaCommand { name: ".resume", usage: "", description: "Resume a paused print" },
+    MetaCommand { name: ".cancel", usage: "", description: "Cancel the current print" },
+    MetaCommand { name: ".power", usage: "[<device> on|off|toggle]", description: "List or switch power devices" },
+    MetaCommand { name: ".objects", usage: "", description: "List loaded printer objects" },
+    MetaCommand { name: ".query", usage: "<object>...", description: "Show the status of printer objects" },
+    MetaCommand { name: ".history", usage: "[count]", description: "Show recent print jobs" },
+    MetaCommand { name: ".reconnect", usage: "", description: "Reconnect to Moonraker" },
+    MetaCommand { name: ".config", usage: "ls|edit <file>", description: "List or edit Klipper config files" },
+    MetaCommand { name: ".log", usage: "[on|off]", description: "Show or toggle the session transcript" },
+    MetaCommand { name: ".source", usage: "<file>", description: "Run a G-code file or saved script line by line" },
+    MetaCommand { name: ".record", usage: "<name>", description: "Record typed commands into a script" },
+    MetaCommand { name: ".stop", usage: "", description: "Stop recording and save the script" },
+    MetaCommand { name: ".help", usage: "", description: "List local commands" },
+];
+
+/// Subcommands of `.config`
+pub const CONFIG_ACTIONS: &[&str] = &["ls", "edit"];
+
+/// Actions accepted by `.power <device>`
+pub const POWER_ACTIONS: &[&str] = &["on", "off", "toggle"];
+
+/// How many jobs `.history` shows by default
+const DEFAULT_HISTORY_COUNT: u64 = 10;
+
+pub fn is_meta_command(line: &str) -> bool {
+    line.starts_with('.')
+}
+
+/// Look up a meta command by name, ignoring case
+pub fn find(name: &str) -> Option<&'static MetaCommand> {
+    META_COMMANDS.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+}
+
+/// Run a `.`-prefixed line
+pub async fn run(client: &mut MoonrakerClient, line: &str) -> Result<()> {
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or("");
+    let args: Vec<&str> = words.collect();
+
+    let Some(command) = find(name) else {
+        return Err(anyhow!("Unknown command '{}'. Type .help for a list", name));
+    };
+
+    match command.name {
+        ".status" => status(client).await,
+        ".files" => {
+            let files = list_files(client).await?;
+            if files.is_empty() {
+                println!("{}", "No G-code files".yellow());
+            }
+            for file in files {
+                println!("  {}", file.cyan());
+            }
+            Ok(())
+        }
+        ".print" => {
+            let filename = args.join(" ");
+            if filename.is_empty() {
+                return Err(anyhow!("Usage: .print <file>"));
+            }
+            client
+                .request("printer.print.start", json!({ "filename": filename }))
+                .await?;
+            println!("{}", format!("Started printing {}", filename).green());
+            Ok(())
+        }
+        ".pause" | ".resume" | ".cancel" => {
+            let action = &command.name[1..];
+            client
+                .request(&format!("printer.print.{}", action), json!({}))
+                .await?;
+            println!("{}", format!("Print {} requested", action).green());
+            Ok(())
+        }
+        ".power" => power(client, &args).await,
+        ".objects" => {
+            for object in client.list_objects().await? {
+                println!("  {}", object.cyan());
+            }
+            Ok(())
+        }
+        ".query" => {
+            if args.is_empty() {
+                return Err(anyhow!("Usage: .query <object>..."));
+            }
+            // Object names may contain a space (`temperature_sensor mcu_temp`)
+            let objects: serde_json::Map<String, Value> = split_objects(&args)
+                .into_iter()
+                .map(|object| (object, Value::Null))
+                .collect();
+            let status = client.query_objects(Value::Object(objects)).await?;
+            println!("{}", serde_json::to_string_pretty(&status)?.green());
+            Ok(())
+        }
+        ".history" => {
+            let count = match args.first() {
+                Some(count) => count
+                    .parse()
+                    .map_err(|_| anyhow!("Usage: .history [count]"))?,
+                None => DEFAULT_HISTORY_COUNT,
+            };
+            history(client, count).await
+        }
+        ".reconnect" => client.reconnect().await,
+        ".config" => printer_config::run(client, &args).await,
+        ".log" => {
+            let transcript = client.transcript();
+            match args.first().map(|a| a.to_lowercase()).as_deref() {
+                Some("on") => transcript.set_enabled(true),
+                Some("off") => transcript.set_enabled(false),
+                Some(_) => return Err(anyhow!("Usage: .log [on|off]")),
+                None => {}
+            }
+            let state = if transcript.is_enabled() { "on".green() } else { "off".yellow() };
+            println!("Transcript {} ({})", state, transcript.path().display());
+            Ok(())
+        }
+        // Scripts need the REPL's line handling, so it runs them itself
+        ".source" | ".record" | ".stop" => Err(anyhow!("{} is handled by the REPL", command.name)),
+        _ => {
+            print_help();
+            Ok(())
+        }
+    }
+}
+
+/// Print the list of meta commands
+pub fn print_help() {
+    println!("Local commands:");
+    for command in META_COMMANDS {
+        let usage = format!("{} {}", command.name, command.usage);
+        println!("  {:<34} {}", usage.bright_yellow(), command.description.dimmed());
+    }
+}
+
+/// Ask a yes/no question on stdin
+pub async fn confirm(question: String) -> bool {
+    let answer = prompt(format!("{} {}", question, "[y/N]".dimmed())).await;
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask a question on stdin and return the trimmed answer
+pub async fn prompt(question: String) -> String {
+    tokio::task::spawn_blocking(move || {
+        print!("\r\x1b[K{} ", question.yellow());
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        answer.trim().to_string()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// G-code file paths, most recently modified first (`server.files.list`)
+pub async fn list_files(client: &mut MoonrakerClient) -> Result<Vec<String>> {
+    let result = client
+        .request("server.files.list", json!({ "root": "gcodes" }))
+        .await?;
+    let mut files: Vec<(f64, String)> = result
+        .as_array()
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|file| {
+                    let path = file.get("path").or_else(|| file.get("filename"))?.as_str()?;
+                    let modified = file.get("modified").and_then(|m| m.as_f64()).unwrap_or(0.0);
+                    Some((modified, path.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Configured power devices and their status (`machine.device_power.devices`)
+pub async fn list_power_devices(client: &mut MoonrakerClient) -> Result<Vec<(String, String)>> {
+    let result = client
+        .request("machine.device_power.devices", json!({}))
+        .await?;
+    Ok(result
+        .get("devices")
+        .and_then(|d| d.as_array())
+        .map(|devices| {
+            devices
+                .iter()
+                .filter_map(|device| {
+                    let name = device.get("device")?.as_str()?.to_string();
+                    let status = device
+                        .get("status")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    Some((name, status))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Directory holding scripts saved with `.record`
+pub fn scripts_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("moonriver").join("scripts"))
+}
+
+/// Names of saved scripts, without the `.gcode` extension
+pub fn list_scripts() -> Vec<String> {
+    let Some(entries) = scripts_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut scripts: Vec<String> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_suffix(".gcode").map(|n| n.to_string())
+        })
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+/// Find a file for `.source`: a path as given, or a saved script by name
+pub fn resolve_script(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(name);
+    if path.is_file() {
+        return Some(path);
+    }
+    let dir = scripts_dir()?;
+    [dir.join(name), dir.join(format!("{}.gcode", name))]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// Script names become file names, so keep them to a single path component
+pub fn validate_script_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(anyhow!("Invalid script name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Write a recorded script to the scripts directory
+pub fn save_script(name: &str, lines: &[String]) -> Result<PathBuf> {
+    validate_script_name(name)?;
+    let dir = scripts_dir().ok_or_else(|| anyhow!("Could not determine the config directory"))?;
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("{}.gcode", name));
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Treat each argument as an object unless that would split a `<type> <name>` pair
+fn split_objects(args: &[&str]) -> Vec<String> {
+    let mut objects: Vec<String> = Vec::new();
+    for arg in args {
+        match objects.last_mut() {
+            Some(last) if !last.contains(' ') && is_named_object_type(last) => {
+                last.push(' ');
+                last.push_str(arg);
+            }
+            _ => objects.push(arg.to_string()),
+        }
+    }
+    objects
+}
+
+/// Object types that are always followed by a name
+fn is_named_object_type(object: &str) -> bool {
+    matches!(
+        object,
+        "temperature_sensor"
+            | "temperature_fan"
+            | "heater_generic"
+            | "heater_fan"
+            | "controller_fan"
+            | "fan_generic"
+            | "output_pin"
+            | "gcode_macro"
+            | "filament_switch_sensor"
+            | "filament_motion_sensor"
+            | "neopixel"
+            | "led"
+            | "servo"
+            | "manual_stepper"
+    ) || object.starts_with("tmc")
+}
+
+async fn status(client: &mut MoonrakerClient) -> Result<()> {
+    let status = client
+        .query_objects(json!({
+            "print_stats": ["state", "filename", "print_duration"],
+            "virtual_sdcard": ["progress"],
+            "toolhead": ["position", "homed_axes"],
+            "extruder": ["temperature", "target"],
+            "heater_bed": ["temperature", "target"],
+        }))
+        .await?;
+
+    let field = |object: &str, key: &str| status.get(object).and_then(|o| o.get(key));
+
+    let state = field("print_stats", "state").and_then(|s| s.as_str()).unwrap_or("unknown");
+    let filename = field("print_stats", "filename").and_then(|s| s.as_str()).unwrap_or("");
+    if filename.is_empty() {
+        println!("{} {}", "State:".bold(), state.magenta());
+    } else {
+        let progress = field("virtual_sdcard", "progress").and_then(|p| p.as_f64()).unwrap_or(0.0);
+        let duration = field("print_stats", "print_duration").and_then(|d| d.as_f64()).unwrap_or(0.0);
+        println!(
+            "{} {} {} ({:.1}%, {})",
+            "State:".bold(),
+            state.magenta(),
+            filename.cyan(),
+            progress * 100.0,
+            format_duration(duration)
+        );
+    }
+
+    for (object, label) in [("extruder", "Extruder"), ("heater_bed", "Bed")] {
+        if let Some(temp) = field(object, "temperature").and_then(|t| t.as_f64()) {
+            let target = field(object, "target").and_then(|t| t.as_f64()).unwrap_or(0.0);
+            println!(
+                "{} {:.1}°C / {:.0}°C",
+                format!("{}:", label).bold(),
+                temp,
+                target
+            );
+        }
+    }
+
+    if let Some(position) = field("toolhead", "position").and_then(|p| p.as_array()) {
+        let axis = |i: usize| position.get(i).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let homed = field("toolhead", "homed_axes").and_then(|h| h.as_str()).unwrap_or("");
+        println!(
+            "{} X{:.2} Y{:.2} Z{:.2} {}",
+            "Position:".bold(),
+            axis(0),
+            axis(1),
+            axis(2),
+            if homed.is_empty() {
+                "(not homed)".yellow().to_string()
+            } else {
+                format!("(homed: {})", homed).dimmed().to_string()
+            }
+        );
+    }
+
+    Ok(())
+}
+
+async fn power(client: &mut MoonrakerClient, args: &[&str]) -> Result<()> {
+    let [device, action] = args else {
+        if !args.is_empty() {
+            return Err(anyhow!("Usage: .power [<device> on|off|toggle]"));
+        }
+        let devices = list_power_devices(client).await?;
+        if devices.is_empty() {
+            println!("{}", "No power devices configured".yellow());
+        }
+        for (name, status) in devices {
+            let status = match status.as_str() {
+                "on" => status.green(),
+                "off" => status.red(),
+                _ => status.yellow(),
+            };
+            println!("  {:<20} {}", name.cyan(), status);
+        }
+        return Ok(());
+    };
+
+    let action = action.to_lowercase();
+    if !POWER_ACTIONS.contains(&action.as_str()) {
+        return Err(anyhow!("Power action must be one of: {}", POWER_ACTIONS.join(", ")));
+    }
+
+    let result = client
+        .request(
+            "machine.device_power.post_device",
+            json!({ "device": device, "action": action }),
+        )
+        .await?;
+    let status = result.get(*device).and_then(|s| s.as_str()).unwrap_or("unknown");
+    println!("{}", format!("{} is {}", device, status).green());
+    Ok(())
+}
+
+async fn history(client: &mut MoonrakerClient, count: u64) -> Result<()> {
+    let result = client
+        .request("server.history.list", json!({ "limit": count, "order": "desc" }))
+        .await?;
+    let jobs = result.get("jobs").and_then(|j| j.as_array()).cloned().unwrap_or_default();
+    if jobs.is_empty() {
+        println!("{}", "No print history".yellow());
+    }
+
+    for job in jobs {
+        let filename = job.get("filename").and_then(|f| f.as_str()).unwrap_or("unknown");
+        let status = job.get("status").and_then(|s| s.as_str()).unwrap_or("unknown");
+        let duration = job.get("print_duration").and_then(|d| d.as_f64()).unwrap_or(0.0);
+        let started = job
+            .get("start_time")
+            .and_then(|t| t.as_f64())
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0))
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let status = match status {
+            "completed" => status.green(),
+            "cancelled" | "klippy_shutdown" | "error" => status.red(),
+            _ => status.yellow(),
+        };
+        println!(
+            "  {}  {:<10} {:>10}  {}",
+            started.dimmed(),
+            status,
+            format_duration(duration),
+            filename.cyan()
+        );
+    }
+    Ok(())
+}
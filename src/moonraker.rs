@@ -1,7 +1,10 @@
+use crate::transcript::{EntryKind, Transcript};
 use anyhow::{anyhow, Result};
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::protocol::Message;
@@ -11,8 +14,20 @@ pub struct MoonrakerClient {
     write: mpsc::UnboundedSender<Message>,
     read: mpsc::UnboundedReceiver<String>,
     request_id: u64,
+    /// WebSocket URL, kept for `reconnect`
+    url: String,
+    /// Messages received while waiting for a specific response
+    backlog: VecDeque<String>,
+    /// Session transcript; sent G-code and received messages are logged here
+    transcript: Transcript,
+    /// Objects subscribed to so far; Moonraker replaces a connection's
+    /// subscription on every `printer.objects.subscribe`, so it is resent whole
+    subscriptions: serde_json::Map<String, Value>,
 }
 
+/// How long to wait for a JSON-RPC response before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl MoonrakerClient {
     pub async fn connect(url: &str) -> Result<Self> {
         let url = Url::parse(url)?;
@@ -70,6 +85,10 @@ impl MoonrakerClient {
             write: write_tx,
             read: read_rx,
             request_id: 1,
+            url: url.to_string(),
+            backlog: VecDeque::new(),
+            transcript: Transcript::disabled(),
+            subscriptions: serde_json::Map::new(),
         };
 
         // Subscribe to printer status updates
@@ -79,27 +98,26 @@ impl MoonrakerClient {
     }
 
     async fn subscribe_to_updates(&mut self) -> Result<()> {
-        // Subscribe to updates for all objects using wildcard approach
-        // This subscribes to the base objects we know about
-        let request = json!({
-            "jsonrpc": "2.0",
-            "method": "printer.objects.subscribe",
-            "params": {
-                "objects": {
-                    "gcode_move": null,
-                    "toolhead": ["position", "homed_axes"],
-                    "extruder": ["temperature", "target", "power"],
-                    "heater_bed": ["temperature", "target", "power"],
-                    "print_stats": ["state", "filename", "total_duration", "print_duration", "filament_used"],
-                    "fan": ["speed", "rpm"],
-                    "mcu": ["temperature"],
-                    "temperature_host": ["temperature"],
-                }
-            },
-            "id": self.next_id()
+        // Subscribe to the base objects we know about; heaters and sensors
+        // are added once they are discovered
+        let objects = json!({
+            "gcode_move": null,
+            "toolhead": ["position", "homed_axes", "axis_minimum", "axis_maximum"],
+            "heaters": ["available_heaters"],
+            "extruder": ["temperature", "target", "power"],
+            "heater_bed": ["temperature", "target", "power"],
+            "print_stats": ["state", "filename", "total_duration", "print_duration", "filament_used", "info"],
+            "virtual_sdcard": ["progress"],
+            "display_status": ["progress", "message"],
+            "bed_mesh": ["profile_name", "mesh_min", "mesh_max", "probed_matrix", "mesh_matrix", "profiles"],
+            "fan": ["speed", "rpm"],
+            "mcu": ["temperature"],
+            "temperature_host": ["temperature"],
         });
-
-        self.send_raw(&request.to_string()).await?;
+        if let Value::Object(objects) = objects {
+            self.subscriptions = objects;
+        }
+        self.send_subscription().await?;
         
         // Query for ALL available objects to discover temperature sensors and fans
         let list_request = json!({
@@ -121,33 +139,49 @@ impl MoonrakerClient {
 
     /// Subscribe to additional objects (called after discovering available objects)
     pub async fn subscribe_to_additional_objects(&mut self, objects: Vec<String>) -> Result<()> {
-        let mut subscribe_objects = serde_json::Map::new();
+        let mut added = false;
         
         for obj in objects {
-            // Subscribe to temperature sensors and fans
-            if obj.starts_with("temperature_sensor ") || obj.starts_with("temperature_fan ") {
-                subscribe_objects.insert(obj, json!(["temperature"]));
+            // Subscribe to heaters, temperature sensors and fans
+            let fields = if obj == "heater_bed"
+                || obj.strip_prefix("extruder").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+                || obj.starts_with("heater_generic ")
+            {
+                json!(["temperature", "target", "power"])
+            } else if obj.starts_with("temperature_sensor ") || obj.starts_with("temperature_fan ") {
+                json!(["temperature"])
             } else if obj.starts_with("heater_fan ") || obj.starts_with("controller_fan ") {
-                subscribe_objects.insert(obj, json!(["speed", "rpm"]));
+                json!(["speed", "rpm"])
+            } else {
+                continue;
+            };
+            if !self.subscriptions.contains_key(&obj) {
+                self.subscriptions.insert(obj, fields);
+                added = true;
             }
         }
         
-        if !subscribe_objects.is_empty() {
-            let request = json!({
-                "jsonrpc": "2.0",
-                "method": "printer.objects.subscribe",
-                "params": {
-                    "objects": subscribe_objects
-                },
-                "id": self.next_id()
-            });
-            
-            self.send_raw(&request.to_string()).await?;
+        if added {
+            self.send_subscription().await?;
         }
         
         Ok(())
     }
 
+    /// Send every subscribed object; the response carries their current status
+    async fn send_subscription(&mut self) -> Result<()> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "printer.objects.subscribe",
+            "params": {
+                "objects": self.subscriptions
+            },
+            "id": self.next_id()
+        });
+
+        self.send_raw(&request.to_string()).await
+    }
+
     async fn send_raw(&self, message: &str) -> Result<()> {
         self.write
             .send(Message::Text(message.to_string().into()))
@@ -155,8 +189,11 @@ impl MoonrakerClient {
         Ok(())
     }
 
-    pub async fn send_gcode(&mut self, gcode: &str) -> Result<()> {
+    /// Send a G-code script, returning the request id
+    /// Moonraker answers on that id once Klipper has finished running the script
+    pub async fn send_gcode(&mut self, gcode: &str) -> Result<u64> {
         let gcode = gcode.trim();
+        self.transcript.log(EntryKind::Command, gcode);
 
         // Check for emergency stop
         if gcode.to_uppercase() == "M112" {
@@ -164,59 +201,188 @@ impl MoonrakerClient {
             return self.emergency_stop().await;
         }
 
+        let id = self.next_id();
         let request = json!({
             "jsonrpc": "2.0",
             "method": "printer.gcode.script",
             "params": {
                 "script": gcode
             },
-            "id": self.next_id()
+            "id": id
         });
 
         self.send_raw(&request.to_string()).await?;
-        Ok(())
+        Ok(id)
     }
 
-    async fn emergency_stop(&mut self) -> Result<()> {
+    async fn emergency_stop(&mut self) -> Result<u64> {
+        let id = self.next_id();
         let request = json!({
             "jsonrpc": "2.0",
             "method": "printer.emergency_stop",
             "params": {},
-            "id": self.next_id()
+            "id": id
         });
 
         self.send_raw(&request.to_string()).await?;
-        Ok(())
+        Ok(id)
     }
 
-    pub async fn get_macros(&mut self) -> Result<Vec<String>> {
+    /// Send a JSON-RPC request and wait for its result
+    /// Other messages received in the meantime are kept for `try_receive_message`
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id();
         let request = json!({
             "jsonrpc": "2.0",
-            "method": "printer.gcode.help",
-            "params": {},
-            "id": self.next_id()
+            "method": method,
+            "params": params,
+            "id": id
         });
 
         self.send_raw(&request.to_string()).await?;
 
-        // Wait for response
-        if let Some(response) = self.read.recv().await
-            && let Ok(value) = serde_json::from_str::<Value>(&response)
-            && let Some(result) = value.get("result") {
-                let mut macros = Vec::new();
-                if let Some(obj) = result.as_object() {
-                    for key in obj.keys() {
-                        macros.push(key.clone());
-                    }
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, async {
+            while let Some(message) = self.read.recv().await {
+                if let Ok(value) = serde_json::from_str::<Value>(&message)
+                    && value.get("id").and_then(|i| i.as_u64()) == Some(id)
+                {
+                    return Ok(value);
                 }
-                return Ok(macros);
+                self.backlog.push_back(message);
             }
+            Err(anyhow!("Connection closed while waiting for {}", method))
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for {}", method))??;
+
+        if let Some(error) = response.get("error") {
+            let message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error");
+            return Err(anyhow!("{}", message));
+        }
 
-        Ok(Vec::new())
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_macros(&mut self) -> Result<Vec<String>> {
+        Ok(self.get_gcode_help().await?.into_keys().collect())
+    }
+
+    /// Fetch every registered command with its description (`printer.gcode.help`)
+    pub async fn get_gcode_help(&mut self) -> Result<BTreeMap<String, String>> {
+        let result = self.request("printer.gcode.help", json!({})).await?;
+        Ok(result
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .map(|(name, description)| {
+                        (name.clone(), description.as_str().unwrap_or("").to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// List every loaded printer object (`printer.objects.list`)
+    pub async fn list_objects(&mut self) -> Result<Vec<String>> {
+        let result = self.request("printer.objects.list", json!({})).await?;
+        Ok(result
+            .get("objects")
+            .and_then(|o| o.as_array())
+            .map(|objects| {
+                objects
+                    .iter()
+                    .filter_map(|o| o.as_str())
+                    .map(|o| o.to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Heater objects Klipper reports in `heaters.available_heaters`
+    pub async fn available_heaters(&mut self) -> Result<Vec<String>> {
+        let status = self
+            .query_objects(json!({ "heaters": ["available_heaters"] }))
+            .await?;
+        Ok(status
+            .pointer("/heaters/available_heaters")
+            .and_then(|h| h.as_array())
+            .map(|heaters| {
+                heaters
+                    .iter()
+                    .filter_map(|h| h.as_str())
+                    .map(|h| h.to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Query printer objects (`printer.objects.query`), returning the `status` map
+    pub async fn query_objects(&mut self, objects: Value) -> Result<Value> {
+        let result = self
+            .request("printer.objects.query", json!({ "objects": objects }))
+            .await?;
+        Ok(result.get("status").cloned().unwrap_or(Value::Null))
     }
 
     pub fn try_receive_message(&mut self) -> Option<String> {
-        self.read.try_recv().ok()
+        let message = self
+            .backlog
+            .pop_front()
+            .or_else(|| self.read.try_recv().ok())?;
+        self.transcript.log_message(&message);
+        Some(message)
+    }
+
+    /// Wait for the next message from Moonraker
+    /// Returns None once the connection has closed
+    pub async fn receive_message(&mut self) -> Option<String> {
+        let message = match self.backlog.pop_front() {
+            Some(message) => message,
+            None => self.read.recv().await?,
+        };
+        self.transcript.log_message(&message);
+        Some(message)
+    }
+
+    /// Close the connection and open a fresh one to the same URL
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let _ = self.write.send(Message::Close(None));
+        let transcript = self.transcript.clone();
+        *self = Self::connect(&self.url).await?;
+        self.transcript = transcript;
+        self.transcript.log(EntryKind::Info, "Reconnected to Moonraker");
+        Ok(())
+    }
+
+    /// `host:port` of the Moonraker server
+    pub fn server(&self) -> String {
+        match Url::parse(&self.url) {
+            Ok(url) => format!(
+                "{}:{}",
+                url.host_str().unwrap_or(""),
+                url.port_or_known_default().unwrap_or(0)
+            ),
+            Err(_) => self.url.clone(),
+        }
+    }
+
+    /// Base URL of Moonraker's HTTP API, e.g. `http://host:port`
+    pub fn http_url(&self) -> String {
+        let scheme = if self.url.starts_with("wss://") { "https" } else { "http" };
+        format!("{}://{}", scheme, self.server())
+    }
+
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Log sent G-code and received messages to `transcript`
+    pub fn set_transcript(&mut self, transcript: Transcript) {
+        self.transcript = transcript;
     }
 
     pub async fn disconnect(self) -> Result<()> {
@@ -228,47 +394,97 @@ impl MoonrakerClient {
 }
 
 pub fn format_response(response: &str) {
-    if let Ok(value) = serde_json::from_str::<Value>(response) {
-        // Handle JSON-RPC response
-        if let Some(result) = value.get("result") {
-            if result.is_string() {
-                let result_str = result.as_str().unwrap();
-                // Skip standalone "ok" responses
-                if result_str.trim() != "ok" && !result_str.trim().is_empty() {
-                    println!("{}", result_str.green());
-                }
-            } else {
-                println!("{}", serde_json::to_string_pretty(&result).unwrap().green());
+    if let Some(text) = render_response(response) {
+        println!("{}", text);
+    }
+}
+
+/// What a Moonraker message means to someone reading the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    /// Text output of a G-code command
+    Response,
+    /// Structured JSON result of a request
+    Data,
+    /// JSON-RPC error or Klipper `!!` message
+    Error,
+    /// Print state change or Klipper ready/shutdown
+    State,
+}
+
+/// Render a Moonraker message as colored text for display
+/// Returns None for messages that should not be shown
+pub fn render_response(response: &str) -> Option<String> {
+    classify_response(response).map(|(_, text)| text.to_string())
+}
+
+/// Classify a Moonraker message and render it as colored text
+/// The plain text is available through the `ColoredString`'s `Deref`
+pub fn classify_response(response: &str) -> Option<(ResponseKind, ColoredString)> {
+    let Ok(value) = serde_json::from_str::<Value>(response) else {
+        // Plain text response
+        return Some((ResponseKind::Response, response.green()));
+    };
+
+    // Handle JSON-RPC response
+    if let Some(result) = value.get("result") {
+        if let Some(result_str) = result.as_str() {
+            // Skip standalone "ok" responses
+            if result_str.trim() != "ok" && !result_str.trim().is_empty() {
+                return Some((ResponseKind::Response, result_str.green()));
             }
-        } else if let Some(error) = value.get("error") {
-            println!("{}", format!("Error: {}", error).red().bold());
-        } else if let Some(method) = value.get("method") {
-            // Handle notification
-            if method == "notify_gcode_response" {
-                if let Some(params) = value.get("params").and_then(|p| p.get(0)) {
-                    let msg = params.as_str().unwrap_or("");
-
-                    // Skip standalone "ok" messages (with or without whitespace)
-                    let trimmed = msg.trim();
-                    if trimmed == "ok" || trimmed.is_empty() {
-                        return;
-                    }
+            return None;
+        }
+        return Some((ResponseKind::Data, serde_json::to_string_pretty(&result).unwrap().green()));
+    }
 
-                    // Color code based on content
-                    if msg.contains("error") || msg.contains("!!") {
-                        println!("{}", msg.red().bold());
-                    } else if msg.contains("warning") || msg.contains("//") {
-                        println!("{}", msg.yellow());
-                    } else {
-                        println!("{}", msg.cyan());
-                    }
-                }
-            } else if method == "notify_status_update" {
-                // Silently handle status updates (we could display these if needed)
+    if let Some(error) = value.get("error") {
+        return Some((ResponseKind::Error, format!("Error: {}", error).red().bold()));
+    }
+
+    // Handle notifications
+    match value.get("method").and_then(|m| m.as_str())? {
+        "notify_gcode_response" => {
+            let msg = value.get("params").and_then(|p| p.get(0))?.as_str().unwrap_or("");
+
+            // Skip standalone "ok" messages (with or without whitespace)
+            let trimmed = msg.trim();
+            if trimmed == "ok" || trimmed.is_empty() {
+                return None;
+            }
+
+            // Color code based on content
+            if msg.contains("error") || msg.contains("!!") {
+                Some((ResponseKind::Error, msg.red().bold()))
+            } else if msg.contains("warning") || msg.contains("//") {
+                Some((ResponseKind::Response, msg.yellow()))
+            } else {
+                Some((ResponseKind::Response, msg.cyan()))
             }
         }
-    } else {
-        // Plain text response
-        println!("{}", response.green());
+        "notify_status_update" => {
+            // Only surface print state transitions; temperatures etc. stay silent
+            let state = value
+                .get("params")
+                .and_then(|p| p.get(0))
+                .and_then(|s| s.get("print_stats"))
+                .and_then(|p| p.get("state"))
+                .and_then(|s| s.as_str())?;
+            let text = format!("// Print state: {}", state);
+            Some((
+                ResponseKind::State,
+                match state {
+                    "error" => text.red().bold(),
+                    "complete" => text.green().bold(),
+                    _ => text.magenta(),
+                },
+            ))
+        }
+        "notify_klippy_ready" => Some((ResponseKind::State, "// Klipper ready".green().bold())),
+        "notify_klippy_shutdown" => Some((ResponseKind::State, "!! Klipper shutdown".red().bold())),
+        "notify_klippy_disconnected" => {
+            Some((ResponseKind::State, "// Klipper disconnected".yellow().bold()))
+        }
+        _ => None,
     }
 }
@@ -0,0 +1,329 @@
+use crate::meta::{confirm, prompt};
+use crate::moonraker::MoonrakerClient;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde_json::json;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Lines of unchanged context shown around each change in a diff
+const DIFF_CONTEXT: usize = 3;
+
+/// Above this many line pairs (4 bytes each) only a summary of the change is shown
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// A file in Klipper's config directory
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    pub path: String,
+    pub modified: f64,
+    pub size: u64,
+}
+
+/// List the files in the `config` root (`server.files.list`)
+pub async fn list_config_files(client: &mut MoonrakerClient) -> Result<Vec<ConfigFile>> {
+    let result = client
+        .request("server.files.list", json!({ "root": "config" }))
+        .await?;
+    let mut files: Vec<ConfigFile> = result
+        .as_array()
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|file| {
+                    Some(ConfigFile {
+                        path: file.get("path").or_else(|| file.get("filename"))?.as_str()?.to_string(),
+                        modified: file.get("modified").and_then(|m| m.as_f64()).unwrap_or(0.0),
+                        size: file.get("size").and_then(|s| s.as_u64()).unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Run `.config ls` or `.config edit <file>`
+pub async fn run(client: &mut MoonrakerClient, args: &[&str]) -> Result<()> {
+    match args {
+        [] | ["ls"] => {
+            for file in list_config_files(client).await? {
+                let modified = chrono::DateTime::from_timestamp(file.modified as i64, 0)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "  {}  {:>8}  {}",
+                    modified.dimmed(),
+                    format_size(file.size),
+                    file.path.cyan()
+                );
+            }
+            Ok(())
+        }
+        ["edit", path] => edit(client, path).await,
+        _ => Err(anyhow!("Usage: .config ls | .config edit <file>")),
+    }
+}
+
+/// Download a config file, open it in $EDITOR and upload the result
+async fn edit(client: &mut MoonrakerClient, path: &str) -> Result<()> {
+    let before = find_file(client, path).await?;
+    let http = reqwest::Client::new();
+    let original = http
+        .get(file_url(client, path)?)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    // A private, uniquely named copy (removed on drop); the file name is kept
+    // as the suffix so editors still pick the right syntax
+    let mut local = tempfile::Builder::new()
+        .prefix("moonriver-")
+        .suffix(&format!("-{}", path.replace('/', "_")))
+        .tempfile()?;
+    local.write_all(original.as_bytes())?;
+    local.flush()?;
+    open_editor(local.path()).await?;
+    let edited = std::fs::read_to_string(local.path())?;
+
+    if edited == original {
+        println!("{}", format!("No changes to {}", path).yellow());
+        return Ok(());
+    }
+
+    let old_lines: Vec<&str> = original.lines().collect();
+    let new_lines: Vec<&str> = edited.lines().collect();
+    match diff_lines(&old_lines, &new_lines) {
+        Some(lines) => print_diff(&lines),
+        None => println!(
+            "{}",
+            format!(
+                "{} changed ({} -> {} lines); too many changes to show a diff",
+                path,
+                old_lines.len(),
+                new_lines.len()
+            )
+            .yellow()
+        ),
+    }
+
+    if !confirm(format!("Upload changes to {}?", path)).await {
+        let local = local.into_temp_path().keep()?;
+        println!(
+            "{}",
+            format!("Changes not uploaded; your copy is at {}", local.display()).yellow()
+        );
+        return Ok(());
+    }
+
+    // Refuse to overwrite changes made on the printer while we were editing
+    let current = find_file(client, path).await?;
+    if current.modified != before.modified {
+        let local = local.into_temp_path().keep()?;
+        return Err(anyhow!(
+            "{} was modified on the printer while you were editing; not uploading. Your copy is at {}",
+            path,
+            local.display()
+        ));
+    }
+
+    upload(&http, client, path, edited).await?;
+    println!("{}", format!("Uploaded {}", path).green());
+
+    let answer = prompt(
+        "Apply now? [f]irmware restart, [r]estart, or [N]o".to_string(),
+    )
+    .await;
+    let command = match answer.to_lowercase().as_str() {
+        "f" | "firmware" | "firmware_restart" => "FIRMWARE_RESTART",
+        "r" | "restart" => "RESTART",
+        _ => return Ok(()),
+    };
+    client.send_gcode(command).await?;
+    println!("{}", format!("Sent {}", command).cyan());
+    Ok(())
+}
+
+async fn find_file(client: &mut MoonrakerClient, path: &str) -> Result<ConfigFile> {
+    list_config_files(client)
+        .await?
+        .into_iter()
+        .find(|file| file.path == path)
+        .ok_or_else(|| anyhow!("No config file named '{}'. Try .config ls", path))
+}
+
+/// `/server/files/config/<path>` with each path segment escaped
+fn file_url(client: &MoonrakerClient, path: &str) -> Result<url::Url> {
+    let mut url = url::Url::parse(&client.http_url())?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("Invalid Moonraker URL"))?
+        .extend(["server", "files", "config"])
+        .extend(path.split('/'));
+    Ok(url)
+}
+
+/// Upload through `/server/files/upload`
+///
+/// The multipart body is built by hand since reqwest's `multipart` feature isn't enabled.
+async fn upload(
+    http: &reqwest::Client,
+    client: &MoonrakerClient,
+    path: &str,
+    contents: String,
+) -> Result<()> {
+    let boundary = format!("moonriver-{}", chrono::Local::now().timestamp_nanos_opt().unwrap_or(0));
+    let body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"root\"\r\n\r\nconfig\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{path}\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n{contents}\r\n--{b}--\r\n",
+        b = boundary,
+        path = path.replace('"', "%22"),
+        contents = contents,
+    );
+
+    http.post(format!("{}/server/files/upload", client.http_url()))
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Open `$VISUAL` / `$EDITOR` (falling back to `vi`) and wait for it to exit
+async fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi").to_string();
+    let args: Vec<String> = words.map(|w| w.to_string()).collect();
+    let path: PathBuf = path.to_path_buf();
+
+    let status = tokio::task::spawn_blocking(move || {
+        std::process::Command::new(&program).args(&args).arg(&path).status()
+    })
+    .await?
+    .map_err(|e| anyhow!("Could not start editor '{}': {}", editor, e))?;
+
+    if !status.success() {
+        return Err(anyhow!("Editor exited with {}", status));
+    }
+    Ok(())
+}
+
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff based on the longest common subsequence
+///
+/// Returns `None` when the changed region is too large to diff cheaply.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Option<Vec<DiffLine<'a>>> {
+    // Only run the quadratic part on what lies between the common prefix and suffix
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    if (old_mid.len() + 1).saturating_mul(new_mid.len() + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    let mut lines: Vec<DiffLine> = old[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+
+    // lcs[i][j] = length of the LCS of old_mid[i..] and new_mid[j..]
+    let width = new_mid.len() + 1;
+    let mut lcs = vec![0u32; (old_mid.len() + 1) * width];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() && j < new_mid.len() {
+        if old_mid[i] == new_mid[j] {
+            lines.push(DiffLine::Same(old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            lines.push(DiffLine::Removed(old_mid[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new_mid[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old_mid[i..].iter().map(|l| DiffLine::Removed(l)));
+    lines.extend(new_mid[j..].iter().map(|l| DiffLine::Added(l)));
+    lines.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Same(l)));
+    Some(lines)
+}
+
+/// Print changed lines with a little context, like `diff -u`
+fn print_diff(lines: &[DiffLine]) {
+    let mut visible = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if !matches!(line, DiffLine::Same(_)) {
+            let end = (i + DIFF_CONTEXT).min(lines.len() - 1);
+            for flag in &mut visible[i.saturating_sub(DIFF_CONTEXT)..=end] {
+                *flag = true;
+            }
+        }
+    }
+
+    let (mut old_line, mut new_line) = (1, 1);
+    let mut in_hunk = false;
+    for (line, visible) in lines.iter().zip(visible) {
+        if visible && !in_hunk {
+            println!("{}", format!("@@ -{} +{} @@", old_line, new_line).cyan());
+        }
+        in_hunk = visible;
+
+        match line {
+            DiffLine::Same(text) => {
+                if visible {
+                    println!(" {}", text);
+                }
+                old_line += 1;
+                new_line += 1;
+            }
+            DiffLine::Removed(text) => {
+                println!("{}", format!("-{}", text).red());
+                old_line += 1;
+            }
+            DiffLine::Added(text) => {
+                println!("{}", format!("+{}", text).green());
+                new_line += 1;
+            }
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
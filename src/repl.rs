@@ -1,226 +1,69 @@
-use crate::moonraker::{format_response, MoonrakerClient};
-use anyhow::Result;
+use crate::alias::Aliases;
+use crate::completion::{CommandCatalog, CommandKind};
+use crate::config::Config;
+use crate::history::History;
+use crate::meta::{self, META_COMMANDS};
+use crate::moonraker::{format_response, render_response, MoonrakerClient};
+use crate::transcript::EntryKind;
+use anyhow::{anyhow, Result};
 use colored::Colorize;
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::{Highlighter, CmdKind};
-use rustyline::hint::Hinter;
-use rustyline::validate::Validator;
-use rustyline::{Context, Editor, Helper};
+use rustyline::hint::{Hint, Hinter};
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::history::DefaultHistory;
+use rustyline::{Cmd, Context, Editor, ExternalPrinter, Helper, KeyCode, KeyEvent, Modifiers};
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use tokio::time::{Duration, Instant};
 
 struct MoonriverHelper {
-    macros: HashSet<String>,
-    gcode_commands: HashSet<String>,
-    klipper_commands: HashSet<String>,
+    catalog: CommandCatalog,
 }
 
 impl MoonriverHelper {
     fn new() -> Self {
-        let mut gcode_commands = HashSet::new();
-
-        // Standard G-code commands from Klipper documentation
-        // Basic movement and positioning
-        let commands = vec![
-            // G-codes (movement and basic commands)
-            "G0", "G1", "G2", "G3", "G4", "G10", "G11", "G17", "G18", "G19",
-            "G28", "G90", "G91", "G92",
-            
-            // M-codes (standard)
-            "M18", "M84", "M82", "M83", "M104", "M105", "M106", "M107", "M109",
-            "M112", "M114", "M115", "M117", "M118", "M119", "M140", "M190",
-            "M204", "M220", "M221", "M400",
-            
-            // Additional M-codes from broader RepRap compatibility
-            "M0", "M1", "M17", "M20", "M21", "M22", "M23", "M24", "M25", "M26", "M27",
-            "M73", "M110", "M111", "M113", "M120", "M121", "M141", "M143", "M191",
-            "M205", "M206", "M207", "M208", "M209", "M226", "M280", "M300", "M301",
-            "M302", "M303", "M304", "M305", "M401", "M402", "M403", "M404", "M405",
-            "M406", "M407", "M408", "M409", "M410", "M412", "M413", "M415", "M420",
-            "M421", "M422", "M425", "M428", "M500", "M501", "M502", "M503", "M504",
-            "M505", "M510", "M511", "M512", "M524", "M540", "M550", "M551", "M552",
-            "M553", "M554", "M555", "M556", "M557", "M558", "M559", "M560", "M561",
-            "M562", "M563", "M564", "M565", "M566", "M567", "M568", "M569", "M570",
-            "M571", "M572", "M573", "M574", "M575", "M576", "M577", "M578", "M579",
-            "M580", "M581", "M582", "M583", "M584", "M585", "M586", "M587", "M588",
-            "M589", "M591", "M592", "M593", "M594", "M595", "M596", "M597", "M598",
-            "M599", "M600", "M601", "M602", "M603", "M605", "M665", "M666", "M667",
-            "M668", "M669", "M670", "M671", "M672", "M673", "M674", "M675", "M700",
-            "M701", "M702", "M703", "M704", "M705", "M706", "M707", "M708", "M709",
-            "M710", "M850", "M851", "M852", "M860", "M861", "M862", "M863", "M864",
-            "M865", "M866", "M867", "M868", "M869", "M871", "M876", "M900", "M905",
-            "M906", "M907", "M908", "M909", "M910", "M911", "M912", "M913", "M914",
-            "M915", "M916", "M917", "M918", "M919", "M928", "M951", "M997", "M998",
-            "M999",
-        ];
-
-        for cmd in commands {
-            gcode_commands.insert(cmd.to_string());
-        }
-
-        // Klipper extended commands from documentation
-        let mut klipper_commands = HashSet::new();
-        let klipper_cmds = vec![
-            // Core commands
-            "HELP", "STATUS", "RESTART", "FIRMWARE_RESTART",
-            
-            // Movement and positioning
-            "GET_POSITION", "SET_GCODE_OFFSET", "SAVE_GCODE_STATE", "RESTORE_GCODE_STATE",
-            "SET_VELOCITY_LIMIT", "SET_KINEMATIC_POSITION",
-            
-            // Temperature
-            "SET_HEATER_TEMPERATURE", "TURN_OFF_HEATERS", "TEMPERATURE_WAIT",
-            "PID_CALIBRATE", "SET_TEMPERATURE_FAN_TARGET",
-            
-            // Bed mesh and calibration
-            "BED_MESH_CALIBRATE", "BED_MESH_PROFILE", "BED_MESH_OUTPUT", "BED_MESH_MAP",
-            "BED_MESH_CLEAR", "BED_MESH_OFFSET",
-            "BED_SCREWS_ADJUST", "BED_TILT_CALIBRATE",
-            "SCREWS_TILT_CALCULATE",
-            
-            // Probing
-            "PROBE", "QUERY_PROBE", "PROBE_ACCURACY", "PROBE_CALIBRATE",
-            "Z_OFFSET_APPLY_PROBE", "Z_OFFSET_APPLY_ENDSTOP",
-            "MANUAL_PROBE", "Z_ENDSTOP_CALIBRATE",
-            "ACTIVATE_PROBE", "DEACTIVATE_PROBE",
-            
-            // Delta calibration
-            "DELTA_CALIBRATE", "DELTA_ANALYZE",
-            
-            // Extruder
-            "ACTIVATE_EXTRUDER", "SET_PRESSURE_ADVANCE", "SET_EXTRUDER_ROTATION_DISTANCE",
-            "SYNC_EXTRUDER_MOTION",
-            
-            // Firmware retraction
-            "SET_RETRACTION", "GET_RETRACTION",
-            
-            // Stepper control
-            "SET_STEPPER_ENABLE", "STEPPER_BUZZ", "FORCE_MOVE",
-            
-            // TMC drivers
-            "DUMP_TMC", "INIT_TMC", "SET_TMC_CURRENT", "SET_TMC_FIELD",
-            
-            // Input shaper and resonance
-            "SET_INPUT_SHAPER", "MEASURE_AXES_NOISE", "TEST_RESONANCES", "SHAPER_CALIBRATE",
-            
-            // Accelerometer
-            "ACCELEROMETER_MEASURE", "ACCELEROMETER_QUERY",
-            "ACCELEROMETER_DEBUG_READ", "ACCELEROMETER_DEBUG_WRITE",
-            
-            // Endstops and limits
-            "QUERY_ENDSTOPS", "QUERY_ADC",
-            
-            // Print control
-            "PAUSE", "RESUME", "CANCEL_PRINT", "CLEAR_PAUSE",
-            "SDCARD_PRINT_FILE", "SDCARD_RESET_FILE",
-            
-            // Configuration
-            "SAVE_CONFIG", "SET_GCODE_VARIABLE", "SAVE_VARIABLE",
-            "SET_IDLE_TIMEOUT",
-            
-            // Display and output
-            "SET_DISPLAY_TEXT", "SET_DISPLAY_GROUP", "RESPOND",
-            
-            // Fans and pins
-            "SET_FAN_SPEED", "SET_PIN", "SET_LED", "SET_LED_TEMPLATE",
-            
-            // Gantry leveling
-            "QUAD_GANTRY_LEVEL", "Z_TILT_ADJUST",
-            
-            // Skew correction
-            "SET_SKEW", "GET_CURRENT_SKEW", "CALC_MEASURED_SKEW", "SKEW_PROFILE",
-            
-            // Manual stepper
-            "MANUAL_STEPPER",
-            
-            // Tuning tower
-            "TUNING_TOWER",
-            
-            // Filament sensors
-            "QUERY_FILAMENT_SENSOR", "SET_FILAMENT_SENSOR",
-            "QUERY_FILAMENT_WIDTH", "RESET_FILAMENT_WIDTH_SENSOR",
-            "ENABLE_FILAMENT_WIDTH_SENSOR", "DISABLE_FILAMENT_WIDTH_SENSOR",
-            "QUERY_RAW_FILAMENT_WIDTH", "ENABLE_FILAMENT_WIDTH_LOG",
-            "DISABLE_FILAMENT_WIDTH_LOG",
-            
-            // Exclude object
-            "EXCLUDE_OBJECT", "EXCLUDE_OBJECT_DEFINE", "EXCLUDE_OBJECT_START",
-            "EXCLUDE_OBJECT_END",
-            
-            // Dual carriage
-            "SET_DUAL_CARRIAGE", "SAVE_DUAL_CARRIAGE_STATE", "RESTORE_DUAL_CARRIAGE_STATE",
-            
-            // Palette 2
-            "PALETTE_CONNECT", "PALETTE_DISCONNECT", "PALETTE_CLEAR",
-            "PALETTE_CUT", "PALETTE_SMART_LOAD",
-            
-            // Smart effector
-            "SET_SMART_EFFECTOR", "RESET_SMART_EFFECTOR",
-            
-            // Servo
-            "SET_SERVO",
-            
-            // BLTouch
-            "BLTOUCH_DEBUG", "BLTOUCH_STORE",
-            
-            // Delayed gcode
-            "UPDATE_DELAYED_GCODE",
-            
-            // Angle calibration
-            "ANGLE_CALIBRATE", "ANGLE_CHIP_CALIBRATE", "ANGLE_DEBUG_READ", "ANGLE_DEBUG_WRITE",
-            
-            // Endstop phase
-            "ENDSTOP_PHASE_CALIBRATE",
-            
-            // Axis twist compensation
-            "AXIS_TWIST_COMPENSATION_CALIBRATE",
-            
-            // Generic cartesian
-            "SET_STEPPER_CARRIAGES",
-            
-            // Load cell
-            "LOAD_CELL_DIAGNOSTIC", "LOAD_CELL_CALIBRATE", "LOAD_CELL_TARE",
-            "LOAD_CELL_READ", "LOAD_CELL_TEST_TAP",
-            
-            // Probe eddy current
-            "PROBE_EDDY_CURRENT_CALIBRATE", "LDC_CALIBRATE_DRIVE_CURRENT",
-            
-            // PWM cycle time
-            "SET_DIGIPOT",
-            
-            // Print stats
-            "SET_PRINT_STATS_INFO",
-            
-            // SDCard loop
-            "SDCARD_LOOP_BEGIN", "SDCARD_LOOP_END", "SDCARD_LOOP_DESIST",
-            
-            // Temperature probe
-            "TEMPERATURE_PROBE_CALIBRATE", "TEMPERATURE_PROBE_NEXT",
-            "TEMPERATURE_PROBE_COMPLETE", "TEMPERATURE_PROBE_ENABLE",
-            
-            // Z thermal adjust
-            "SET_Z_THERMAL_ADJUST",
-        ];
-
-        for cmd in klipper_cmds {
-            klipper_commands.insert(cmd.to_string());
-        }
-
         Self {
-            macros: HashSet::new(),
-            gcode_commands,
-            klipper_commands,
+            catalog: CommandCatalog::new(),
         }
     }
 
-    fn set_macros(&mut self, macros: Vec<String>) {
-        self.macros = macros.into_iter().collect();
-    }
+    /// Print the full description of a command, plus parameters for macros
+    fn print_help(&self, command: &str) {
+        let command = command.trim().to_uppercase();
+        let info = self.catalog.macro_info.get(&command);
+        let description = self
+            .catalog
+            .descriptions
+            .get(&command)
+            .filter(|d| !d.is_empty())
+            .or_else(|| info.and_then(|i| i.description.as_ref()));
+
+        match description {
+            Some(description) => println!("{}: {}", command.bright_blue().bold(), description),
+            None if info.is_some() => println!("{}", command.bright_cyan().bold()),
+            None => {
+                println!("{}", format!("No help available for '{}'", command).yellow());
+                return;
+            }
+        }
 
-    fn add_klipper_commands(&mut self, commands: Vec<String>) {
-        for cmd in commands {
-            self.klipper_commands.insert(cmd);
+        if let Some(info) = info {
+            if info.params.is_empty() {
+                println!("  {}", "(no parameters)".dimmed());
+            } else {
+                println!("Parameters of {}:", info.name);
+                for param in &info.params {
+                    match &param.default {
+                        Some(default) => {
+                            println!("  {}={}", param.name.cyan(), format!("(default: {})", default).dimmed())
+                        }
+                        None => println!("  {}", param.name.cyan()),
+                    }
+                }
+            }
         }
     }
 }
@@ -234,101 +77,685 @@ impl Completer for MoonriverHelper {
         pos: usize,
         _ctx: &Context<'_>,
     ) -> rustyline::Result<(usize, Vec<Pair>)> {
-        let mut candidates = Vec::new();
         let input = &line[..pos];
-        let input_upper = input.to_uppercase();
-
-        // Check for G-code command completion
-        for cmd in &self.gcode_commands {
-            if cmd.starts_with(&input_upper) {
-                candidates.push(Pair {
-                    display: cmd.clone(),
-                    replacement: cmd.clone(),
-                });
-            }
+
+        // Each line of a multi-line block is completed on its own
+        let current_line = input[input.rfind('\n').map(|i| i + 1).unwrap_or(0)..].trim_start();
+
+        // Meta commands take the whole line; their arguments may contain commas
+        let (start, candidates) = if meta::is_meta_command(current_line) {
+            let word_start = input.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+            let word = &input[word_start..];
+            (word_start, self.catalog.complete_meta(current_line, word))
+        } else {
+            self.catalog.complete(input)
+        };
+        let pairs = candidates
+            .into_iter()
+            .map(|c| Pair {
+                display: c.display,
+                replacement: c.replacement,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+/// Dimmed hint showing a command's description
+///
+/// Only the rest of the command name is inserted when the hint is accepted;
+/// the description is display-only.
+struct CommandHint {
+    display: String,
+    completion: Option<String>,
+}
+
+impl Hint for CommandHint {
+    fn display(&self) -> &str {
+        &self.display
+    }
+
+    fn completion(&self) -> Option<&str> {
+        self.completion.as_deref()
+    }
+}
+
+impl Hinter for MoonriverHelper {
+    type Hint = CommandHint;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<CommandHint> {
+        // Only hint at the end of the line
+        if pos < line.len() {
+            return None;
+        }
+        let line = &line[line.rfind('\n').map(|i| i + 1).unwrap_or(0)..];
+
+        if meta::is_meta_command(line) {
+            return meta_hint(line);
         }
 
-        // Check for Klipper extended command completion
-        for cmd in &self.klipper_commands {
-            if cmd.starts_with(&input_upper) {
-                candidates.push(Pair {
-                    display: cmd.clone(),
-                    replacement: cmd.clone(),
-                });
+        let command = &line[line.rfind(',').map(|i| i + 1).unwrap_or(0)..];
+        let command = command.trim_start();
+        let word_end = command.find(char::is_whitespace).unwrap_or(command.len());
+        let typed = &command[..word_end];
+        if typed.is_empty() {
+            return None;
+        }
+        let typed_upper = typed.to_uppercase();
+
+        // Still typing the command name: suggest the rest of a unique match
+        if word_end == command.len() && !self.catalog.descriptions.contains_key(&typed_upper) {
+            let mut matches = self
+                .catalog
+                .descriptions
+                .iter()
+                .filter(|(name, _)| name.starts_with(&typed_upper));
+            let (name, description) = matches.next()?;
+            if matches.next().is_some() {
+                return None;
             }
+            let rest = name[typed.len()..].to_string();
+            return Some(CommandHint {
+                display: format!("{}  {}", rest, description),
+                completion: Some(rest),
+            });
         }
 
-        // Check for macro completion
-        for macro_name in &self.macros {
-            let macro_upper = macro_name.to_uppercase();
-            if macro_upper.starts_with(&input_upper) {
-                candidates.push(Pair {
-                    display: macro_name.clone(),
-                    replacement: macro_name.clone(),
-                });
+        // Complete command: show its description until parameters are typed
+        if command[word_end..].trim().is_empty() {
+            let description = self.catalog.descriptions.get(&typed_upper)?;
+            if description.is_empty() {
+                return None;
             }
+            let separator = if word_end == command.len() { "  " } else { " " };
+            return Some(CommandHint {
+                display: format!("{}{}", separator, description),
+                completion: None,
+            });
         }
 
-        Ok((0, candidates))
+        None
     }
 }
 
-impl Hinter for MoonriverHelper {
-    type Hint = String;
+/// Hint the rest of a meta command name, or its usage once the name is complete
+fn meta_hint(line: &str) -> Option<CommandHint> {
+    let (typed, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    if !rest.trim().is_empty() {
+        return None;
+    }
 
-    fn hint(&self, _line: &str, _pos: usize, _ctx: &Context<'_>) -> Option<String> {
-        None
+    if let Some(command) = meta::find(typed) {
+        let separator = if rest.is_empty() { "  " } else { " " };
+        let usage = if command.usage.is_empty() {
+            command.description.to_string()
+        } else {
+            format!("{}  {}", command.usage, command.description)
+        };
+        return Some(CommandHint {
+            display: format!("{}{}", separator, usage),
+            completion: None,
+        });
+    }
+
+    if !rest.is_empty() {
+        return None;
     }
+    let typed_lower = typed.to_lowercase();
+    let mut matches = META_COMMANDS.iter().filter(|c| c.name.starts_with(&typed_lower));
+    let command = matches.next()?;
+    if matches.next().is_some() {
+        return None;
+    }
+    let remainder = command.name[typed.len()..].to_string();
+    Some(CommandHint {
+        display: format!("{}  {}", remainder, command.description),
+        completion: Some(remainder),
+    })
 }
 
 impl Highlighter for MoonriverHelper {
     fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
-        // Simple syntax highlighting
-        let upper = line.to_uppercase();
-        let first_word = upper.split_whitespace().next().unwrap_or("");
+        // Colour the whole line by what its first word is
+        let first_word = line.split_whitespace().next().unwrap_or("");
+        match self.catalog.kind(first_word) {
+            CommandKind::Gcode => Cow::Owned(line.bright_green().to_string()),
+            CommandKind::Klipper => Cow::Owned(line.bright_blue().to_string()),
+            CommandKind::Meta => Cow::Owned(line.bright_yellow().to_string()),
+            CommandKind::Alias => Cow::Owned(line.bright_magenta().to_string()),
+            CommandKind::Macro => Cow::Owned(line.bright_cyan().to_string()),
+            CommandKind::Unknown => Cow::Borrowed(line),
+        }
+    }
 
-        // Check if it's a standard G-code command (G or M code)
-        if (first_word.starts_with('G') || first_word.starts_with('M'))
-            && self.gcode_commands.contains(first_word)
-        {
-            return Cow::Owned(line.bright_green().to_string());
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: CmdKind) -> bool {
+        true
+    }
+}
+
+impl Validator for MoonriverHelper {
+    /// Keep reading lines after a trailing `\` or while a `{` or `[` is open
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input().trim_end();
+        if input.ends_with('\\') || is_unclosed(input) {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
+}
+
+/// Whether `text` ends inside an open `{` or `[` block
+///
+/// Brackets inside double quotes or after a `;` comment don't count, and a
+/// stray `"` or `(` never holds the line open: `M117 5" nozzle` is complete.
+fn is_unclosed(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    for c in text.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => break,
+            '[' | '{' if !in_quotes => depth += 1,
+            ']' | '}' if !in_quotes => depth -= 1,
+            _ => {}
         }
+    }
+    depth > 0
+}
 
-        // Check if it's a Klipper extended command
-        if self.klipper_commands.contains(first_word) {
-            return Cow::Owned(line.bright_blue().to_string());
+/// Drop a `;` comment the way Klipper does, keeping `;` inside double quotes
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => return &line[..i],
+            _ => {}
         }
+    }
+    line
+}
 
-        // Check if it's a macro
-        for macro_name in &self.macros {
-            if upper.starts_with(&macro_name.to_uppercase()) {
-                return Cow::Owned(line.bright_cyan().to_string());
+/// Split a multi-line block into commands, one per line
+///
+/// A trailing `\` only marks that more lines follow. Lines inside an open `{` or
+/// `[` are joined, so a long `{...}` expression can span lines.
+fn split_block(input: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+
+    for line in input.lines() {
+        let line = line.trim();
+        let line = line.strip_suffix('\\').map(str::trim_end).unwrap_or(line);
+        if !current.is_empty() && !line.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+        if !is_unclosed(&current) && !current.is_empty() {
+            commands.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        commands.push(current);
+    }
+    commands
+}
+
+impl Helper for MoonriverHelper {}
+
+/// Handle the local `alias` / `unalias` commands
+/// Returns true if the line was one of them
+fn handle_alias_command(line: &str, aliases: &mut Aliases) -> bool {
+    let mut parts = line.splitn(2, char::is_whitespace);
+    let command = parts.next().unwrap_or("").to_lowercase();
+    let rest = parts.next().unwrap_or("").trim();
+
+    match command.as_str() {
+        "alias" if rest.is_empty() => {
+            if aliases.is_empty() {
+                println!("{}", "No aliases defined".yellow());
+            }
+            for (name, body) in aliases.iter() {
+                println!("{} {} {}", name.bright_magenta().bold(), "=".dimmed(), body);
             }
+            true
         }
+        "alias" => {
+            // Accept both `alias name body` and `alias name = body`
+            let split = rest
+                .find(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or(rest.len());
+            let (name, body) = rest.split_at(split);
+            let body = body.trim_start().trim_start_matches('=').trim();
+
+            if body.is_empty() {
+                match aliases.lookup(name) {
+                    Some((name, body)) => println!("{} {} {}", name.bright_magenta().bold(), "=".dimmed(), body),
+                    None => println!("{}", format!("No alias named '{}'", name).yellow()),
+                }
+            } else {
+                aliases.define(name, body);
+                println!("{}", format!("Alias '{}' defined", name).green());
+            }
+            true
+        }
+        "unalias" => {
+            if aliases.remove(rest) {
+                println!("{}", format!("Alias '{}' removed", rest).green());
+            } else {
+                println!("{}", format!("No alias named '{}'", rest).yellow());
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Read a line on a blocking thread while printing printer messages above the prompt
+///
+/// Without this, anything Klipper says while we sit at the prompt (RESPOND output,
+/// print finished, shutdown) would only appear after the next Enter.
+async fn read_line<P: ExternalPrinter>(
+    mut rl: Editor<MoonriverHelper, DefaultHistory>,
+    prompt: String,
+    client: &mut MoonrakerClient,
+    printer: &mut Option<P>,
+    background: &mut Background,
+) -> Result<(Editor<MoonriverHelper, DefaultHistory>, rustyline::Result<String>)> {
+    let mut readline = tokio::task::spawn_blocking(move || {
+        let line = rl.readline(&prompt);
+        (rl, line)
+    });
 
-        Cow::Borrowed(line)
+    loop {
+        tokio::select! {
+            result = &mut readline => return Ok(result?),
+            Some(msg) = client.receive_message() => {
+                let text = background_result(&msg, background).or_else(|| render_response(&msg));
+                if let Some(text) = text {
+                    match printer {
+                        Some(printer) => {
+                            let _ = printer.print(text);
+                        }
+                        None => println!("{}", text),
+                    }
+                }
+            }
+        }
     }
+}
 
-    fn highlight_char(&self, _line: &str, _pos: usize, _forced: CmdKind) -> bool {
-        true
+/// Commands left running in the background, keyed by request id
+type Background = HashMap<u64, String>;
+
+/// Frames for the "waiting for command" spinner
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Only show the spinner for commands that take a noticeable amount of time
+const SPINNER_DELAY: Duration = Duration::from_millis(300);
+
+/// Response id and error text of a JSON-RPC reply, if `message` is one
+fn parse_reply(message: &str) -> Option<(u64, Option<String>)> {
+    let value: serde_json::Value = serde_json::from_str(message).ok()?;
+    let id = value.get("id")?.as_u64()?;
+    let error = value.get("error").map(|error| {
+        error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown error")
+            .to_string()
+    });
+    Some((id, error))
+}
+
+/// Report a finished background command, if `message` is its reply
+fn background_result(message: &str, background: &mut Background) -> Option<String> {
+    let (id, error) = parse_reply(message)?;
+    let command = background.remove(&id)?;
+    Some(match error {
+        None => format!("[bg] {} done", command).green().to_string(),
+        Some(error) => format!("[bg] {} failed: {}", command, error).red().to_string(),
+    })
+}
+
+/// Wait for the reply to request `id`, printing printer output as it streams in
+///
+/// A spinner with the elapsed time is shown while the command runs. Ctrl+C offers
+/// to stop waiting; the command itself keeps running and is moved to the background.
+async fn wait_for_command(
+    client: &mut MoonrakerClient,
+    id: u64,
+    command: &str,
+    background: &mut Background,
+) -> Result<()> {
+    let start = Instant::now();
+    let mut ticker = tokio::time::interval(Duration::from_millis(100));
+    let mut frame = 0;
+
+    loop {
+        tokio::select! {
+            message = client.receive_message() => {
+                print!("\r\x1b[K");
+                let Some(message) = message else {
+                    eprintln!("{}", "Connection to Moonraker closed".red());
+                    return Ok(());
+                };
+                if let Some((reply_id, _)) = parse_reply(&message)
+                    && reply_id == id
+                {
+                    format_response(&message);
+                    return Ok(());
+                }
+                match background_result(&message, background) {
+                    Some(text) => println!("{}", text),
+                    None => format_response(&message),
+                }
+            }
+            _ = ticker.tick() => {
+                let elapsed = start.elapsed();
+                if elapsed >= SPINNER_DELAY {
+                    print!(
+                        "\r\x1b[K{} {} {}",
+                        SPINNER[frame % SPINNER.len()].cyan(),
+                        command.dimmed(),
+                        format!("{:.1}s", elapsed.as_secs_f64()).dimmed()
+                    );
+                    let _ = std::io::stdout().flush();
+                    frame += 1;
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                let question = format!(
+                    "Stop waiting for '{}'? It keeps running on the printer.",
+                    command
+                );
+                if meta::confirm(question).await {
+                    println!("{}", format!("[bg] {} continues in the background", command).cyan());
+                    background.insert(id, command.to_string());
+                    return Ok(());
+                }
+            }
+        }
     }
 }
 
-impl Validator for MoonriverHelper {}
+/// Send one command, waiting for it unless it ends with `&`
+async fn run_command(
+    client: &mut MoonrakerClient,
+    command: &str,
+    background: &mut Background,
+) -> Result<()> {
+    let (command, detached) = match command.strip_suffix('&') {
+        Some(command) => (command.trim_end(), true),
+        None => (command, false),
+    };
+    if command.is_empty() {
+        return Ok(());
+    }
 
-impl Helper for MoonriverHelper {}
+    let id = client.send_gcode(command).await?;
+    if detached {
+        println!("{}", format!("[bg] {} started", command).cyan());
+        background.insert(id, command.to_string());
+        return Ok(());
+    }
 
-pub async fn run_repl(mut client: MoonrakerClient) -> Result<()> {
-    let mut rl = Editor::new()?;
-    let mut helper = MoonriverHelper::new();
+    wait_for_command(client, id, command, background).await
+}
 
-    // Try to load macros
-    if let Ok(macros) = client.get_macros().await {
-        helper.set_macros(macros);
+/// How deep `.source` files may source other files
+const MAX_SOURCE_DEPTH: usize = 8;
+
+/// Lines waiting to run, with how many `.source` files deep they came from
+type LineQueue = VecDeque<(String, usize)>;
+
+/// Commands being captured by `.record`
+struct Recording {
+    name: String,
+    lines: Vec<String>,
+}
+
+/// Whether the REPL keeps reading input after a line
+enum Flow {
+    Continue,
+    Exit,
+}
+
+/// State shared by every line the REPL runs
+struct Session {
+    client: MoonrakerClient,
+    aliases: Aliases,
+    background: Background,
+    recording: Option<Recording>,
+}
+
+impl Session {
+    /// Run a single line of input
+    async fn handle_line(
+        &mut self,
+        rl: &mut Editor<MoonriverHelper, DefaultHistory>,
+        line: &str,
+        depth: usize,
+        queue: &mut LineQueue,
+    ) -> Flow {
+        let line = line.trim();
+        if line.is_empty() {
+            return Flow::Continue;
+        }
+
+        // Check for exit command
+        if line.eq_ignore_ascii_case("exit") || line.eq_ignore_ascii_case("quit") {
+            return Flow::Exit;
+        }
+
+        // Echo lines from sourced files so progress is visible
+        if depth > 0 {
+            println!("{} {}", ">".dimmed(), line.dimmed());
+        }
+
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let name = name.to_lowercase();
+        let rest = rest.trim();
+
+        // Record what the user types, not what sourced files contain
+        if depth == 0
+            && let Some(recording) = &mut self.recording
+            && !matches!(name.as_str(), ".record" | ".stop")
+        {
+            recording.lines.push(line.to_string());
+        }
+
+        match name.as_str() {
+            ".source" => {
+                if let Err(e) = self.source(rest, depth, queue) {
+                    self.report_error(format!("Error: {}", e));
+                }
+                return Flow::Continue;
+            }
+            ".record" => {
+                self.start_recording(rest);
+                return Flow::Continue;
+            }
+            ".stop" => {
+                if self.recording.is_none() {
+                    println!("{}", "Not recording".yellow());
+                }
+                self.stop_recording();
+                return Flow::Continue;
+            }
+            _ => {}
+        }
+
+        // Local `help <CMD>`; a bare HELP goes to Klipper
+        if name == "help" && !rest.is_empty() {
+            if let Some(helper) = rl.helper() {
+                helper.print_help(rest);
+            }
+            return Flow::Continue;
+        }
+
+        // Local `.` commands talk to Moonraker directly
+        if meta::is_meta_command(line) {
+            if let Err(e) = meta::run(&mut self.client, line).await {
+                self.report_error(format!("Error: {}", e));
+            }
+            match name.as_str() {
+                // Request ids restart on a new connection
+                ".reconnect" => self.background.clear(),
+                ".files" | ".print" => {
+                    if let (Ok(files), Some(helper)) =
+                        (meta::list_files(&mut self.client).await, rl.helper_mut())
+                    {
+                        helper.catalog.files = files;
+                    }
+                }
+                _ => {}
+            }
+            return Flow::Continue;
+        }
+
+        // Local alias management
+        if handle_alias_command(line, &mut self.aliases) {
+            if let Some(helper) = rl.helper_mut() {
+                helper.catalog.set_aliases(&self.aliases);
+            }
+            return Flow::Continue;
+        }
+
+        // Split by comma to support multiple commands, expanding aliases
+        let commands = match self.aliases.expand_line(line) {
+            Ok(commands) => commands,
+            Err(e) => {
+                self.report_error(format!("Error: {}", e));
+                return Flow::Continue;
+            }
+        };
+
+        for cmd in commands {
+            if let Err(e) = run_command(&mut self.client, &cmd, &mut self.background).await {
+                self.report_error(format!("Error sending command: {}", e));
+            }
+        }
+        Flow::Continue
+    }
+
+    /// Print an error and add it to the transcript
+    fn report_error(&self, message: String) {
+        eprintln!("{}", message.red());
+        self.client.transcript().log(EntryKind::Error, &message);
+    }
+
+    /// Queue the lines of a local file ahead of anything else waiting to run
+    fn source(&self, name: &str, depth: usize, queue: &mut LineQueue) -> Result<()> {
+        if name.is_empty() {
+            return Err(anyhow!("Usage: .source <file>"));
+        }
+        if depth >= MAX_SOURCE_DEPTH {
+            return Err(anyhow!("'.source {}' is nested too deeply", name));
+        }
+        let path = meta::resolve_script(name)
+            .ok_or_else(|| anyhow!("No file or saved script named '{}'", name))?;
+        let contents = std::fs::read_to_string(&path)?;
+
+        // Drop `;` comments the way Klipper does
+        let code: Vec<&str> = contents
+            .lines()
+            .map(strip_comment)
+            .collect();
+        let lines = split_block(&code.join("\n"));
+
+        println!(
+            "{}",
+            format!("Running {} line(s) from {}", lines.len(), path.display()).cyan()
+        );
+        for line in lines.into_iter().rev() {
+            queue.push_front((line, depth + 1));
+        }
+        Ok(())
     }
 
+    fn start_recording(&mut self, name: &str) {
+        if let Some(recording) = &self.recording {
+            println!(
+                "{}",
+                format!("Already recording '{}'. Type .stop first", recording.name).yellow()
+            );
+            return;
+        }
+        if name.is_empty() {
+            self.report_error("Error: Usage: .record <name>".to_string());
+            return;
+        }
+        if let Err(e) = meta::validate_script_name(name) {
+            self.report_error(format!("Error: {}", e));
+            return;
+        }
+
+        println!(
+            "{}",
+            format!("Recording to '{}'. Type .stop to save", name).cyan()
+        );
+        self.recording = Some(Recording {
+            name: name.to_string(),
+            lines: Vec::new(),
+        });
+    }
+
+    /// Save the current recording, if any
+    fn stop_recording(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        if recording.lines.is_empty() {
+            println!("{}", format!("Nothing recorded for '{}'", recording.name).yellow());
+            return;
+        }
+
+        match meta::save_script(&recording.name, &recording.lines) {
+            Ok(path) => println!(
+                "{}",
+                format!(
+                    "Saved {} line(s) to {}. Run it with .source {}",
+                    recording.lines.len(),
+                    path.display(),
+                    recording.name
+                )
+                .green()
+            ),
+            Err(e) => self.report_error(format!("Error saving script: {}", e)),
+        }
+    }
+}
+
+pub async fn run_repl(mut client: MoonrakerClient, config: &Config) -> Result<()> {
+    let editor_config = rustyline::Config::builder()
+        .max_history_size(config.history.max_entries)?
+        .build();
+    let mut rl = Editor::with_config(editor_config)?;
+
+    // Up/Down only walk through entries starting with what has been typed
+    rl.bind_sequence(
+        KeyEvent(KeyCode::Up, Modifiers::NONE),
+        Cmd::HistorySearchBackward,
+    );
+    rl.bind_sequence(
+        KeyEvent(KeyCode::Down, Modifiers::NONE),
+        Cmd::HistorySearchForward,
+    );
+    let mut helper = MoonriverHelper::new();
+    let aliases = Aliases::new(config.aliases.clone());
+    helper.catalog.set_aliases(&aliases);
+
+    // Load macros, printer objects, files and power devices for completion
+    helper.catalog.load(&mut client).await;
+
     // Try to get available commands from HELP dynamically
-    if let Ok(()) = client.send_gcode("HELP").await {
+    if client.send_gcode("HELP").await.is_ok() {
         // Wait longer for HELP response - it can be large
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         
@@ -396,16 +823,19 @@ pub async fn run_repl(mut client: MoonrakerClient) -> Result<()> {
         }
         
         if !help_commands.is_empty() {
-            helper.add_klipper_commands(help_commands);
+            helper.catalog.add_klipper_commands(help_commands);
         }
     }
 
     rl.set_helper(Some(helper));
 
-    // Load history if it exists
-    let history_path = dirs::home_dir().map(|p| p.join(".moonriver_history"));
-    if let Some(ref path) = history_path {
-        let _ = rl.load_history(path);
+    // Printer for showing notifications while the prompt is idle
+    let mut printer = rl.create_external_printer().ok();
+
+    // Load the history shared with the TUI console
+    let mut history = History::open(&config.history, &client.server());
+    for entry in history.entries() {
+        rl.add_history_entry(entry.as_str())?;
     }
 
     println!(
@@ -420,79 +850,69 @@ pub async fn run_repl(mut client: MoonrakerClient) -> Result<()> {
         "{}",
         "Use ',' to separate multiple commands on one line.".cyan()
     );
+    println!(
+        "{}",
+        "End a command with '&' to run it in the background.".cyan()
+    );
+    println!(
+        "{}",
+        "Type '.help' for local commands such as .status and .print.".cyan()
+    );
     println!("{}", "Type 'M112' for emergency stop.\n".yellow().bold());
 
+    let mut session = Session {
+        client,
+        aliases,
+        background: Background::new(),
+        recording: None,
+    };
+
     loop {
         // Check for any incoming messages before showing prompt
-        while let Some(msg) = client.try_receive_message() {
+        while let Some(msg) = session.client.try_receive_message() {
             print!("\r\x1b[K"); // Clear current line
-            format_response(&msg);
+            match background_result(&msg, &mut session.background) {
+                Some(text) => println!("{}", text),
+                None => format_response(&msg),
+            }
         }
 
-        let readline = rl.readline(&format!("{} ", ">".bright_blue().bold()));
+        let prompt = format!("{} ", ">".bright_blue().bold());
+        let (editor, readline) = read_line(
+            rl,
+            prompt,
+            &mut session.client,
+            &mut printer,
+            &mut session.background,
+        )
+        .await?;
+        rl = editor;
 
         match readline {
-            Ok(line) => {
-                let line = line.trim();
-
-                if line.is_empty() {
+            Ok(input) => {
+                if input.trim().is_empty() {
                     continue;
                 }
 
-                rl.add_history_entry(line)?;
-
-                // Check for exit command
-                if line.to_lowercase() == "exit" || line.to_lowercase() == "quit" {
+                rl.add_history_entry(input.trim())?;
+                history.add(&input);
+
+                // A multi-line block runs line by line; `.source` queues more lines
+                let mut queue: LineQueue = split_block(&input)
+                    .into_iter()
+                    .map(|line| (line, 0))
+                    .collect();
+                let mut flow = Flow::Continue;
+                while let Some((line, depth)) = queue.pop_front() {
+                    flow = session.handle_line(&mut rl, &line, depth, &mut queue).await;
+                    if let Flow::Exit = flow {
+                        break;
+                    }
+                }
+                if let Flow::Exit = flow {
                     println!("{}", "Goodbye!".cyan());
                     break;
                 }
-
-                // Split by comma to support multiple commands
-                let commands: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
-
-                for cmd in commands {
-                    if !cmd.is_empty() {
-                        if let Err(e) = client.send_gcode(cmd).await {
-                            eprintln!("{}", format!("Error sending command: {}", e).red());
-                            continue;
-                        }
-
-                        // Wait for responses - use a timeout approach
-                        let start = tokio::time::Instant::now();
-                        let mut got_response = false;
-                        let mut last_message_time = tokio::time::Instant::now();
-                        
-                        // Wait up to 2 seconds for responses, checking every 30ms
-                        // Give 250ms after the last message to ensure all responses arrive
-                        while start.elapsed() < tokio::time::Duration::from_millis(2000) {
-                            // Check for messages
-                            let mut found_msg = false;
-                            while let Some(msg) = client.try_receive_message() {
-                                format_response(&msg);
-                                found_msg = true;
-                                got_response = true;
-                                last_message_time = tokio::time::Instant::now();
-                            }
-                            
-                            // If we got messages, continue checking
-                            if found_msg {
-                                tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
-                            } else if got_response && last_message_time.elapsed() > tokio::time::Duration::from_millis(250) {
-                                // We've received responses and 250ms has passed with no new messages - we're done
-                                break;
-                            } else {
-                                // No response yet, wait a bit and check again
-                                tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
-                            }
-                        }
-                        
-                        // Final drain of any remaining messages
-                        tokio::time::sleep(tokio::time::Duration::from_millis(80)).await;
-                        while let Some(msg) = client.try_receive_message() {
-                            format_response(&msg);
-                        }
-                    }
-                }
             }
             Err(ReadlineError::Interrupted) => {
                 println!("{}", "Ctrl+C pressed. Type 'exit' to quit.".yellow());
@@ -508,11 +928,40 @@ pub async fn run_repl(mut client: MoonrakerClient) -> Result<()> {
         }
     }
 
-    // Save history
-    if let Some(ref path) = history_path {
-        let _ = rl.save_history(path);
-    }
+    // Don't lose a recording that was never stopped
+    session.stop_recording();
 
-    client.disconnect().await?;
+    session.client.disconnect().await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_open_braces_and_brackets_continue() {
+        assert!(is_unclosed("SET_GCODE_VARIABLE VALUE={"));
+        assert!(is_unclosed("VALUE=[1, 2,"));
+        assert!(!is_unclosed("VALUE={1: [2]}"));
+        assert!(!is_unclosed("M117 5\" nozzle"));
+        assert!(!is_unclosed("M117 (note"));
+        assert!(!is_unclosed("M117 \"{\""));
+        assert!(!is_unclosed("G28 ; {"));
+    }
+
+    #[test]
+    fn comments_are_stripped_outside_quotes() {
+        assert_eq!(strip_comment("G28 ; home"), "G28 ");
+        assert_eq!(strip_comment("M117 \"a;b\" ; note"), "M117 \"a;b\" ");
+        assert_eq!(strip_comment("M117 \"a;b"), "M117 \"a;b");
+        assert_eq!(strip_comment("; only a comment"), "");
+    }
+
+    #[test]
+    fn blocks_split_per_line_and_join_open_braces() {
+        assert_eq!(split_block("G28 \\\nM105"), ["G28", "M105"]);
+        assert_eq!(split_block("M117 5\" nozzle\nM105"), ["M117 5\" nozzle", "M105"]);
+        assert_eq!(split_block("VALUE={\n'x': 1 }\nG28"), ["VALUE={ 'x': 1 }", "G28"]);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::config::{LogConfig, LogFormat};
+use crate::moonraker::{classify_response, ResponseKind};
+use colored::Colorize;
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// What a transcript line records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Command sent to the printer
+    Command,
+    /// Output from Klipper
+    Response,
+    /// Error from Moonraker, Klipper or Moonriver itself
+    Error,
+    /// Print state change or Klipper ready/shutdown
+    State,
+    /// Informational message from Moonriver
+    Info,
+}
+
+impl EntryKind {
+    fn label(self) -> &'static str {
+        match self {
+            EntryKind::Command => "command",
+            EntryKind::Response => "response",
+            EntryKind::Error => "error",
+            EntryKind::State => "state",
+            EntryKind::Info => "info",
+        }
+    }
+}
+
+/// Timestamped log of a session, appended to a file
+///
+/// Cloning gives another handle to the same file, so the client and the
+/// front-end can both write to it. A disabled transcript ignores everything.
+#[derive(Clone)]
+pub struct Transcript {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    path: PathBuf,
+    format: LogFormat,
+    max_bytes: u64,
+    keep: usize,
+    /// Which front-end is writing: `repl`, `tui` or `script`
+    source: &'static str,
+    enabled: bool,
+    file: Option<File>,
+}
+
+impl Transcript {
+    /// Create a transcript from the config, with `--log-file` taking precedence
+    /// Logging starts enabled only if a file was given in either place
+    pub fn new(config: &LogConfig, log_file: Option<&Path>, source: &'static str) -> Self {
+        let path = log_file
+            .map(Path::to_path_buf)
+            .or_else(|| config.file.as_deref().map(expand_home));
+        let enabled = path.is_some();
+
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path: path.unwrap_or_else(default_path),
+                format: config.format,
+                max_bytes: (config.max_size_mb * 1024.0 * 1024.0) as u64,
+                keep: config.keep,
+                source,
+                enabled,
+                file: None,
+            })),
+        }
+    }
+
+    /// A transcript that never writes anything
+    pub fn disabled() -> Self {
+        Self::new(&LogConfig::default(), None, "")
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.lock().map(|inner| inner.enabled).unwrap_or(false)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.enabled = enabled;
+            if !enabled {
+                inner.file = None;
+            }
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.inner
+            .lock()
+            .map(|inner| inner.path.clone())
+            .unwrap_or_default()
+    }
+
+    /// Append one entry
+    pub fn log(&self, kind: EntryKind, text: &str) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if !inner.enabled {
+            return;
+        }
+        if let Err(e) = inner.write(kind, text) {
+            // Don't fail the session over the transcript; stop logging instead
+            eprintln!(
+                "{}",
+                format!("Transcript disabled, cannot write {}: {}", inner.path.display(), e).red()
+            );
+            inner.enabled = false;
+            inner.file = None;
+        }
+    }
+
+    /// Log a raw Moonraker message the way the console shows it
+    /// Structured request results and silent notifications are skipped
+    pub fn log_message(&self, message: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some((kind, text)) = classify_response(message) else {
+            return;
+        };
+        let kind = match kind {
+            ResponseKind::Response => EntryKind::Response,
+            ResponseKind::Error => EntryKind::Error,
+            ResponseKind::State => EntryKind::State,
+            ResponseKind::Data => return,
+        };
+        self.log(kind, &text);
+    }
+}
+
+impl Inner {
+    fn write(&mut self, kind: EntryKind, text: &str) -> std::io::Result<()> {
+        let now = chrono::Local::now();
+        let line = match self.format {
+            LogFormat::Text => {
+                let mut line = String::new();
+                for (i, text_line) in text.trim_end().lines().enumerate() {
+                    // Indent continuation lines so every entry starts with a timestamp
+                    if i == 0 {
+                        line.push_str(&format!(
+                            "{} [{}] [{}] {}\n",
+                            now.format("%Y-%m-%d %H:%M:%S%.3f"),
+                            self.source,
+                            kind.label(),
+                            text_line
+                        ));
+                    } else {
+                        line.push_str(&format!("    {}\n", text_line));
+                    }
+                }
+                line
+            }
+            LogFormat::Jsonl => format!(
+                "{}\n",
+                json!({
+                    "time": now.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+                    "source": self.source,
+                    "kind": kind.label(),
+                    "text": text.trim_end(),
+                })
+            ),
+        };
+
+        self.rotate_if_needed(line.len() as u64)?;
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                std::fs::create_dir_all(parent)?;
+            }
+            self.file = Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        }
+        if let Some(file) = &mut self.file {
+            file.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Shift `log`, `log.1`, ... up by one once the current file would grow too large
+    fn rotate_if_needed(&mut self, incoming: u64) -> std::io::Result<()> {
+        if self.max_bytes == 0 {
+            return Ok(());
+        }
+        let size = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size == 0 || size + incoming <= self.max_bytes {
+            return Ok(());
+        }
+
+        self.file = None;
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        if self.keep == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(rotated(self.keep));
+        for n in (1..self.keep).rev() {
+            let _ = std::fs::rename(rotated(n), rotated(n + 1));
+        }
+        std::fs::rename(&self.path, rotated(1))
+    }
+}
+
+/// Transcript location when logging is switched on without a configured file
+fn default_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("moonriver")
+        .join("transcript.log")
+}
+
+/// Expand a leading `~/` in a configured path
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -1,13 +1,36 @@
+use super::console_log::{ConsoleLog, ConsoleSearch, ScrollAnchor};
+use super::file_browser::FileBrowser;
+use super::macro_browser::{self, MacroBrowser, MacroForm};
+use super::modal::{Modal, ModalAction, ModalButton};
 use super::printer::PrinterState;
 use super::stateful_list::StatefulList;
 use super::tabs::Tab;
-use crate::config::Config;
+use super::temperature_history::{self, TemperatureHistory};
+use super::thumbnail::{GraphicsOverlay, Thumbnail};
+use crate::alias::Aliases;
+use crate::completion::{CommandCatalog, Completion};
+use crate::config::{Config, ConsoleCategory, ThumbnailProtocol};
+use crate::history::History;
+use crate::macros::MacroInfo;
 use crate::moonraker::MoonrakerClient;
+use crate::transcript::{EntryKind, Transcript};
 use crate::tui::event::Event;
-use crate::tui::printer::PrintJob;
+use crate::tui::printer::{JobTotals, PrintJob};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use serde::{Deserialize, Serialize};
 
+/// Jog distances selectable with +/- on the Position tab, in mm
+pub const JOG_STEPS: &[f64] = &[0.1, 1.0, 10.0, 100.0];
+
+/// Axis letters, indexed like `Toolhead::position`
+const AXES: [&str; 3] = ["X", "Y", "Z"];
+
+/// Jobs fetched per page of the job history
+pub const JOB_PAGE_SIZE: usize = 50;
+
+/// Commands that restart Klipper, confirmed before they are sent from the console
+const CONFIRM_COMMANDS: &[&str] = &["SAVE_CONFIG", "FIRMWARE_RESTART"];
+
 /// Console message types
 #[derive(Debug, Clone)]
 pub enum ConsoleMessage {
@@ -17,6 +40,26 @@ pub enum ConsoleMessage {
     Info(String),
 }
 
+impl ConsoleMessage {
+    pub fn text(&self) -> &str {
+        match self {
+            ConsoleMessage::Command(text)
+            | ConsoleMessage::Response(text)
+            | ConsoleMessage::Error(text)
+            | ConsoleMessage::Info(text) => text,
+        }
+    }
+
+    /// Two-column marker shown before the message in the console
+    pub fn marker(&self) -> &'static str {
+        match self {
+            ConsoleMessage::Command(_) => "> ",
+            ConsoleMessage::Error(_) => "✗ ",
+            ConsoleMessage::Response(_) | ConsoleMessage::Info(_) => "  ",
+        }
+    }
+}
+
 /// Panel visibility state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PanelVisibility {
@@ -43,10 +86,28 @@ pub enum InputMode {
 }
 
 /// Temperature heater being edited
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TempEditTarget {
-    Extruder,
-    Bed,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TempEditTarget {
+    /// Klipper object name of the heater
+    pub heater: String,
+}
+
+/// Entry in the material preset picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetChoice {
+    /// Named preset from the `[presets]` config section
+    Preset(String),
+    /// Turn off all heaters
+    Cooldown,
+}
+
+/// Row in the console filter menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterRow {
+    /// Show or hide a category of output
+    Category(ConsoleCategory),
+    /// The regex for lines to hide
+    Pattern,
 }
 
 /// Fan being edited (index into the fans array)
@@ -63,6 +124,13 @@ pub enum PosEditTarget {
     Z,
 }
 
+/// Tab completion candidates shown above the console input
+pub struct CompletionMenu {
+    /// Byte offset in the input where the completed word starts
+    pub start: usize,
+    pub candidates: StatefulList<Completion>,
+}
+
 /// Input field state
 #[derive(Debug, Clone)]
 pub struct InputState {
@@ -82,7 +150,7 @@ impl InputState {
 
     pub fn enter_edit_mode(&mut self) {
         self.mode = InputMode::Editing;
-        self.cursor_position = self.value.len() as u16;
+        self.cursor_position = self.value.chars().count() as u16;
     }
 
     pub fn exit_edit_mode(&mut self) {
@@ -93,6 +161,64 @@ impl InputState {
         self.value.clear();
         self.cursor_position = 0;
     }
+
+    /// Byte offset of the cursor, which counts characters
+    pub fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor_position as usize)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    /// Insert a character at the cursor
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index();
+        self.value.insert(index, c);
+        self.cursor_position += 1;
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor_position > 0 {
+            self.cursor_position -= 1;
+            let index = self.byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    /// Delete the character under the cursor
+    pub fn delete(&mut self) {
+        let index = self.byte_index();
+        if index < self.value.len() {
+            self.value.remove(index);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor_position = self.cursor_position.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        if (self.cursor_position as usize) < self.value.chars().count() {
+            self.cursor_position += 1;
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor_position = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor_position = self.value.chars().count() as u16;
+    }
+
+    /// Replace the text from byte `start` up to the cursor, leaving the cursor after it
+    pub fn replace_before_cursor(&mut self, start: usize, text: &str) {
+        let end = self.byte_index();
+        self.value.replace_range(start..end, text);
+        self.cursor_position = self.value[..start + text.len()].chars().count() as u16;
+    }
 }
 
 impl Default for InputState {
@@ -101,6 +227,16 @@ impl Default for InputState {
     }
 }
 
+/// Reverse-i-search through the command history
+#[derive(Debug, Clone, Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// Index of the current match in the history
+    pub index: Option<usize>,
+    /// Input to restore if the search is cancelled
+    pub original: String,
+}
+
 /// Main application state
 pub struct App {
     /// Whether the application should keep running
@@ -109,14 +245,36 @@ pub struct App {
     pub current_tab: Tab,
     /// Panel visibility state
     pub panel_visibility: PanelVisibility,
-    /// Console scroll position
-    pub console_scroll: u16,
+    /// First visible console line when scrolled back; `None` follows new output
+    pub console_scroll: Option<ScrollAnchor>,
+    /// Console output area (stored after rendering), for paging and wrapping
+    pub console_area: ratatui::layout::Rect,
+    /// Active `/` search in the console
+    pub console_search: Option<ConsoleSearch>,
+    /// Console filter menu (open when set)
+    pub console_filter_menu: Option<StatefulList<FilterRow>>,
+    /// Filter regex being edited in the menu
+    pub filter_input: InputState,
+    /// Why the last filter regex was rejected
+    pub filter_error: Option<String>,
+    /// Filter menu rows area (stored after rendering), for mouse clicks
+    pub console_filter_area: ratatui::layout::Rect,
     /// Console input field
     pub console_input: InputState,
+    /// Tab completion candidates (open when set)
+    pub console_completion: Option<CompletionMenu>,
+    /// Commands, macros and printer objects for completion and highlighting
+    pub command_catalog: CommandCatalog,
     /// Temperature editing input field
     pub temp_input: InputState,
     /// Which temperature is being edited (if any)
     pub temp_edit_target: Option<TempEditTarget>,
+    /// Material preset picker (open when set)
+    pub preset_picker: Option<StatefulList<PresetChoice>>,
+    /// Confirmation dialog (open when set)
+    pub modal: Option<Modal>,
+    /// Modal button click areas (stored after rendering)
+    pub modal_buttons: Vec<(ratatui::layout::Rect, ModalButton)>,
     /// Fan speed editing input field
     pub fan_input: InputState,
     /// Which fan is being edited (if any)
@@ -125,18 +283,49 @@ pub struct App {
     pub pos_input: InputState,
     /// Which position axis is being edited (if any)
     pub pos_edit_target: Option<PosEditTarget>,
-    /// Console message history
-    pub console_messages: Vec<ConsoleMessage>,
-    /// Command history for up/down navigation
-    pub command_history: Vec<String>,
+    /// Index into `JOG_STEPS`
+    pub jog_step_index: usize,
+    /// Result of the last jog, shown on the Position tab
+    pub jog_message: Option<ConsoleMessage>,
+    /// Saved bed mesh profiles, mirroring `bed_mesh.profiles`
+    pub mesh_profiles: StatefulList<String>,
+    /// Show the interpolated mesh rather than the probed points
+    pub mesh_interpolated: bool,
+    /// Profile name being entered to save the active mesh
+    pub mesh_profile_input: InputState,
+    /// Console message history, oldest dropped beyond `[console] max_messages`
+    pub console_messages: ConsoleLog,
+    /// Command history shared with the REPL
+    pub command_history: History,
     /// Current position in command history (None = not browsing)
     pub history_index: Option<usize>,
+    /// Text typed before browsing history; Up/Down only show entries starting with it
+    pub history_prefix: String,
+    /// Active Ctrl+R reverse search
+    pub history_search: Option<HistorySearch>,
     /// Pending commands to send
     pub pending_commands: Vec<String>,
+    /// User-defined command aliases
+    pub aliases: Aliases,
     /// Print job history
     pub job_list: StatefulList<PrintJob>,
+    /// Lifetime totals shown above the job history
+    pub job_totals: Option<JobTotals>,
+    /// Page of the job history being shown (0 = newest)
+    pub job_page: usize,
+    /// Total number of jobs in Moonraker's history
+    pub job_count: usize,
+    /// G-code file browser state
+    pub file_browser: FileBrowser,
+    /// Macros tab: macro list, favourites and the parameter form
+    pub macro_browser: MacroBrowser,
+    /// How thumbnails are drawn, resolved for this terminal
+    pub thumbnail_protocol: ThumbnailProtocol,
+    /// Where a graphics-protocol thumbnail should go (stored after rendering)
+    pub thumbnail_placement: Option<(ratatui::layout::Rect, String)>,
+    /// Graphics-protocol thumbnail currently on screen
+    pub graphics_overlay: GraphicsOverlay,
     /// Configuration
-    #[allow(dead_code)]
     pub config: Config,
     /// Server URL
     pub server_url: String,
@@ -144,12 +333,18 @@ pub struct App {
     pub http_url: String,
     /// Printer state
     pub printer: PrinterState,
+    /// Temperature readings for the Graphs tab
+    pub temperature_history: TemperatureHistory,
     /// Optional Moonraker client (will be set after connection)
     pub client: Option<MoonrakerClient>,
     /// HTTP client for REST API calls
     pub http_client: reqwest::Client,
     /// Power device click areas (stored after rendering)
     pub power_device_click_areas: Vec<(ratatui::layout::Rect, String)>,
+    /// Session transcript
+    pub transcript: Transcript,
+    /// Number of console messages already written to the transcript
+    logged_messages: usize,
 }
 
 impl App {
@@ -160,46 +355,170 @@ impl App {
         let mut console_input = InputState::new();
         console_input.enter_edit_mode();
         
+        let mut console_messages = ConsoleLog::new(config.console.max_messages);
+        console_messages.filter.hidden = config.console.hide.clone();
+        console_messages.filter.pattern_enabled = true;
+        if let Err(e) = console_messages.filter.set_pattern(&config.console.filter) {
+            console_messages.push(ConsoleMessage::Error(
+                format!("Invalid [console] filter: {}", e)
+            ));
+        }
+
+        let aliases = Aliases::new(config.aliases.clone());
+        let mut command_catalog = CommandCatalog::new();
+        command_catalog.set_aliases(&aliases);
+        let mut macro_browser = MacroBrowser::open(&server_url);
+        macro_browser.refresh(&command_catalog);
+
         App {
             running: true,
             current_tab: Tab::default(),
             panel_visibility: PanelVisibility::default(),
-            console_scroll: 0,
+            console_scroll: None,
+            console_area: ratatui::layout::Rect::default(),
+            console_search: None,
+            console_filter_menu: None,
+            filter_input: InputState::new(),
+            filter_error: None,
+            console_filter_area: ratatui::layout::Rect::default(),
             console_input,
+            console_completion: None,
+            command_catalog,
             temp_input: InputState::new(),
             temp_edit_target: None,
+            preset_picker: None,
+            modal: None,
+            modal_buttons: Vec::new(),
             fan_input: InputState::new(),
             fan_edit_target: None,
             pos_input: InputState::new(),
             pos_edit_target: None,
-            console_messages: Vec::new(),
-            command_history: Vec::new(),
+            jog_step_index: 1,
+            jog_message: None,
+            mesh_profiles: StatefulList::new(),
+            mesh_interpolated: false,
+            mesh_profile_input: InputState::new(),
+            console_messages,
+            command_history: History::open(&config.history, &server_url),
             history_index: None,
+            history_prefix: String::new(),
+            history_search: None,
             pending_commands: Vec::new(),
+            aliases,
             job_list: StatefulList::with_items(Vec::new()),
+            job_totals: None,
+            job_page: 0,
+            job_count: 0,
+            file_browser: FileBrowser::default(),
+            macro_browser,
+            thumbnail_protocol: config.thumbnails.protocol.detect(),
+            thumbnail_placement: None,
+            graphics_overlay: GraphicsOverlay::default(),
             config,
             http_url,
             server_url,
             printer: PrinterState::default(),
+            temperature_history: TemperatureHistory::default(),
             client: None,
             http_client: reqwest::Client::new(),
             power_device_click_areas: Vec::new(),
+            transcript: Transcript::disabled(),
+            logged_messages: 0,
         }
     }
 
     /// Set the Moonraker client
-    pub fn set_client(&mut self, client: MoonrakerClient) {
+    pub fn set_client(&mut self, mut client: MoonrakerClient) {
         self.printer.connected = true;
         self.printer.state = "connecting".to_string();
+        client.set_transcript(self.transcript.clone());
         self.client = Some(client);
     }
     
-    /// Add a console message and ensure auto-scroll to show latest content
+    /// Add a console message and jump back to the newest output
     fn add_console_message(&mut self, message: ConsoleMessage) {
         self.console_messages.push(message);
-        // Set scroll to a large value to ensure we show the bottom
-        // The rendering widget will clamp this to the actual content height
-        self.console_scroll = 9999;
+        self.console_scroll = None;
+    }
+
+    /// Scroll the console by `delta` lines (negative scrolls back)
+    fn scroll_console(&mut self, delta: isize) {
+        self.console_scroll = self.console_messages.scroll(
+            self.console_scroll,
+            delta,
+            self.console_area.width as usize,
+            self.console_area.height as usize,
+            self.config.console.timestamps,
+        );
+    }
+
+    /// Lines moved by PgUp/PgDn, keeping one line of context
+    fn console_page(&self) -> isize {
+        (self.console_area.height as isize - 1).max(1)
+    }
+
+    /// Handle keys during a console search; returns whether the key was used
+    ///
+    /// `n`/`N` only move between matches while the console input isn't being edited.
+    fn handle_console_search_key(&mut self, key: KeyEvent, editing_input: bool) -> bool {
+        let Some(search) = &mut self.console_search else {
+            return false;
+        };
+
+        if search.editing {
+            match key.code {
+                KeyCode::Enter => search.editing = false,
+                KeyCode::Backspace => {
+                    if search.query.pop().is_none() {
+                        self.console_search = None;
+                        return true;
+                    }
+                    search.current = None;
+                    self.find_console_match(true);
+                }
+                KeyCode::Char(c) => {
+                    search.query.push(c);
+                    search.current = None;
+                    self.find_console_match(true);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if editing_input {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('n') => self.find_console_match(true),
+            KeyCode::Char('N') => self.find_console_match(false),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Move to the next older (or newer) search match and scroll to it
+    fn find_console_match(&mut self, older: bool) {
+        let Some(search) = &mut self.console_search else {
+            return;
+        };
+        let matches = self.console_messages.matches(&search.query);
+        let next = match (search.current, older) {
+            (None, _) => matches.last().copied(),
+            (Some(current), true) => matches.iter().rev().find(|&&seq| seq < current).copied(),
+            (Some(current), false) => matches.iter().find(|&&seq| seq > current).copied(),
+        };
+        let Some(seq) = next else {
+            return;
+        };
+
+        search.current = Some(seq);
+        self.console_scroll = self.console_messages.anchor_on(
+            seq,
+            self.console_area.width as usize,
+            self.console_area.height as usize,
+            self.config.console.timestamps,
+        );
     }
 
     /// Handle an event and return whether to continue running
@@ -222,6 +541,22 @@ impl App {
     fn handle_mouse(&mut self, mouse: crossterm::event::MouseEvent) -> crate::tui::Result<()> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        // An open modal takes every click; only its buttons respond
+        if self.modal.is_some() {
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                && let Some((_, button)) = self.modal_buttons.iter().find(|(area, _)| {
+                    mouse.column >= area.x
+                        && mouse.column < area.x + area.width
+                        && mouse.row >= area.y
+                        && mouse.row < area.y + area.height
+                })
+            {
+                let confirmed = *button == ModalButton::Confirm;
+                self.close_modal(confirmed);
+            }
+            return Ok(());
+        }
+
         // Handle power device clicks in header (row 0)
         if mouse.row == 0 && mouse.kind == MouseEventKind::Down(MouseButton::Left) {
             for (area, device_name) in &self.power_device_click_areas {
@@ -232,9 +567,43 @@ impl App {
                 {
                     // Toggle the power device
                     let device_name = device_name.clone();
-                    self.pending_commands.push(format!("__TOGGLE_POWER__{}", device_name));
+                    self.request_power_toggle(device_name);
+                    return Ok(());
+                }
+            }
+        }
+
+        // Clicking a filter menu row toggles it
+        if self.console_filter_menu.is_some() {
+            let area = self.console_filter_area;
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                && mouse.column >= area.x
+                && mouse.column < area.x + area.width
+                && mouse.row >= area.y
+                && mouse.row < area.y + area.height
+                && let Some(menu) = &mut self.console_filter_menu
+            {
+                let index = (mouse.row - area.y) as usize;
+                if index < menu.items.len() {
+                    menu.state.select(Some(index));
+                    self.toggle_filter_row();
+                }
+            }
+            return Ok(());
+        }
+
+        // Scroll the console back through its history
+        if self.current_tab == Tab::Console {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => {
+                    self.scroll_console(-3);
+                    return Ok(());
+                }
+                MouseEventKind::ScrollDown => {
+                    self.scroll_console(3);
                     return Ok(());
                 }
+                _ => {}
             }
         }
 
@@ -263,10 +632,10 @@ impl App {
                         top_offset += 1;
                     }
                     
-                    // Jobs list is between top_offset and footer_row
-                    if mouse.row >= top_offset && mouse.row < footer_row {
+                    // Jobs list starts below the totals header and the list border
+                    let list_start_row = top_offset + crate::tui::widgets::jobs::HEADER_HEIGHT + 1;
+                    if mouse.row >= list_start_row && mouse.row < footer_row {
                         // Calculate which item was clicked
-                        let list_start_row = top_offset + 1; // +1 for border
                         let clicked_index = (mouse.row - list_start_row) as usize;
                         
                         // Get the current scroll offset from the list state
@@ -285,6 +654,47 @@ impl App {
             }
         }
 
+        // Handle mouse scroll and selection for Files tab
+        if self.current_tab == Tab::Files {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => {
+                    self.file_browser.entries.previous();
+                    self.queue_file_metadata();
+                    return Ok(());
+                }
+                MouseEventKind::ScrollDown => {
+                    self.file_browser.entries.next();
+                    self.queue_file_metadata();
+                    return Ok(());
+                }
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let terminal_size = crossterm::terminal::size().unwrap_or((80, 24));
+                    let footer_row = terminal_size.1.saturating_sub(1);
+
+                    let mut top_offset = 1; // header
+                    if self.panel_visibility.temperature {
+                        top_offset += 1;
+                    }
+                    if self.panel_visibility.position {
+                        top_offset += 1;
+                    }
+
+                    // The list sits left of the details pane, inside its border
+                    let list_start_row = top_offset + 1;
+                    let list_end_column = terminal_size.0.saturating_sub(crate::tui::widgets::files::DETAILS_WIDTH);
+                    if mouse.row >= list_start_row && mouse.row < footer_row && mouse.column < list_end_column {
+                        let index = self.file_browser.entries.state.offset() + (mouse.row - list_start_row) as usize;
+                        if index < self.file_browser.entries.items.len() {
+                            self.file_browser.entries.state.select(Some(index));
+                            self.queue_file_metadata();
+                        }
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+
         // Only handle left mouse button clicks for other interactions
         if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
             // Get terminal size to determine footer position
@@ -311,15 +721,18 @@ impl App {
                             crate::tui::widgets::footer::FooterAction::Tab(tab) => {
                                 self.current_tab = tab;
                                 
-                                // If switching to Jobs tab, fetch jobs
+                                // If switching to Jobs or Files tab, fetch its contents
                                 if tab == Tab::Jobs && self.client.is_some() {
                                     self.pending_commands.push("__FETCH_JOBS__".to_string());
                                 }
+                                if tab == Tab::Files && self.client.is_some() {
+                                    self.pending_commands.push("__FETCH_FILES__".to_string());
+                                }
                             }
                             crate::tui::widgets::footer::FooterAction::Escape => {
-                                // Handle escape - go back to Console (default tab)
+                                // Handle escape - go back to the default tab
                                 if self.current_tab == Tab::Help {
-                                    self.current_tab = Tab::Console;
+                                    self.current_tab = Tab::default();
                                 }
                             }
                             crate::tui::widgets::footer::FooterAction::Quit => {
@@ -364,24 +777,18 @@ impl App {
                         self.pos_input.exit_edit_mode();
                         
                         match element {
-                            crate::tui::widgets::temperatures::TempBarElement::Extruder => {
-                                // Clear other temp/fan editing states
-                                self.fan_edit_target = None;
-                                self.fan_input.exit_edit_mode();
-                                
-                                // Start editing extruder temperature
-                                self.temp_edit_target = Some(TempEditTarget::Extruder);
-                                self.temp_input.value.clear();
-                                self.temp_input.mode = InputMode::Editing;
-                                self.temp_input.cursor_position = 0;
-                            }
-                            crate::tui::widgets::temperatures::TempBarElement::Bed => {
+                            crate::tui::widgets::temperatures::TempBarElement::Heater(index) => {
+                                let Some(heater) = self.printer.temperatures.heaters.get(index) else {
+                                    break;
+                                };
                                 // Clear other temp/fan editing states
                                 self.fan_edit_target = None;
                                 self.fan_input.exit_edit_mode();
                                 
-                                // Start editing bed temperature
-                                self.temp_edit_target = Some(TempEditTarget::Bed);
+                                // Start editing the heater's target
+                                self.temp_edit_target = Some(TempEditTarget {
+                                    heater: heater.object.clone(),
+                                });
                                 self.temp_input.value.clear();
                                 self.temp_input.mode = InputMode::Editing;
                                 self.temp_input.cursor_position = 0;
@@ -466,9 +873,33 @@ impl App {
             return Ok(());
         }
 
+        // An open modal captures every other key
+        if self.modal.is_some() {
+            self.handle_modal_key(key);
+            return Ok(());
+        }
+
         // Handle Esc key
         if key.code == KeyCode::Esc {
-            if self.console_input.mode == InputMode::Editing {
+            if self.preset_picker.is_some() {
+                self.preset_picker = None;
+            } else if self.macro_browser.form.is_some() {
+                self.macro_browser.form = None;
+            } else if self.console_filter_menu.is_some() {
+                if self.filter_input.mode == InputMode::Editing {
+                    self.filter_input.exit_edit_mode();
+                    self.filter_error = None;
+                } else {
+                    self.console_filter_menu = None;
+                }
+            } else if self.console_completion.is_some() {
+                self.console_completion = None;
+            } else if self.console_search.is_some() && self.current_tab == Tab::Console {
+                self.console_search = None;
+            } else if let Some(search) = self.history_search.take() {
+                // Cancel reverse search, restoring what was typed
+                self.set_console_input(search.original);
+            } else if self.console_input.mode == InputMode::Editing && self.current_tab == Tab::Console {
                 self.console_input.exit_edit_mode();
             } else if self.temp_input.mode == InputMode::Editing {
                 // Cancel temperature editing
@@ -482,12 +913,32 @@ impl App {
                 // Cancel position editing
                 self.pos_input.exit_edit_mode();
                 self.pos_edit_target = None;
-            } else {
-                // TODO: Close modals or return to previous view
+            } else if self.mesh_profile_input.mode == InputMode::Editing {
+                self.mesh_profile_input.exit_edit_mode();
+            } else if self.current_tab == Tab::Help {
+                self.current_tab = Tab::default();
             }
             return Ok(());
         }
 
+        // Handle preset picker navigation
+        if self.preset_picker.is_some() {
+            self.handle_preset_picker_key(key);
+            return Ok(());
+        }
+
+        // Handle console filter menu
+        if self.console_filter_menu.is_some() {
+            self.handle_filter_menu_key(key);
+            return Ok(());
+        }
+
+        // Handle the macro parameter form
+        if self.macro_browser.form.is_some() {
+            self.handle_macro_form_key(key);
+            return Ok(());
+        }
+
         // Handle position editing mode
         if self.pos_input.mode == InputMode::Editing {
             self.handle_pos_input(key).await?;
@@ -506,6 +957,61 @@ impl App {
             return Ok(());
         }
 
+        // Handle bed mesh profile name entry
+        if self.mesh_profile_input.mode == InputMode::Editing {
+            self.handle_mesh_profile_input(key);
+            return Ok(());
+        }
+
+        // Console scrollback and search; plain keys that also edit text only
+        // apply while the input isn't being edited
+        if self.current_tab == Tab::Console && self.history_search.is_none() {
+            let editing = self.console_input.mode == InputMode::Editing;
+            let control = key.modifiers.contains(KeyModifiers::CONTROL);
+            if control {
+                match key.code {
+                    KeyCode::Char('f') => {
+                        self.open_filter_menu();
+                        return Ok(());
+                    }
+                    KeyCode::Char('e') => {
+                        self.console_messages.clear_errors();
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+            }
+            if self.handle_console_search_key(key, editing) {
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::PageUp => {
+                    self.scroll_console(-self.console_page());
+                    return Ok(());
+                }
+                KeyCode::PageDown => {
+                    self.scroll_console(self.console_page());
+                    return Ok(());
+                }
+                KeyCode::Home if !editing || control => {
+                    self.scroll_console(isize::MIN / 2);
+                    return Ok(());
+                }
+                KeyCode::End if !editing || control => {
+                    self.console_scroll = None;
+                    return Ok(());
+                }
+                KeyCode::Char('/') if !editing => {
+                    self.console_search = Some(ConsoleSearch {
+                        editing: true,
+                        ..ConsoleSearch::default()
+                    });
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
         // Handle input mode for console
         if self.current_tab == Tab::Console && self.console_input.mode == InputMode::Editing {
             self.handle_console_input(key)?;
@@ -523,6 +1029,20 @@ impl App {
                     self.job_list.next();
                     return Ok(());
                 }
+                KeyCode::Left | KeyCode::PageUp => {
+                    if self.job_page > 0 {
+                        self.job_page -= 1;
+                        self.pending_commands.push("__FETCH_JOBS__".to_string());
+                    }
+                    return Ok(());
+                }
+                KeyCode::Right | KeyCode::PageDown => {
+                    if (self.job_page + 1) * JOB_PAGE_SIZE < self.job_count {
+                        self.job_page += 1;
+                        self.pending_commands.push("__FETCH_JOBS__".to_string());
+                    }
+                    return Ok(());
+                }
                 KeyCode::Char('r') => {
                     // Refresh job list
                     if self.client.is_some() {
@@ -533,16 +1053,28 @@ impl App {
                     }
                     return Ok(());
                 }
+                KeyCode::Char('d') => {
+                    if let Some(selected) = self.job_list.selected() {
+                        self.modal = Some(
+                            Modal::new("Delete Job", ModalAction::DeleteJob(selected.job_id.clone()))
+                                .with_content(vec![
+                                    format!("Remove {} from the print history?", selected.filename),
+                                ]),
+                        );
+                    }
+                    return Ok(());
+                }
                 KeyCode::Enter => {
-                    // Start selected job
+                    // Reprint selected job
                     if let Some(selected) = self.job_list.selected() {
                         let filename = selected.filename.clone();
-                        // Add message and use GCode command to start print
-                        self.console_messages.push(ConsoleMessage::Info(
-                            format!("Starting print: {}", filename)
-                        ));
-                        // Use M23/M24 to start the print via GCode
-                        self.pending_commands.push(format!("SDCARD_PRINT_FILE FILENAME=\"{}\"", filename));
+                        if !selected.exists {
+                            self.console_messages.push(ConsoleMessage::Error(
+                                format!("Cannot reprint {}: file no longer exists", filename)
+                            ));
+                            return Ok(());
+                        }
+                        self.confirm_start_print(filename);
                     }
                     return Ok(());
                 }
@@ -550,101 +1082,351 @@ impl App {
             }
         }
 
-        // Handle normal mode keys
-        if let KeyCode::Char(c) = key.code {
-            // Tab switching
-            if let Some(tab) = Tab::from_key(c) {
-                let old_tab = self.current_tab;
-                self.current_tab = tab;
-                
-                // Fetch jobs when switching to Jobs tab
-                if self.current_tab == Tab::Jobs && old_tab != Tab::Jobs
-                    && self.client.is_some() {
-                        // Queue job fetch
-                        self.pending_commands.push("__FETCH_JOBS__".to_string());
-                    }
-                
-                return Ok(());
-            }
-
-            // Panel toggles (work from all tabs)
-            match c {
-                't' => {
-                    self.panel_visibility.temperature = !self.panel_visibility.temperature;
+        // Handle Macros tab
+        if self.current_tab == Tab::Macros {
+            match key.code {
+                KeyCode::Up => {
+                    self.macro_browser.list.previous();
+                    return Ok(());
                 }
-                'l' => {
-                    self.panel_visibility.position = !self.panel_visibility.position;
+                KeyCode::Down => {
+                    self.macro_browser.list.next();
+                    return Ok(());
                 }
-                'i' => {
-                    // Enter edit mode on console tab
-                    if self.current_tab == Tab::Console {
-                        self.console_input.mode = InputMode::Editing;
+                KeyCode::Enter => {
+                    if let Some(name) = self.macro_browser.list.selected().cloned() {
+                        self.open_macro(&name);
                     }
+                    return Ok(());
                 }
-                // Homing commands on position tab
-                'x' if self.current_tab == Tab::Position => {
-                    if self.client.is_some() {
-                        self.pending_commands.push("G28 X".to_string());
-                        self.console_messages.push(ConsoleMessage::Info(
-                            "Homing X axis...".to_string()
+                KeyCode::Char('*') => {
+                    if let Some(name) = self.macro_browser.list.selected().cloned()
+                        && let Err(e) = self.macro_browser.toggle_favourite(&name, &self.command_catalog)
+                    {
+                        self.add_console_message(ConsoleMessage::Error(
+                            format!("Could not save favourite macros: {}", e)
                         ));
                     }
+                    return Ok(());
                 }
-                'y' if self.current_tab == Tab::Position => {
-                    if self.client.is_some() {
-                        self.pending_commands.push("G28 Y".to_string());
-                        self.console_messages.push(ConsoleMessage::Info(
-                            "Homing Y axis...".to_string()
-                        ));
+                KeyCode::Char(c @ '1'..='9') => {
+                    let number = c.to_digit(10).unwrap_or(0) as usize;
+                    if let Some(name) = self.macro_browser.numbered(number).cloned() {
+                        self.open_macro(&name);
                     }
+                    return Ok(());
                 }
-                'z' if self.current_tab == Tab::Position => {
-                    if self.client.is_some() {
-                        self.pending_commands.push("G28 Z".to_string());
-                        self.console_messages.push(ConsoleMessage::Info(
-                            "Homing Z axis...".to_string()
-                        ));
+                _ => {}
+            }
+        }
+
+        // Handle Bed Mesh tab
+        if self.current_tab == Tab::BedMesh {
+            match key.code {
+                KeyCode::Up => {
+                    self.mesh_profiles.previous();
+                    return Ok(());
+                }
+                KeyCode::Down => {
+                    self.mesh_profiles.next();
+                    return Ok(());
+                }
+                KeyCode::Enter => {
+                    if let Some(name) = self.mesh_profiles.selected().cloned() {
+                        self.queue_command(format!("BED_MESH_PROFILE LOAD={}", name));
                     }
+                    return Ok(());
                 }
-                'a' if self.current_tab == Tab::Position => {
-                    if self.client.is_some() {
-                        self.pending_commands.push("G28".to_string());
-                        self.console_messages.push(ConsoleMessage::Info(
-                            "Homing all axes...".to_string()
+                KeyCode::Char('v') => {
+                    self.mesh_interpolated = !self.mesh_interpolated;
+                    return Ok(());
+                }
+                KeyCode::Char('n') => {
+                    self.confirm_mesh_calibrate();
+                    return Ok(());
+                }
+                KeyCode::Char('s') => {
+                    if !self.printer.bed_mesh.is_loaded() {
+                        self.add_console_message(ConsoleMessage::Error(
+                            "No mesh loaded to save".to_string()
                         ));
+                        return Ok(());
                     }
+                    // Default to overwriting the active profile
+                    let name = match self.printer.bed_mesh.profile_name.as_str() {
+                        "" => "default",
+                        name => name,
+                    };
+                    self.mesh_profile_input.value = name.to_string();
+                    self.mesh_profile_input.enter_edit_mode();
+                    return Ok(());
                 }
-                'q' => {
-                    self.running = false;
+                KeyCode::Char('d') => {
+                    if let Some(name) = self.mesh_profiles.selected().cloned() {
+                        self.modal = Some(
+                            Modal::new(
+                                "Remove Mesh Profile",
+                                ModalAction::SendGcode(vec![format!("BED_MESH_PROFILE REMOVE={}", name)]),
+                            )
+                            .with_content(vec![
+                                format!("Remove the bed mesh profile '{}'?", name),
+                                "Run SAVE_CONFIG afterwards to make it permanent.".to_string(),
+                            ]),
+                        );
+                    }
+                    return Ok(());
                 }
                 _ => {}
             }
         }
 
-        Ok(())
-    }
-
-    /// Handle console input in editing mode
-    fn handle_console_input(&mut self, key: KeyEvent) -> crate::tui::Result<()> {
-        match key.code {
-            KeyCode::Enter => {
-                let command = self.console_input.value.trim().to_string();
-                if !command.is_empty() {
-                    // Add command to history
-                    self.console_messages.push(ConsoleMessage::Command(command.clone()));
-                    
-                    // Add to command history (avoid duplicates at end)
-                    if self.command_history.last() != Some(&command) {
-                        self.command_history.push(command.clone());
+        // Handle Files tab navigation
+        if self.current_tab == Tab::Files {
+            match key.code {
+                KeyCode::Up => {
+                    self.file_browser.entries.previous();
+                    self.queue_file_metadata();
+                    return Ok(());
+                }
+                KeyCode::Down => {
+                    self.file_browser.entries.next();
+                    self.queue_file_metadata();
+                    return Ok(());
+                }
+                KeyCode::Enter | KeyCode::Right => {
+                    let Some(entry) = self.file_browser.selected().cloned() else {
+                        return Ok(());
+                    };
+                    if entry.is_dir {
+                        self.file_browser.enter(&entry.name);
+                        self.pending_commands.push("__FETCH_FILES__".to_string());
+                    } else if key.code == KeyCode::Enter {
+                        let path = self.file_browser.entry_path(&entry);
+                        self.confirm_start_print(path);
                     }
-                    // Keep history to a reasonable size
-                    if self.command_history.len() > 100 {
-                        self.command_history.remove(0);
+                    return Ok(());
+                }
+                KeyCode::Backspace | KeyCode::Left => {
+                    if self.file_browser.leave() {
+                        self.pending_commands.push("__FETCH_FILES__".to_string());
                     }
-                    
-                    // Queue command for sending
+                    return Ok(());
+                }
+                KeyCode::Char('s') => {
+                    self.file_browser.cycle_sort();
+                    return Ok(());
+                }
+                KeyCode::Char('o') => {
+                    self.file_browser.toggle_order();
+                    return Ok(());
+                }
+                KeyCode::Char('r') => {
+                    if self.client.is_some() {
+                        self.pending_commands.push("__FETCH_FILES__".to_string());
+                    }
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        // Handle Position tab jogging
+        if self.current_tab == Tab::Position {
+            let jog = match key.code {
+                KeyCode::Left => Some((0, -1.0)),
+                KeyCode::Right => Some((0, 1.0)),
+                KeyCode::Up => Some((1, 1.0)),
+                KeyCode::Down => Some((1, -1.0)),
+                KeyCode::PageUp => Some((2, 1.0)),
+                KeyCode::PageDown => Some((2, -1.0)),
+                _ => None,
+            };
+            if let Some((axis, direction)) = jog {
+                self.jog(axis, direction);
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    self.jog_step_index = (self.jog_step_index + 1).min(JOG_STEPS.len() - 1);
+                    return Ok(());
+                }
+                KeyCode::Char('-') => {
+                    self.jog_step_index = self.jog_step_index.saturating_sub(1);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        // Handle normal mode keys
+        if let KeyCode::Char(c) = key.code {
+            // Tab switching
+            if let Some(tab) = Tab::from_key(c) {
+                let old_tab = self.current_tab;
+                self.current_tab = tab;
+                
+                // Fetch jobs when switching to Jobs tab
+                if self.current_tab == Tab::Jobs && old_tab != Tab::Jobs
+                    && self.client.is_some() {
+                        // Queue job fetch
+                        self.pending_commands.push("__FETCH_JOBS__".to_string());
+                    }
+                if self.current_tab == Tab::Files && old_tab != Tab::Files
+                    && self.client.is_some() {
+                        self.pending_commands.push("__FETCH_FILES__".to_string());
+                    }
+                
+                return Ok(());
+            }
+
+            // Panel toggles (work from all tabs)
+            match c {
+                't' => {
+                    self.panel_visibility.temperature = !self.panel_visibility.temperature;
+                }
+                'l' => {
+                    self.panel_visibility.position = !self.panel_visibility.position;
+                }
+                'P' => {
+                    self.open_preset_picker();
+                }
+                'C' => {
+                    self.request_preset_choice(PresetChoice::Cooldown);
+                }
+                ' ' => {
+                    self.confirm_pause_resume();
+                }
+                'X' => {
+                    self.confirm_cancel_print();
+                }
+                'i' if self.current_tab == Tab::Console => {
+                    // Enter edit mode on console tab
+                    self.console_input.mode = InputMode::Editing;
+                }
+                // Homing commands on position tab
+                'x' if self.current_tab == Tab::Position && self.client.is_some() => {
+                    self.pending_commands.push("G28 X".to_string());
+                    self.console_messages.push(ConsoleMessage::Info(
+                        "Homing X axis...".to_string()
+                    ));
+                }
+                'y' if self.current_tab == Tab::Position && self.client.is_some() => {
+                    self.pending_commands.push("G28 Y".to_string());
+                    self.console_messages.push(ConsoleMessage::Info(
+                        "Homing Y axis...".to_string()
+                    ));
+                }
+                'z' if self.current_tab == Tab::Position && self.client.is_some() => {
+                    self.pending_commands.push("G28 Z".to_string());
+                    self.console_messages.push(ConsoleMessage::Info(
+                        "Homing Z axis...".to_string()
+                    ));
+                }
+                // Series toggles and window on the graphs tab
+                '1'..='9' if self.current_tab == Tab::Graphs => {
+                    let index = c.to_digit(10).unwrap_or(1) as usize - 1;
+                    self.temperature_history.toggle(index);
+                }
+                'w' if self.current_tab == Tab::Graphs => {
+                    self.temperature_history.cycle_window();
+                }
+                'a' if self.current_tab == Tab::Position && self.client.is_some() => {
+                    self.pending_commands.push("G28".to_string());
+                    self.console_messages.push(ConsoleMessage::Info(
+                        "Homing all axes...".to_string()
+                    ));
+                }
+                'q' => {
+                    self.running = false;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queue a relative move of one jog step along `axis` (0 = X) in `direction`
+    ///
+    /// The move is refused on unhomed axes and clamped to the axis limits.
+    fn jog(&mut self, axis: usize, direction: f64) {
+        if self.client.is_none() {
+            self.jog_message = Some(ConsoleMessage::Error("Not connected to printer".to_string()));
+            return;
+        }
+        let letter = AXES[axis];
+        let toolhead = &self.printer.toolhead;
+        if !toolhead.is_homed(&letter.to_lowercase()) {
+            self.jog_message = Some(ConsoleMessage::Error(format!(
+                "{} is not homed; press {} to home it",
+                letter,
+                letter.to_lowercase()
+            )));
+            return;
+        }
+
+        let current = toolhead.position[axis];
+        let mut target = current + direction * JOG_STEPS[self.jog_step_index];
+        if let Some((min, max)) = toolhead.limits(axis) {
+            target = target.clamp(min, max);
+        }
+        let distance = target - current;
+        if distance.abs() < 0.0005 {
+            self.jog_message = Some(ConsoleMessage::Error(format!(
+                "{} is at its limit ({:.2}mm)",
+                letter, current
+            )));
+            return;
+        }
+
+        // Relative mode only for this move; RESTORE_GCODE_STATE puts everything back
+        let script = [
+            "SAVE_GCODE_STATE NAME=moonriver_jog".to_string(),
+            "G91".to_string(),
+            format!("G1 {}{:.3} F{:.0}", letter, distance, self.config.jog.feed(axis)),
+            "G90".to_string(),
+            "RESTORE_GCODE_STATE NAME=moonriver_jog".to_string(),
+        ]
+        .join("\n");
+        self.pending_commands.push(script);
+
+        // Assume the move happens so quick repeated jogs stay within the limits;
+        // the next status update replaces this with Klipper's own position
+        self.printer.toolhead.position[axis] = target;
+        self.jog_message = Some(ConsoleMessage::Info(format!(
+            "Jog {}{:+.3} to {:.2}mm",
+            letter, distance, target
+        )));
+    }
+
+    /// Handle console input in editing mode
+    fn handle_console_input(&mut self, key: KeyEvent) -> crate::tui::Result<()> {
+        if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.search_history_backward();
+            return Ok(());
+        }
+        if self.history_search.is_some() && !self.handle_history_search_key(key) {
+            return Ok(());
+        }
+        if self.console_completion.is_some() && self.handle_completion_key(key) {
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Tab => self.complete_console_input(),
+            KeyCode::Enter => {
+                let command = self.console_input.value.trim().to_string();
+                if !command.is_empty() {
+                    // Add command to history, following the output it produces
+                    self.add_console_message(ConsoleMessage::Command(command.clone()));
+                    self.command_history.add(&command);
+
+                    // Queue command for sending, expanding any aliases
                     if self.client.is_some() {
-                        self.pending_commands.push(command);
+                        match self.aliases.expand(&command) {
+                            Ok(commands) => self.queue_console_commands(commands),
+                            Err(e) => self.console_messages.push(ConsoleMessage::Error(e.to_string())),
+                        }
                     } else {
                         self.console_messages.push(ConsoleMessage::Error(
                             "Not connected to printer".to_string()
@@ -656,55 +1438,201 @@ impl App {
                 }
             }
             KeyCode::Up => {
-                // Navigate backwards through history
-                if !self.command_history.is_empty() {
-                    if let Some(idx) = self.history_index {
-                        if idx > 0 {
-                            self.history_index = Some(idx - 1);
-                            self.console_input.value = self.command_history[idx - 1].clone();
-                            self.console_input.cursor_position = self.console_input.value.len() as u16;
-                        }
-                    } else {
-                        // Start at the end of history
-                        let idx = self.command_history.len() - 1;
-                        self.history_index = Some(idx);
-                        self.console_input.value = self.command_history[idx].clone();
-                        self.console_input.cursor_position = self.console_input.value.len() as u16;
+                // Navigate backwards through entries starting with what was typed
+                let before = match self.history_index {
+                    Some(idx) => idx,
+                    None => {
+                        self.history_prefix = self.console_input.value.clone();
+                        self.command_history.len()
                     }
+                };
+                if let Some(idx) = self.command_history.search_prefix(&self.history_prefix, before) {
+                    self.history_index = Some(idx);
+                    let entry = self.command_history.get(idx).unwrap_or("").to_string();
+                    self.set_console_input(entry);
                 }
             }
             KeyCode::Down => {
                 // Navigate forwards through history
                 if let Some(idx) = self.history_index {
-                    if idx < self.command_history.len() - 1 {
-                        self.history_index = Some(idx + 1);
-                        self.console_input.value = self.command_history[idx + 1].clone();
-                        self.console_input.cursor_position = self.console_input.value.len() as u16;
-                    } else {
-                        // At the end, clear input
-                        self.history_index = None;
-                        self.console_input.clear();
+                    match self.command_history.search_prefix_forward(&self.history_prefix, idx) {
+                        Some(next) => {
+                            self.history_index = Some(next);
+                            let entry = self.command_history.get(next).unwrap_or("").to_string();
+                            self.set_console_input(entry);
+                        }
+                        None => {
+                            // At the end, restore what was typed
+                            self.history_index = None;
+                            self.set_console_input(self.history_prefix.clone());
+                        }
                     }
                 }
             }
             KeyCode::Char(c) => {
-                self.console_input.value.push(c);
-                self.console_input.cursor_position += 1;
+                self.console_input.insert(c);
                 self.history_index = None; // Stop browsing history when typing
+                self.refresh_completion();
             }
             KeyCode::Backspace => {
-                if !self.console_input.value.is_empty() {
-                    self.console_input.value.pop();
-                    self.console_input.cursor_position = 
-                        self.console_input.cursor_position.saturating_sub(1);
-                    self.history_index = None; // Stop browsing history when editing
-                }
+                self.console_input.backspace();
+                self.history_index = None; // Stop browsing history when editing
+                self.refresh_completion();
+            }
+            KeyCode::Delete => {
+                self.console_input.delete();
+                self.history_index = None;
             }
+            KeyCode::Left => self.console_input.move_left(),
+            KeyCode::Right => self.console_input.move_right(),
+            KeyCode::Home => self.console_input.move_home(),
+            KeyCode::End => self.console_input.move_end(),
             _ => {}
         }
         Ok(())
     }
 
+    /// Complete the word before the cursor
+    ///
+    /// A single candidate is inserted straight away. With several, the text
+    /// they all share is filled in and the rest are offered in a popup.
+    fn complete_console_input(&mut self) {
+        let cursor = self.console_input.byte_index();
+        let (start, candidates) = self.command_catalog.complete(&self.console_input.value[..cursor]);
+        match candidates.as_slice() {
+            [] => {}
+            [only] => {
+                let text = self.completion_text(only);
+                self.console_input.replace_before_cursor(start, &text);
+            }
+            _ => {
+                let common = common_prefix(&candidates);
+                if common.chars().count() > self.console_input.value[start..cursor].chars().count() {
+                    self.console_input.replace_before_cursor(start, &common);
+                }
+                self.console_completion = Some(CompletionMenu {
+                    start,
+                    candidates: StatefulList::with_items(candidates),
+                });
+            }
+        }
+        self.history_index = None;
+    }
+
+    /// Keep an open completion popup in step with what's typed, closing it when nothing matches
+    fn refresh_completion(&mut self) {
+        if self.console_completion.is_none() {
+            return;
+        }
+        let cursor = self.console_input.byte_index();
+        let (start, candidates) = self.command_catalog.complete(&self.console_input.value[..cursor]);
+        self.console_completion = (!candidates.is_empty()).then(|| CompletionMenu {
+            start,
+            candidates: StatefulList::with_items(candidates),
+        });
+    }
+
+    /// Handle a key while the completion popup is open
+    /// Returns true if the key was used by the popup
+    fn handle_completion_key(&mut self, key: KeyEvent) -> bool {
+        let Some(menu) = &mut self.console_completion else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Tab | KeyCode::Down => menu.candidates.next(),
+            KeyCode::BackTab | KeyCode::Up => menu.candidates.previous(),
+            KeyCode::Enter => {
+                let start = menu.start;
+                if let Some(candidate) = menu.candidates.selected().cloned() {
+                    let text = self.completion_text(&candidate);
+                    self.console_input.replace_before_cursor(start, &text);
+                }
+                self.console_completion = None;
+            }
+            // Typing narrows the candidates; anything else closes the popup
+            KeyCode::Char(_) | KeyCode::Backspace => return false,
+            _ => {
+                self.console_completion = None;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Text to insert for a candidate: a finished word at the end of the line gets a trailing space
+    fn completion_text(&self, candidate: &Completion) -> String {
+        let at_end = self.console_input.byte_index() == self.console_input.value.len();
+        if at_end && !candidate.replacement.ends_with('=') {
+            format!("{} ", candidate.replacement)
+        } else {
+            candidate.replacement.clone()
+        }
+    }
+
+    /// Start a reverse search, or jump to the next older match
+    fn search_history_backward(&mut self) {
+        let Some(search) = &mut self.history_search else {
+            self.history_search = Some(HistorySearch {
+                original: self.console_input.value.clone(),
+                ..HistorySearch::default()
+            });
+            return;
+        };
+        if search.query.is_empty() {
+            return;
+        }
+        let before = search.index.unwrap_or(self.command_history.len());
+        if let Some(idx) = self.command_history.search(&search.query, before) {
+            search.index = Some(idx);
+            let entry = self.command_history.get(idx).unwrap_or("").to_string();
+            self.set_console_input(entry);
+        }
+    }
+
+    /// Handle a key during reverse search
+    /// Returns true if the key should also be handled as normal input
+    fn handle_history_search_key(&mut self, key: KeyEvent) -> bool {
+        let Some(search) = &mut self.history_search else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Char(c) => search.query.push(c),
+            KeyCode::Backspace => {
+                search.query.pop();
+            }
+            // Enter runs the match, anything else accepts it for editing
+            KeyCode::Enter => {
+                self.history_search = None;
+                return true;
+            }
+            _ => {
+                self.history_search = None;
+                return false;
+            }
+        }
+
+        // Search again from the newest entry as the query changed
+        let found = if search.query.is_empty() {
+            None
+        } else {
+            self.command_history.search(&search.query, self.command_history.len())
+        };
+        search.index = found;
+        let text = match found {
+            Some(idx) => self.command_history.get(idx).unwrap_or("").to_string(),
+            None if search.query.is_empty() => search.original.clone(),
+            None => return false,
+        };
+        self.set_console_input(text);
+        false
+    }
+
+    fn set_console_input(&mut self, value: String) {
+        self.console_input.cursor_position = value.chars().count() as u16;
+        self.console_input.value = value;
+        self.console_completion = None;
+    }
+
     /// Handle temperature input
     async fn handle_temp_input(&mut self, key: KeyEvent) -> crate::tui::Result<()> {
         match key.code {
@@ -712,41 +1640,8 @@ impl App {
                 // Parse and set temperature
                 if let Ok(temp) = self.temp_input.value.trim().parse::<f64>() {
                     if (0.0..=300.0).contains(&temp) {
-                        let gcode = match self.temp_edit_target {
-                            Some(TempEditTarget::Extruder) => format!("M104 S{}", temp as u32),
-                            Some(TempEditTarget::Bed) => format!("M140 S{}", temp as u32),
-                            None => String::new(),
-                        };
-                        
-                        if !gcode.is_empty() {
-                            self.add_console_message(ConsoleMessage::Command(gcode.clone()));
-                            
-                            if self.client.is_some() {
-                                // Optimistically update local state for immediate UI feedback
-                                match self.temp_edit_target {
-                                    Some(TempEditTarget::Extruder) => {
-                                        self.printer.temperatures.extruder.target = temp;
-                                    }
-                                    Some(TempEditTarget::Bed) => {
-                                        self.printer.temperatures.bed.target = temp;
-                                    }
-                                    None => {}
-                                }
-                                
-                                self.pending_commands.push(gcode);
-                                let heater_name = match self.temp_edit_target {
-                                    Some(TempEditTarget::Extruder) => "Extruder",
-                                    Some(TempEditTarget::Bed) => "Bed",
-                                    None => "",
-                                };
-                                self.add_console_message(ConsoleMessage::Info(
-                                    format!("{} target set to {}°C", heater_name, temp as u32)
-                                ));
-                            } else {
-                                self.add_console_message(ConsoleMessage::Error(
-                                    "Not connected to printer".to_string()
-                                ));
-                            }
+                        if let Some(target) = self.temp_edit_target.take() {
+                            self.set_heater_target(&target.heater, temp);
                         }
                     } else {
                         self.add_console_message(ConsoleMessage::Error(
@@ -767,16 +1662,523 @@ impl App {
                 self.temp_input.value.push(c);
                 self.temp_input.cursor_position += 1;
             }
-            KeyCode::Backspace => {
-                if !self.temp_input.value.is_empty() {
-                    self.temp_input.value.pop();
-                    self.temp_input.cursor_position = 
-                        self.temp_input.cursor_position.saturating_sub(1);
+            KeyCode::Backspace if !self.temp_input.value.is_empty() => {
+                self.temp_input.value.pop();
+                self.temp_input.cursor_position = 
+                    self.temp_input.cursor_position.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Send a heater target, optimistically updating local state for immediate UI feedback
+    fn set_heater_target(&mut self, object: &str, temp: f64) {
+        let label = match self.printer.temperatures.heater(object) {
+            Some(heater) => heater.label(),
+            None => {
+                self.add_console_message(ConsoleMessage::Error(
+                    format!("Unknown heater '{}'", object)
+                ));
+                return;
+            }
+        };
+        let gcode = crate::config::heater_gcode(object, temp);
+
+        self.add_console_message(ConsoleMessage::Command(gcode.clone()));
+
+        if self.client.is_some() {
+            if let Some(heater) = self.printer.temperatures.heater_mut(object) {
+                heater.target = temp;
+            }
+
+            self.pending_commands.push(gcode);
+            self.add_console_message(ConsoleMessage::Info(
+                format!("{} target set to {}°C", label, temp as u32)
+            ));
+        } else {
+            self.add_console_message(ConsoleMessage::Error(
+                "Not connected to printer".to_string()
+            ));
+        }
+    }
+
+    /// Queue a command from one of the tabs, echoing it to the console
+    fn queue_command(&mut self, gcode: String) {
+        if self.client.is_none() {
+            self.add_console_message(ConsoleMessage::Error(
+                "Not connected to printer".to_string()
+            ));
+            return;
+        }
+        self.add_console_message(ConsoleMessage::Command(gcode.clone()));
+        self.pending_commands.push(gcode);
+    }
+
+    /// Run a macro, first asking for its parameters if it takes any
+    fn open_macro(&mut self, name: &str) {
+        let description = macro_browser::description(&self.command_catalog, name);
+        let form = match self.command_catalog.macro_info.get(name) {
+            Some(info) => MacroForm::new(info, description),
+            // Not in the config (yet); run it by name
+            None => MacroForm::new(
+                &MacroInfo {
+                    name: name.to_string(),
+                    ..Default::default()
+                },
+                description,
+            ),
+        };
+        if form.fields.is_empty() {
+            self.queue_command(form.command());
+        } else {
+            self.macro_browser.form = Some(form);
+        }
+    }
+
+    /// Handle keys while the macro parameter form is open
+    fn handle_macro_form_key(&mut self, key: KeyEvent) {
+        let Some(form) = &mut self.macro_browser.form else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let command = form.command();
+                self.macro_browser.form = None;
+                self.queue_command(command);
+            }
+            KeyCode::Tab | KeyCode::Down => form.next_field(),
+            KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+            _ => {
+                let Some(input) = form.selected_input() else {
+                    return;
+                };
+                match key.code {
+                    KeyCode::Char(c) => input.insert(c),
+                    KeyCode::Backspace => input.backspace(),
+                    KeyCode::Delete => input.delete(),
+                    KeyCode::Left => input.move_left(),
+                    KeyCode::Right => input.move_right(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Ask before probing a new mesh, homing first if needed
+    fn confirm_mesh_calibrate(&mut self) {
+        if self.client.is_none() {
+            self.add_console_message(ConsoleMessage::Error(
+                "Not connected to printer".to_string()
+            ));
+            return;
+        }
+        let toolhead = &self.printer.toolhead;
+        let homed = ["x", "y", "z"].iter().all(|axis| toolhead.is_homed(axis));
+        let mut commands = Vec::new();
+        let mut content = vec!["Probe the bed and load the new mesh?".to_string()];
+        if !homed {
+            commands.push("G28".to_string());
+            content.push("The printer is not homed; it will home all axes first.".to_string());
+        }
+        commands.push("BED_MESH_CALIBRATE".to_string());
+        content.push("Press s afterwards to save it as a profile.".to_string());
+
+        self.modal = Some(
+            Modal::new("Calibrate Bed Mesh", ModalAction::SendGcode(commands))
+                .with_content(content),
+        );
+    }
+
+    /// Handle keys while entering a name to save the bed mesh under
+    fn handle_mesh_profile_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                let name = self.mesh_profile_input.value.trim().to_string();
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    self.add_console_message(ConsoleMessage::Error(
+                        "Profile names cannot be empty or contain spaces".to_string()
+                    ));
+                    return;
+                }
+                self.mesh_profile_input.exit_edit_mode();
+                self.queue_command(format!("BED_MESH_PROFILE SAVE={}", name));
+            }
+            KeyCode::Char(c) => self.mesh_profile_input.insert(c),
+            KeyCode::Backspace => self.mesh_profile_input.backspace(),
+            KeyCode::Delete => self.mesh_profile_input.delete(),
+            KeyCode::Left => self.mesh_profile_input.move_left(),
+            KeyCode::Right => self.mesh_profile_input.move_right(),
+            _ => {}
+        }
+    }
+
+    /// Rebuild the profile list when Klipper's changes, keeping the selection
+    fn sync_mesh_profiles(&mut self) {
+        let profiles = &self.printer.bed_mesh.profiles;
+        if *profiles == self.mesh_profiles.items {
+            return;
+        }
+        let selected = self.mesh_profiles.selected().cloned();
+        self.mesh_profiles = StatefulList::with_items(profiles.clone());
+        if let Some(index) = selected.and_then(|name| profiles.iter().position(|p| *p == name)) {
+            self.mesh_profiles.select(Some(index));
+        }
+    }
+
+    /// Open the material preset picker
+    fn open_preset_picker(&mut self) {
+        let mut choices: Vec<PresetChoice> = self.config.presets
+            .keys()
+            .map(|name| PresetChoice::Preset(name.clone()))
+            .collect();
+        choices.push(PresetChoice::Cooldown);
+        self.preset_picker = Some(StatefulList::with_items(choices));
+    }
+
+    /// Handle keys while the preset picker is open
+    fn handle_preset_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = &mut self.preset_picker else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => picker.previous(),
+            KeyCode::Down | KeyCode::Char('j') => picker.next(),
+            KeyCode::Enter => {
+                if let Some(choice) = picker.selected().cloned() {
+                    self.preset_picker = None;
+                    self.request_preset_choice(choice);
+                }
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
+                // Number keys pick an entry directly
+                let index = c.to_digit(10).unwrap_or(0) as usize - 1;
+                if let Some(choice) = picker.items.get(index).cloned() {
+                    self.preset_picker = None;
+                    self.request_preset_choice(choice);
+                }
+            }
+            KeyCode::Char('q') => self.preset_picker = None,
+            _ => {}
+        }
+    }
+
+    /// Open the console filter menu
+    fn open_filter_menu(&mut self) {
+        let mut rows: Vec<FilterRow> = ConsoleCategory::ALL.into_iter().map(FilterRow::Category).collect();
+        rows.push(FilterRow::Pattern);
+        self.console_filter_menu = Some(StatefulList::with_items(rows));
+        self.filter_error = None;
+    }
+
+    /// Handle keys while the console filter menu is open
+    fn handle_filter_menu_key(&mut self, key: KeyEvent) {
+        // Editing the regex takes every key until Enter or Esc
+        if self.filter_input.mode == InputMode::Editing {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = self.filter_input.value.trim().to_string();
+                    match self.console_messages.filter.set_pattern(&text) {
+                        Ok(()) => {
+                            self.console_messages.filter.pattern_enabled = true;
+                            self.filter_input.exit_edit_mode();
+                            self.filter_error = None;
+                        }
+                        Err(e) => self.filter_error = Some(e.to_string()),
+                    }
+                }
+                KeyCode::Char(c) => {
+                    self.filter_input.value.push(c);
+                    self.filter_input.cursor_position += 1;
+                }
+                KeyCode::Backspace => {
+                    self.filter_input.value.pop();
+                    self.filter_input.cursor_position = self.filter_input.cursor_position.saturating_sub(1);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let Some(menu) = &mut self.console_filter_menu else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => menu.previous(),
+            KeyCode::Down | KeyCode::Char('j') => menu.next(),
+            KeyCode::Char(' ') => self.toggle_filter_row(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if menu.selected() == Some(&FilterRow::Pattern) {
+                    self.filter_input.value = self.console_messages.filter.pattern_text.clone();
+                    self.filter_input.cursor_position = self.filter_input.value.chars().count() as u16;
+                    self.filter_input.mode = InputMode::Editing;
+                } else {
+                    self.toggle_filter_row();
+                }
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
+                // Number keys toggle a row directly
+                let index = c.to_digit(10).unwrap_or(0) as usize - 1;
+                if index < menu.items.len() {
+                    menu.state.select(Some(index));
+                    self.toggle_filter_row();
+                }
+            }
+            KeyCode::Char('q') => self.console_filter_menu = None,
+            _ => {}
+        }
+    }
+
+    /// Toggle the selected filter menu row
+    fn toggle_filter_row(&mut self) {
+        let Some(row) = self.console_filter_menu.as_ref().and_then(|menu| menu.selected().copied()) else {
+            return;
+        };
+        let filter = &mut self.console_messages.filter;
+        match row {
+            FilterRow::Category(category) => filter.toggle(category),
+            FilterRow::Pattern => filter.pattern_enabled = !filter.pattern_enabled,
+        }
+    }
+
+    /// Heater objects Klipper reported in `heaters.available_heaters`
+    fn available_heaters(&self) -> Vec<String> {
+        self.printer
+            .temperatures
+            .heaters
+            .iter()
+            .map(|heater| heater.object.clone())
+            .collect()
+    }
+
+    /// Apply a preset or cool down, asking first while a print is running
+    fn request_preset_choice(&mut self, choice: PresetChoice) {
+        if self.client.is_none() || !self.print_active() {
+            self.apply_preset_choice(choice);
+            return;
+        }
+
+        let filename = self.printer.print_stats.filename.clone();
+        let modal = match choice {
+            PresetChoice::Preset(name) => {
+                let Some((_, preset)) = self.config.find_preset(&name) else {
+                    self.add_console_message(ConsoleMessage::Error(
+                        format!("Unknown preset '{}'", name)
+                    ));
+                    return;
+                };
+                let (commands, _) = preset.to_gcode(&self.available_heaters());
+                Modal::new("Apply Preset", ModalAction::SendGcode(commands)).with_content(vec![
+                    format!("Apply preset {} while {} is printing?", name, filename),
+                    "The running print's temperatures will change.".to_string(),
+                ])
+            }
+            PresetChoice::Cooldown => Modal::new(
+                "Cool Down",
+                ModalAction::SendGcode(vec![crate::config::COOLDOWN_GCODE.to_string()]),
+            )
+            .with_content(vec![
+                format!("Turn off all heaters while {} is printing?", filename),
+                "The print will fail.".to_string(),
+            ])
+            .with_buttons("Turn off", "Keep heating"),
+        };
+        self.modal = Some(modal);
+    }
+
+    /// Apply a material preset or cool down all heaters
+    fn apply_preset_choice(&mut self, choice: PresetChoice) {
+        if self.client.is_none() {
+            self.add_console_message(ConsoleMessage::Error(
+                "Not connected to printer".to_string()
+            ));
+            return;
+        }
+
+        match choice {
+            PresetChoice::Preset(name) => {
+                let Some((_, preset)) = self.config.find_preset(&name) else {
+                    self.add_console_message(ConsoleMessage::Error(
+                        format!("Unknown preset '{}'", name)
+                    ));
+                    return;
+                };
+                let preset = preset.clone();
+
+                self.add_console_message(ConsoleMessage::Info(
+                    format!("Applying preset {}", name)
+                ));
+                let (targets, missing) = preset.targets(&self.available_heaters());
+                for role in missing {
+                    self.add_console_message(ConsoleMessage::Info(
+                        format!("Skipping {}: no such heater on this printer", role)
+                    ));
+                }
+                for (object, temp) in targets {
+                    self.set_heater_target(&object, temp);
+                }
+            }
+            PresetChoice::Cooldown => {
+                let gcode = crate::config::COOLDOWN_GCODE.to_string();
+                self.add_console_message(ConsoleMessage::Command(gcode.clone()));
+                self.pending_commands.push(gcode);
+
+                for heater in &mut self.printer.temperatures.heaters {
+                    heater.target = 0.0;
                 }
+                self.add_console_message(ConsoleMessage::Info(
+                    "All heaters turned off".to_string()
+                ));
+            }
+        }
+    }
+
+    /// Handle keys while a modal is open
+    fn handle_modal_key(&mut self, key: KeyEvent) {
+        let Some(modal) = &mut self.modal else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => modal.toggle_focus(),
+            KeyCode::Enter => {
+                let confirmed = modal.focus == ModalButton::Confirm;
+                self.close_modal(confirmed);
             }
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.close_modal(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.close_modal(false),
             _ => {}
         }
-        Ok(())
+    }
+
+    /// Close the modal, carrying out its action if it was confirmed
+    fn close_modal(&mut self, confirmed: bool) {
+        let Some(modal) = self.modal.take() else {
+            return;
+        };
+        self.modal_buttons.clear();
+        if !confirmed {
+            return;
+        }
+
+        match modal.action {
+            ModalAction::StartPrint(filename) => {
+                self.pending_commands.push(format!("__START_PRINT__{}", filename));
+            }
+            ModalAction::PausePrint => self.pending_commands.push("__PRINT_ACTION__pause".to_string()),
+            ModalAction::ResumePrint => self.pending_commands.push("__PRINT_ACTION__resume".to_string()),
+            ModalAction::CancelPrint => self.pending_commands.push("__PRINT_ACTION__cancel".to_string()),
+            ModalAction::TogglePower(name) => {
+                self.pending_commands.push(format!("__TOGGLE_POWER__{}", name));
+            }
+            ModalAction::DeleteJob(job_id) => {
+                self.pending_commands.push(format!("__DELETE_JOB__{}", job_id));
+            }
+            ModalAction::SendGcode(commands) => self.pending_commands.extend(commands),
+        }
+    }
+
+    /// Whether a print is running or paused
+    fn print_active(&self) -> bool {
+        matches!(self.printer.print_stats.state.as_str(), "printing" | "paused")
+    }
+
+    /// Ask before starting a print of `filename`, relative to the gcodes root
+    fn confirm_start_print(&mut self, filename: String) {
+        if self.client.is_none() {
+            self.add_console_message(ConsoleMessage::Error("Not connected to printer".to_string()));
+            return;
+        }
+        if self.print_active() {
+            self.add_console_message(ConsoleMessage::Error(
+                format!("Cannot print {}: a print is already running", filename)
+            ));
+            return;
+        }
+
+        let mut content = vec![format!("Start printing {}?", filename)];
+        if let Some(estimate) = self.file_browser.metadata.get(&filename).and_then(|m| m.estimated_time) {
+            content.push(format!(
+                "Estimated time {}",
+                crate::format::format_duration(estimate)
+            ));
+        }
+        self.modal = Some(
+            Modal::new("Start Print", ModalAction::StartPrint(filename))
+                .with_content(content)
+                .with_buttons("Print", "Cancel"),
+        );
+    }
+
+    /// Ask before pausing the running print, or resuming a paused one
+    fn confirm_pause_resume(&mut self) {
+        let filename = self.printer.print_stats.filename.clone();
+        let modal = match self.printer.print_stats.state.as_str() {
+            "printing" => Modal::new("Pause Print", ModalAction::PausePrint)
+                .with_content(vec![format!("Pause {}?", filename)]),
+            "paused" => Modal::new("Resume Print", ModalAction::ResumePrint)
+                .with_content(vec![format!("Resume {}?", filename)]),
+            _ => {
+                self.add_console_message(ConsoleMessage::Error("No print is running".to_string()));
+                return;
+            }
+        };
+        self.modal = Some(modal);
+    }
+
+    /// Ask before cancelling the running print
+    fn confirm_cancel_print(&mut self) {
+        if !self.print_active() {
+            self.add_console_message(ConsoleMessage::Error("No print is running".to_string()));
+            return;
+        }
+        self.modal = Some(
+            Modal::new("Cancel Print", ModalAction::CancelPrint)
+                .with_content(vec![
+                    format!("Cancel {}?", self.printer.print_stats.filename),
+                    "The print can't be resumed afterwards.".to_string(),
+                ])
+                .with_buttons("Cancel print", "Keep printing"),
+        );
+    }
+
+    /// Toggle a power device, asking first before switching one off mid-print
+    fn request_power_toggle(&mut self, name: String) {
+        let is_on = self.printer.power_devices.iter().any(|d| d.name == name && d.status == "on");
+        if is_on && self.print_active() {
+            self.modal = Some(
+                Modal::new("Power Off", ModalAction::TogglePower(name.clone()))
+                    .with_content(vec![
+                        format!("Turn off {} while a print is running?", name),
+                    ]),
+            );
+        } else {
+            self.pending_commands.push(format!("__TOGGLE_POWER__{}", name));
+        }
+    }
+
+    /// Queue commands typed in the console, confirming any that restart Klipper
+    fn queue_console_commands(&mut self, commands: Vec<String>) {
+        let restarts: Vec<String> = commands
+            .iter()
+            .filter_map(|command| command.split_whitespace().next())
+            .map(|word| word.to_uppercase())
+            .filter(|word| CONFIRM_COMMANDS.contains(&word.as_str()))
+            .collect();
+        if restarts.is_empty() {
+            self.pending_commands.extend(commands);
+            return;
+        }
+
+        let mut content = vec![format!("{} restarts Klipper.", restarts.join(" and "))];
+        if self.print_active() {
+            content.push("The running print will be lost!".to_string());
+        }
+        content.push("Send it?".to_string());
+        self.modal = Some(
+            Modal::new("Restart Klipper", ModalAction::SendGcode(commands)).with_content(content),
+        );
     }
 
     /// Handle fan speed input
@@ -829,19 +2231,15 @@ impl App {
                 self.fan_input.exit_edit_mode();
                 self.fan_edit_target = None;
             }
-            KeyCode::Char(c) if c.is_ascii_digit() => {
+            KeyCode::Char(c) if c.is_ascii_digit() && self.fan_input.value.len() < 3 => {
                 // Only allow up to 3 digits (max 100)
-                if self.fan_input.value.len() < 3 {
-                    self.fan_input.value.push(c);
-                    self.fan_input.cursor_position += 1;
-                }
+                self.fan_input.value.push(c);
+                self.fan_input.cursor_position += 1;
             }
-            KeyCode::Backspace => {
-                if !self.fan_input.value.is_empty() {
-                    self.fan_input.value.pop();
-                    self.fan_input.cursor_position = 
-                        self.fan_input.cursor_position.saturating_sub(1);
-                }
+            KeyCode::Backspace if !self.fan_input.value.is_empty() => {
+                self.fan_input.value.pop();
+                self.fan_input.cursor_position = 
+                    self.fan_input.cursor_position.saturating_sub(1);
             }
             _ => {}
         }
@@ -855,12 +2253,13 @@ impl App {
                 // Parse and move to position
                 if let Ok(pos) = self.pos_input.value.trim().parse::<f64>() {
                     // Determine valid range based on axis
-                    let (min, max, axis_letter) = match self.pos_edit_target {
-                        Some(PosEditTarget::X) => (0.0, 400.0, "X"),
-                        Some(PosEditTarget::Y) => (0.0, 400.0, "Y"),
-                        Some(PosEditTarget::Z) => (0.0, 400.0, "Z"),
-                        None => (0.0, 0.0, ""),
+                    let (axis, axis_letter) = match self.pos_edit_target {
+                        Some(PosEditTarget::X) => (0, "X"),
+                        Some(PosEditTarget::Y) => (1, "Y"),
+                        Some(PosEditTarget::Z) => (2, "Z"),
+                        None => (0, ""),
                     };
+                    let (min, max) = self.printer.toolhead.limits(axis).unwrap_or((0.0, 400.0));
                     
                     if pos >= min && pos <= max && !axis_letter.is_empty() {
                         let gcode = format!("G0 {}{:.2} F3000", axis_letter, pos);
@@ -906,12 +2305,10 @@ impl App {
                 self.pos_input.value.push('-');
                 self.pos_input.cursor_position += 1;
             }
-            KeyCode::Backspace => {
-                if !self.pos_input.value.is_empty() {
-                    self.pos_input.value.pop();
-                    self.pos_input.cursor_position = 
-                        self.pos_input.cursor_position.saturating_sub(1);
-                }
+            KeyCode::Backspace if !self.pos_input.value.is_empty() => {
+                self.pos_input.value.pop();
+                self.pos_input.cursor_position = 
+                    self.pos_input.cursor_position.saturating_sub(1);
             }
             _ => {}
         }
@@ -931,6 +2328,53 @@ impl App {
                         format!("Error fetching jobs: {}", e)
                     ));
                 }
+            } else if self.pending_commands[i] == "__FETCH_FILES__" {
+                self.pending_commands.remove(i);
+                if let Err(e) = self.fetch_files().await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Error fetching files: {}", e)
+                    ));
+                }
+            } else if self.pending_commands[i].starts_with("__FETCH_METADATA__") {
+                let cmd = self.pending_commands.remove(i);
+                let path = cmd.strip_prefix("__FETCH_METADATA__").unwrap_or("");
+                if let Err(e) = self.fetch_file_metadata(path).await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Error fetching metadata: {}", e)
+                    ));
+                }
+            } else if self.pending_commands[i].starts_with("__FETCH_THUMBNAIL__") {
+                let cmd = self.pending_commands.remove(i);
+                let path = cmd.strip_prefix("__FETCH_THUMBNAIL__").unwrap_or("");
+                if let Err(e) = self.fetch_thumbnail(path).await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Error fetching thumbnail: {}", e)
+                    ));
+                }
+            } else if self.pending_commands[i].starts_with("__DELETE_JOB__") {
+                let cmd = self.pending_commands.remove(i);
+                let job_id = cmd.strip_prefix("__DELETE_JOB__").unwrap_or("");
+                if let Err(e) = self.delete_job(job_id).await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Error deleting job: {}", e)
+                    ));
+                }
+            } else if self.pending_commands[i].starts_with("__START_PRINT__") {
+                let cmd = self.pending_commands.remove(i);
+                let filename = cmd.strip_prefix("__START_PRINT__").unwrap_or("");
+                if let Err(e) = self.start_print_job(filename).await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Error starting print: {}", e)
+                    ));
+                }
+            } else if self.pending_commands[i].starts_with("__PRINT_ACTION__") {
+                let cmd = self.pending_commands.remove(i);
+                let action = cmd.strip_prefix("__PRINT_ACTION__").unwrap_or("");
+                if let Err(e) = self.print_action(action).await {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Failed to {} print: {}", action, e)
+                    ));
+                }
             } else if self.pending_commands[i].starts_with("__TOGGLE_POWER__") {
                 let cmd = self.pending_commands.remove(i);
                 let device_name = cmd.strip_prefix("__TOGGLE_POWER__").unwrap_or("");
@@ -944,9 +2388,9 @@ impl App {
             }
         }
         
-        // Send any remaining pending commands
+        // Send any remaining pending commands in the order they were queued
         if let Some(client) = &mut self.client {
-            while let Some(command) = self.pending_commands.pop() {
+            for command in std::mem::take(&mut self.pending_commands) {
                 match client.send_gcode(&command).await {
                     Ok(_) => {
                         // Command sent successfully - response will be shown via notify_gcode_response
@@ -959,15 +2403,39 @@ impl App {
                 }
             }
             
-            // Try to receive any pending messages
-            if let Some(message) = client.try_receive_message()
-                && let Err(e) = self.process_message(&message) {
-                    eprintln!("Error processing message: {}", e);
-                }
+        }
+
+        self.log_console_messages();
+
+        if self.printer.connected {
+            self.temperature_history.sample(&self.printer.temperatures, temperature_history::now());
+            self.queue_print_metadata();
+        }
+
+        // Try to receive any pending messages
+        if let Some(message) = self.client.as_mut().and_then(|c| c.try_receive_message()) {
+            if let Err(e) = self.process_message(&message) {
+                eprintln!("Error processing message: {}", e);
+            }
+            // Printer output was already logged by the client
+            self.logged_messages = self.console_messages.total();
         }
         Ok(())
     }
 
+    /// Log errors and notices Moonriver itself added to the console
+    /// Commands and printer output are logged by the client
+    fn log_console_messages(&mut self) {
+        for entry in self.console_messages.since(self.logged_messages) {
+            match &entry.message {
+                ConsoleMessage::Error(text) => self.transcript.log(EntryKind::Error, text),
+                ConsoleMessage::Info(text) => self.transcript.log(EntryKind::Info, text),
+                ConsoleMessage::Command(_) | ConsoleMessage::Response(_) => {}
+            }
+        }
+        self.logged_messages = self.console_messages.total();
+    }
+
     /// Process a message from the WebSocket
     fn process_message(&mut self, message: &str) -> anyhow::Result<()> {
         let value: serde_json::Value = serde_json::from_str(message)?;
@@ -1010,6 +2478,29 @@ impl App {
         
         // Update printer state from status updates
         super::printer::update_from_json(&mut self.printer, &value);
+        self.sync_mesh_profiles();
+
+        // Subscribe to every heater once Klipper lists them; the client skips
+        // heaters it is already subscribed to
+        let available_heaters = value
+            .pointer("/result/status/heaters/available_heaters")
+            .or_else(|| value.pointer("/params/0/heaters/available_heaters"));
+        if available_heaters.is_some()
+            && let Some(client) = &mut self.client
+        {
+            let heaters: Vec<String> = self
+                .printer
+                .temperatures
+                .heaters
+                .iter()
+                .map(|heater| heater.object.clone())
+                .collect();
+            let _ = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    client.subscribe_to_additional_objects(heaters).await
+                })
+            });
+        }
         
         // Handle GCode responses for console
         if let Some(method) = value.get("method").and_then(|m| m.as_str()) {
@@ -1018,79 +2509,307 @@ impl App {
                 && let Some(msg) = params.as_str() {
                     // Add to console messages
                     if msg.contains("error") || msg.contains("!!") {
-                        self.add_console_message(ConsoleMessage::Error(msg.to_string()));
+                        self.console_messages.push(ConsoleMessage::Error(msg.to_string()));
                     } else {
-                        self.add_console_message(ConsoleMessage::Response(msg.to_string()));
+                        self.console_messages.push(ConsoleMessage::Response(msg.to_string()));
+                    }
+                }
+            // Refresh the file browser when G-code files are added, moved or removed
+            if method == "notify_filelist_changed"
+                && let Some(change) = value.get("params").and_then(|p| p.get(0))
+            {
+                let mut gcodes_changed = false;
+                for key in ["item", "source_item"] {
+                    if let Some(item) = change.get(key)
+                        && item.get("root").and_then(|r| r.as_str()) == Some("gcodes")
+                    {
+                        gcodes_changed = true;
+                        if let Some(path) = item.get("path").and_then(|p| p.as_str()) {
+                            self.file_browser.invalidate(path);
+                        }
                     }
                 }
+                if gcodes_changed
+                    && self.current_tab == Tab::Files
+                    && !self.pending_commands.iter().any(|c| c == "__FETCH_FILES__")
+                {
+                    self.pending_commands.push("__FETCH_FILES__".to_string());
+                }
+            }
+
+            // Keep the job history current while it is on screen
+            if method == "notify_history_changed" && self.current_tab == Tab::Jobs {
+                self.pending_commands.push("__FETCH_JOBS__".to_string());
+            }
         } else if let Some(error) = value.get("error") {
             // Handle error responses
             let error_msg = error.get("message")
                 .and_then(|m| m.as_str())
                 .unwrap_or("Unknown error");
-            self.add_console_message(ConsoleMessage::Error(error_msg.to_string()));
+            self.console_messages.push(ConsoleMessage::Error(error_msg.to_string()));
         }
         
         Ok(())
     }
     
-    /// Fetch print jobs from Moonraker files list
+    /// Fetch the current page of job history and the lifetime totals
     pub async fn fetch_job_history(&mut self) -> crate::tui::Result<()> {
-        let url = format!("{}/server/files/list?root=gcodes", self.http_url);
-        
+        let url = format!(
+            "{}/server/history/list?limit={}&start={}&order=desc",
+            self.http_url,
+            JOB_PAGE_SIZE,
+            self.job_page * JOB_PAGE_SIZE
+        );
+
         match self.http_client.get(&url).send().await {
             Ok(response) => {
                 if let Ok(json) = response.json::<serde_json::Value>().await
-                    && let Some(files) = json.get("result").and_then(|r| r.as_array()) {
-                        let mut job_items = Vec::new();
-                        
-                        for file in files {
-                            // Only include gcode files
-                            let filename = file.get("filename")
-                                .or_else(|| file.get("path"))
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("unknown")
-                                .to_string();
-                            
-                            if !filename.to_lowercase().ends_with(".gcode") {
-                                continue;
-                            }
-                            
-                            let print_job = PrintJob {
-                                job_id: String::new(), // Not applicable for file list
-                                filename,
-                                status: String::new(), // Not applicable for file list
-                                start_time: 0.0,
-                                end_time: 0.0,
-                                total_duration: file.get("estimated_time")
-                                    .and_then(|v| v.as_f64())
-                                    .unwrap_or(0.0),
-                                filament_used: file.get("filament_total")
-                                    .and_then(|v| v.as_f64())
-                                    .unwrap_or(0.0),
-                                print_duration: 0.0,
-                            };
-                            job_items.push(print_job);
-                        }
-                        
-                        self.job_list = StatefulList::with_items(job_items);
-                        if !self.job_list.items.is_empty() {
-                            self.job_list.state.select(Some(0));
-                        }
+                    && let Some(result) = json.get("result")
+                    && let Some(jobs) = result.get("jobs").and_then(|j| j.as_array())
+                {
+                    let job_items: Vec<PrintJob> = jobs.iter().map(PrintJob::from_json).collect();
+                    self.job_count = result
+                        .get("count")
+                        .and_then(|c| c.as_u64())
+                        .unwrap_or(job_items.len() as u64) as usize;
+
+                    // Keep the selection on the same job when it is still listed
+                    let selected_id = self.job_list.selected().map(|job| job.job_id.clone());
+                    let selected = selected_id
+                        .and_then(|id| job_items.iter().position(|job| job.job_id == id))
+                        .or(if job_items.is_empty() { None } else { Some(0) });
+
+                    self.job_list = StatefulList::with_items(job_items);
+                    self.job_list.state.select(selected);
+
+                    // Deleting the last job on a page leaves it empty
+                    if self.job_list.items.is_empty() && self.job_page > 0 {
+                        self.job_page -= 1;
+                        self.pending_commands.push("__FETCH_JOBS__".to_string());
                     }
+                }
             }
             Err(e) => {
                 self.console_messages.push(ConsoleMessage::Error(
                     format!("Failed to fetch print jobs: {}", e)
                 ));
+                return Ok(());
+            }
+        }
+
+        let url = format!("{}/server/history/totals", self.http_url);
+        match self.http_client.get(&url).send().await {
+            Ok(response) => {
+                if let Ok(json) = response.json::<serde_json::Value>().await
+                    && let Some(totals) = json.get("result").and_then(|r| r.get("job_totals"))
+                {
+                    self.job_totals = Some(JobTotals::from_json(totals));
+                }
+            }
+            Err(e) => {
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to fetch job totals: {}", e)
+                ));
             }
         }
-        
+
         Ok(())
     }
-    
+
+    /// Fetch the file browser's current directory
+    pub async fn fetch_files(&mut self) -> crate::tui::Result<()> {
+        let url = format!("{}/server/files/directory", self.http_url);
+        let path = if self.file_browser.path.is_empty() {
+            "gcodes".to_string()
+        } else {
+            format!("gcodes/{}", self.file_browser.path)
+        };
+        let query = [("path", path.as_str()), ("extended", "true")];
+
+        match self.http_client.get(&url).query(&query).send().await {
+            Ok(response) => {
+                if let Ok(json) = response.json::<serde_json::Value>().await {
+                    if let Some(result) = json.get("result") {
+                        self.file_browser.load(result);
+                        self.queue_file_metadata();
+                    } else if let Some(error) = json.get("error") {
+                        // The directory was probably removed; fall back to its parent
+                        let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
+                        self.console_messages.push(ConsoleMessage::Error(
+                            format!("Failed to list {}: {}", path, message)
+                        ));
+                        if self.file_browser.leave() {
+                            self.pending_commands.push("__FETCH_FILES__".to_string());
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to fetch files: {}", e)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queue a metadata fetch for the selected file, unless it is already known,
+    /// then its thumbnail once the metadata names one
+    fn queue_file_metadata(&mut self) {
+        let Some(path) = self.file_browser.selected_file() else {
+            return;
+        };
+        let browser = &self.file_browser;
+        let command = match browser.metadata.get(&path) {
+            None if !browser.wants_metadata(&path) => return,
+            None => format!("__FETCH_METADATA__{}", path),
+            Some(metadata) => {
+                if metadata.thumbnail.is_none()
+                    || self.thumbnail_protocol == ThumbnailProtocol::Off
+                    || browser.thumbnails.contains_key(&path)
+                    || browser.missing_thumbnails.contains(&path)
+                {
+                    return;
+                }
+                format!("__FETCH_THUMBNAIL__{}", path)
+            }
+        };
+        if !self.pending_commands.contains(&command) {
+            self.pending_commands.push(command);
+        }
+    }
+
+    /// Queue a metadata fetch for the file being printed, for its ETA and layer count
+    fn queue_print_metadata(&mut self) {
+        let filename = &self.printer.print_stats.filename;
+        if filename.is_empty() || !self.file_browser.wants_metadata(filename) {
+            return;
+        }
+        let command = format!("__FETCH_METADATA__{}", filename);
+        if !self.pending_commands.contains(&command) {
+            self.pending_commands.push(command);
+        }
+    }
+
+    /// Slicer metadata of the file being printed, once fetched
+    pub fn print_metadata(&self) -> Option<&super::file_browser::FileMetadata> {
+        self.file_browser.metadata.get(&self.printer.print_stats.filename)
+    }
+
+    /// Fetch slicer metadata for a file in the gcodes root
+    pub async fn fetch_file_metadata(&mut self, path: &str) -> crate::tui::Result<()> {
+        let url = format!("{}/server/files/metadata", self.http_url);
+
+        let result = match self.http_client.get(&url).query(&[("filename", path)]).send().await {
+            // Moonraker answers 404 for files it has no metadata for
+            Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => Ok(None),
+            Ok(response) if response.status().is_success() => response
+                .json::<serde_json::Value>()
+                .await
+                .map(|json| json.get("result").cloned())
+                .map_err(|e| e.to_string()),
+            Ok(response) => Err(response.status().to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(Some(result)) => {
+                let metadata = super::file_browser::FileMetadata::from_json(&result);
+                self.file_browser.metadata_retry.remove(path);
+                self.file_browser.metadata.insert(path.to_string(), metadata);
+                self.queue_file_metadata();
+            }
+            Ok(None) => {
+                self.file_browser.metadata_retry.remove(path);
+                self.file_browser.missing_metadata.push(path.to_string());
+            }
+            Err(e) => {
+                // Retried with a growing delay, so the print ETA doesn't retry every tick
+                self.file_browser.metadata_failed(path);
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to fetch metadata for {}: {}", path, e)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Download and decode the largest thumbnail of a file in the gcodes root
+    pub async fn fetch_thumbnail(&mut self, path: &str) -> crate::tui::Result<()> {
+        let Some(relative) = self
+            .file_browser
+            .metadata
+            .get(path)
+            .and_then(|metadata| metadata.thumbnail.clone())
+        else {
+            return Ok(());
+        };
+
+        // Thumbnail paths are relative to the G-code file's directory
+        let mut url = url::Url::parse(&self.http_url)?;
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.extend(["server", "files", "gcodes"]);
+            if let Some((directory, _)) = path.rsplit_once('/') {
+                segments.extend(directory.split('/'));
+            }
+            segments.extend(relative.split('/'));
+        }
+
+        let result = match self.http_client.get(url).send().await {
+            Ok(response) if response.status().is_success() => match response.bytes().await {
+                Ok(bytes) => Thumbnail::decode(bytes.to_vec()).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+            Ok(response) => Err(response.status().to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(thumbnail) => {
+                self.file_browser.thumbnails.insert(path.to_string(), thumbnail);
+            }
+            Err(e) => {
+                self.file_browser.missing_thumbnails.push(path.to_string());
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to load thumbnail for {}: {}", path, e)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a job from Moonraker's history
+    pub async fn delete_job(&mut self, job_id: &str) -> crate::tui::Result<()> {
+        let url = format!("{}/server/history/job?uid={}", self.http_url, job_id);
+
+        match self.http_client.delete(&url).send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    self.console_messages.push(ConsoleMessage::Info(
+                        format!("Deleted job {}", job_id)
+                    ));
+                    // notify_history_changed also triggers this, but only on the Jobs tab
+                    self.pending_commands.push("__FETCH_JOBS__".to_string());
+                } else {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Failed to delete job: {}", response.status())
+                    ));
+                }
+            }
+            Err(e) => {
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to delete job: {}", e)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Start a print job
-    #[allow(dead_code)]
     pub async fn start_print_job(&mut self, filename: &str) -> crate::tui::Result<()> {
         let url = format!("{}/printer/print/start", self.http_url);
         let body = serde_json::json!({
@@ -1123,6 +2842,60 @@ impl App {
         Ok(())
     }
     
+    /// Pause, resume or cancel the running print through `printer.print.<action>`
+    pub async fn print_action(&mut self, action: &str) -> crate::tui::Result<()> {
+        let url = format!("{}/printer/print/{}", self.http_url, action);
+
+        match self.http_client.post(&url).send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    self.console_messages.push(ConsoleMessage::Info(
+                        format!("Print {} requested", action)
+                    ));
+                } else {
+                    self.console_messages.push(ConsoleMessage::Error(
+                        format!("Failed to {} print: {}", action, response.status())
+                    ));
+                }
+            }
+            Err(e) => {
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to {} print: {}", action, e)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Seed the temperature history from Moonraker's temperature store
+    pub async fn fetch_temperature_store(&mut self) {
+        let url = format!("{}/server/temperature_store?include_monitors=false", self.http_url);
+
+        match self.http_client.get(&url).send().await {
+            Ok(response) => {
+                if let Ok(json) = response.json::<serde_json::Value>().await
+                    && let Some(result) = json.get("result")
+                {
+                    self.temperature_history.seed(result, temperature_history::now());
+                }
+            }
+            Err(e) => {
+                self.console_messages.push(ConsoleMessage::Error(
+                    format!("Failed to fetch temperature history: {}", e)
+                ));
+            }
+        }
+    }
+
+    /// Load macros and printer objects for console completion
+    pub async fn fetch_command_catalog(&mut self) {
+        if let Some(client) = &mut self.client {
+            self.command_catalog.load(client).await;
+            self.macro_browser.refresh(&self.command_catalog);
+        }
+    }
+
     /// Fetch power devices from Moonraker
     pub async fn fetch_power_devices(&mut self) -> crate::tui::Result<()> {
         let url = format!("{}/machine/device_power/devices", self.http_url);
@@ -1215,3 +2988,53 @@ impl App {
         Ok(())
     }
 }
+
+/// Longest prefix shared by every candidate's replacement, ignoring case
+fn common_prefix(candidates: &[Completion]) -> String {
+    let Some((first, rest)) = candidates.split_first() else {
+        return String::new();
+    };
+    let mut len = first.replacement.len();
+    for candidate in rest {
+        len = first
+            .replacement
+            .char_indices()
+            .zip(candidate.replacement.chars())
+            .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(len);
+    }
+    first.replacement[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn esc() -> KeyEvent {
+        KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)
+    }
+
+    #[tokio::test]
+    async fn esc_cancels_mesh_profile_name_first() {
+        let mut app = App::new("localhost:7125".to_string(), Config::default());
+        app.current_tab = Tab::BedMesh;
+        app.mesh_profile_input.enter_edit_mode();
+
+        app.handle_key(esc()).await.unwrap();
+        assert_eq!(app.mesh_profile_input.mode, InputMode::Normal);
+        assert_eq!(app.console_input.mode, InputMode::Editing);
+    }
+
+    #[tokio::test]
+    async fn esc_on_help_returns_to_the_default_tab() {
+        let mut app = App::new("localhost:7125".to_string(), Config::default());
+        app.current_tab = Tab::Help;
+
+        app.handle_key(esc()).await.unwrap();
+        assert_eq!(app.current_tab, Tab::default());
+        assert_eq!(app.console_input.mode, InputMode::Editing);
+    }
+}
@@ -0,0 +1,380 @@
+use super::app::ConsoleMessage;
+use crate::config::ConsoleCategory as Category;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::VecDeque;
+
+/// Width of the `HH:MM:SS ` timestamp column
+pub const TIMESTAMP_WIDTH: usize = 9;
+
+/// Width of the marker before each message (`> `, `✗ `)
+pub const MARKER_WIDTH: usize = 2;
+
+/// Errors kept for the error strip, however many the console holds
+const ERROR_LIMIT: usize = 50;
+
+/// First visible line: a message's sequence number and the wrapped line within it
+pub type ScrollAnchor = (usize, usize);
+
+impl Category {
+    pub const ALL: [Category; 5] = [
+        Category::Command,
+        Category::Response,
+        Category::Info,
+        Category::Echo,
+        Category::Error,
+    ];
+
+    /// Classify a message by its type and Klipper's line prefixes
+    pub fn of(message: &ConsoleMessage) -> Self {
+        match message {
+            ConsoleMessage::Command(_) => Category::Command,
+            ConsoleMessage::Error(_) => Category::Error,
+            ConsoleMessage::Info(_) => Category::Info,
+            ConsoleMessage::Response(text) => {
+                if text.starts_with("!!") {
+                    Category::Error
+                } else if text.starts_with("//") {
+                    Category::Info
+                } else if text.starts_with("echo:") {
+                    Category::Echo
+                } else {
+                    Category::Response
+                }
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Command => "Commands",
+            Category::Response => "Responses",
+            Category::Info => "Info (// lines)",
+            Category::Echo => "Echo (echo:, M118, RESPOND)",
+            Category::Error => "Errors (!! lines)",
+        }
+    }
+}
+
+/// Which console messages are shown
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleFilter {
+    /// Categories hidden from the console
+    pub hidden: Vec<Category>,
+    /// Source of `pattern`, kept for editing
+    pub pattern_text: String,
+    /// Lines matching this are hidden while `pattern_enabled`
+    pub pattern: Option<Regex>,
+    pub pattern_enabled: bool,
+}
+
+impl ConsoleFilter {
+    /// Replace the pattern; an empty one hides nothing
+    pub fn set_pattern(&mut self, text: &str) -> Result<(), regex::Error> {
+        self.pattern = if text.is_empty() {
+            None
+        } else {
+            Some(Regex::new(text)?)
+        };
+        self.pattern_text = text.to_string();
+        Ok(())
+    }
+
+    pub fn toggle(&mut self, category: Category) {
+        if let Some(index) = self.hidden.iter().position(|c| *c == category) {
+            self.hidden.remove(index);
+        } else {
+            self.hidden.push(category);
+        }
+    }
+
+    pub fn is_hidden(&self, category: Category) -> bool {
+        self.hidden.contains(&category)
+    }
+
+    pub fn shows(&self, message: &ConsoleMessage) -> bool {
+        if self.is_hidden(Category::of(message)) {
+            return false;
+        }
+        match &self.pattern {
+            Some(pattern) if self.pattern_enabled => !pattern.is_match(message.text()),
+            _ => true,
+        }
+    }
+
+    /// Whether anything is being hidden
+    pub fn is_active(&self) -> bool {
+        !self.hidden.is_empty() || (self.pattern_enabled && self.pattern.is_some())
+    }
+}
+
+/// A console message and when it arrived
+#[derive(Debug, Clone)]
+pub struct ConsoleEntry {
+    pub time: DateTime<Local>,
+    pub message: ConsoleMessage,
+}
+
+/// One wrapped line of the console, ready to style
+pub struct ConsoleLine<'a> {
+    /// Sequence number of the message this line belongs to
+    pub seq: usize,
+    pub entry: &'a ConsoleEntry,
+    /// Whether this is the message's first line (with timestamp and marker)
+    pub first: bool,
+    pub text: String,
+}
+
+/// Console messages, dropping the oldest beyond a limit
+///
+/// Every message gets a sequence number that stays the same as older
+/// messages are dropped, so scroll positions and search matches can refer
+/// to messages by number.
+#[derive(Debug, Clone)]
+pub struct ConsoleLog {
+    entries: VecDeque<ConsoleEntry>,
+    limit: usize,
+    /// Messages pushed since startup, including dropped ones
+    total: usize,
+    /// Recent errors for the error strip, until dismissed
+    errors: VecDeque<ConsoleEntry>,
+    pub filter: ConsoleFilter,
+}
+
+impl ConsoleLog {
+    pub fn new(limit: usize) -> Self {
+        ConsoleLog {
+            entries: VecDeque::new(),
+            limit: limit.max(1),
+            total: 0,
+            errors: VecDeque::new(),
+            filter: ConsoleFilter::default(),
+        }
+    }
+
+    pub fn push(&mut self, message: ConsoleMessage) {
+        if self.entries.len() == self.limit {
+            self.entries.pop_front();
+        }
+        let entry = ConsoleEntry {
+            time: Local::now(),
+            message,
+        };
+        if Category::of(&entry.message) == Category::Error {
+            if self.errors.len() == ERROR_LIMIT {
+                self.errors.pop_front();
+            }
+            self.errors.push_back(entry.clone());
+        }
+        self.entries.push_back(entry);
+        self.total += 1;
+    }
+
+    /// Errors since the strip was last dismissed, oldest first
+    pub fn errors(&self) -> &VecDeque<ConsoleEntry> {
+        &self.errors
+    }
+
+    pub fn clear_errors(&mut self) {
+        self.errors.clear();
+    }
+
+    /// Number of kept messages the filter hides
+    pub fn hidden_count(&self) -> usize {
+        self.entries.iter().filter(|entry| !self.filter.shows(&entry.message)).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of messages pushed since startup, including dropped ones
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Sequence number of the oldest message kept
+    fn first_seq(&self) -> usize {
+        self.total - self.entries.len()
+    }
+
+    /// Messages pushed after the first `total` ones that are still kept
+    pub fn since(&self, total: usize) -> impl Iterator<Item = &ConsoleEntry> {
+        self.entries.iter().skip(total.saturating_sub(self.first_seq()))
+    }
+
+    /// Wrap a message to `width` columns, timestamp and marker included
+    pub fn wrap_entry(entry: &ConsoleEntry, width: usize, timestamps: bool) -> Vec<String> {
+        let prefix_width = MARKER_WIDTH + if timestamps { TIMESTAMP_WIDTH } else { 0 };
+        let mut lines = wrap(entry.message.text(), width.saturating_sub(prefix_width).max(1));
+        for (i, line) in lines.iter_mut().enumerate() {
+            let prefix = if i > 0 {
+                " ".repeat(prefix_width)
+            } else if timestamps {
+                format!("{} {}", entry.time.format("%H:%M:%S"), entry.message.marker())
+            } else {
+                entry.message.marker().to_string()
+            };
+            line.insert_str(0, &prefix);
+        }
+        lines
+    }
+
+    /// Wrapped line count of every message, oldest first; 0 for filtered ones
+    fn line_counts(&self, width: usize, timestamps: bool) -> Vec<usize> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                if self.filter.shows(&entry.message) {
+                    Self::wrap_entry(entry, width, timestamps).len()
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
+    /// Index of the first visible line for `anchor`; `None` follows the tail
+    fn top_line(&self, anchor: Option<ScrollAnchor>, counts: &[usize], height: usize) -> usize {
+        let total: usize = counts.iter().sum();
+        let tail = total.saturating_sub(height);
+        match anchor {
+            None => tail,
+            Some((seq, line)) => {
+                let index = seq.saturating_sub(self.first_seq());
+                let before: usize = counts.iter().take(index).sum();
+                // Messages dropped from the front leave the anchor at the oldest kept
+                let line = if seq < self.first_seq() { 0 } else { line };
+                (before + line).min(tail)
+            }
+        }
+    }
+
+    /// Anchor for a top line index, or `None` once it reaches the tail
+    fn anchor_at(&self, top: usize, counts: &[usize], height: usize) -> Option<ScrollAnchor> {
+        let total: usize = counts.iter().sum();
+        if top >= total.saturating_sub(height) {
+            return None;
+        }
+        let mut remaining = top;
+        for (index, count) in counts.iter().enumerate() {
+            if remaining < *count {
+                return Some((self.first_seq() + index, remaining));
+            }
+            remaining -= count;
+        }
+        None
+    }
+
+    /// Move `anchor` by `delta` lines (negative scrolls back)
+    pub fn scroll(
+        &self,
+        anchor: Option<ScrollAnchor>,
+        delta: isize,
+        width: usize,
+        height: usize,
+        timestamps: bool,
+    ) -> Option<ScrollAnchor> {
+        let counts = self.line_counts(width, timestamps);
+        let top = self.top_line(anchor, &counts, height);
+        self.anchor_at(top.saturating_add_signed(delta), &counts, height)
+    }
+
+    /// Anchor showing message `seq` a third of the way down the view
+    pub fn anchor_on(&self, seq: usize, width: usize, height: usize, timestamps: bool) -> Option<ScrollAnchor> {
+        let counts = self.line_counts(width, timestamps);
+        let line: usize = counts.iter().take(seq.saturating_sub(self.first_seq())).sum();
+        self.anchor_at(line.saturating_sub(height / 3), &counts, height)
+    }
+
+    /// The `height` wrapped lines visible from `anchor`
+    pub fn window(
+        &self,
+        anchor: Option<ScrollAnchor>,
+        width: usize,
+        height: usize,
+        timestamps: bool,
+    ) -> Vec<ConsoleLine<'_>> {
+        let counts = self.line_counts(width, timestamps);
+        let mut skip = self.top_line(anchor, &counts, height);
+
+        let mut lines = Vec::with_capacity(height);
+        for (index, (entry, count)) in self.entries.iter().zip(&counts).enumerate() {
+            if skip >= *count {
+                skip -= count;
+                continue;
+            }
+            let wrapped = Self::wrap_entry(entry, width, timestamps);
+            for (i, text) in wrapped.into_iter().enumerate().skip(skip) {
+                if lines.len() == height {
+                    return lines;
+                }
+                lines.push(ConsoleLine {
+                    seq: self.first_seq() + index,
+                    entry,
+                    first: i == 0,
+                    text,
+                });
+            }
+            skip = 0;
+        }
+        lines
+    }
+
+    /// Sequence numbers of shown messages containing `query`, ignoring case
+    pub fn matches(&self, query: &str) -> Vec<usize> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let query = query.to_lowercase();
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.filter.shows(&entry.message))
+            .filter(|(_, entry)| entry.message.text().to_lowercase().contains(&query))
+            .map(|(index, _)| self.first_seq() + index)
+            .collect()
+    }
+}
+
+/// Incremental `/` search through the console
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleSearch {
+    pub query: String,
+    /// Still typing the query
+    pub editing: bool,
+    /// Sequence number of the message at the current match
+    pub current: Option<usize>,
+}
+
+/// Word-wrap `text` to `width` columns, breaking words longer than a line
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut len = 0;
+        for word in paragraph.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+            if len > 0 && len + 1 + word.len() > width {
+                lines.push(std::mem::take(&mut line));
+                len = 0;
+            } else if len > 0 {
+                line.push(' ');
+                len += 1;
+            }
+            while len + word.len() > width {
+                let fits = width - len;
+                line.extend(word.drain(..fits));
+                lines.push(std::mem::take(&mut line));
+                len = 0;
+            }
+            len += word.len();
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
@@ -0,0 +1,315 @@
+use super::stateful_list::StatefulList;
+use super::thumbnail::Thumbnail;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Extensions Moonraker accepts as printable files
+const PRINTABLE_EXTENSIONS: &[&str] = &["gcode", "g", "gco", "ufp"];
+
+/// Wait before retrying a metadata fetch that failed on the network, doubled
+/// after each further failure up to the maximum
+const METADATA_RETRY: Duration = Duration::from_secs(10);
+const MAX_METADATA_RETRY: Duration = Duration::from_secs(300);
+
+/// A directory or printable file in the gcodes root
+#[derive(Debug, Clone, Default)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// Unix time of the last modification
+    pub modified: f64,
+    /// Size in bytes
+    pub size: u64,
+    /// Slicer estimate in seconds, for files with metadata
+    pub estimated_time: Option<f64>,
+}
+
+impl FileEntry {
+    fn from_json(entry: &serde_json::Value, is_dir: bool) -> Option<Self> {
+        let key = if is_dir { "dirname" } else { "filename" };
+        let name = entry.get(key).and_then(|v| v.as_str())?.to_string();
+        Some(FileEntry {
+            name,
+            is_dir,
+            modified: entry.get("modified").and_then(|v| v.as_f64()).unwrap_or(0.0),
+            size: entry.get("size").and_then(|v| v.as_u64()).unwrap_or(0),
+            estimated_time: entry.get("estimated_time").and_then(|v| v.as_f64()),
+        })
+    }
+}
+
+/// Slicer metadata from `server.files.metadata`
+#[derive(Debug, Clone, Default)]
+pub struct FileMetadata {
+    pub slicer: Option<String>,
+    pub layer_height: Option<f64>,
+    pub first_layer_height: Option<f64>,
+    pub nozzle_diameter: Option<f64>,
+    pub filament_type: Option<String>,
+    pub filament_name: Option<String>,
+    /// Filament weight in grams
+    pub filament_weight: Option<f64>,
+    /// Filament length in mm
+    pub filament_total: Option<f64>,
+    pub first_layer_extr_temp: Option<f64>,
+    pub first_layer_bed_temp: Option<f64>,
+    pub object_height: Option<f64>,
+    pub layer_count: Option<u64>,
+    pub estimated_time: Option<f64>,
+    /// Largest thumbnail, relative to the file's directory
+    pub thumbnail: Option<String>,
+}
+
+impl FileMetadata {
+    pub fn from_json(metadata: &serde_json::Value) -> Self {
+        let number = |key: &str| metadata.get(key).and_then(|v| v.as_f64());
+        let text = |key: &str| metadata.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        // Slicer and version are reported separately
+        let slicer = text("slicer").map(|slicer| match text("slicer_version") {
+            Some(version) => format!("{} {}", slicer, version),
+            None => slicer,
+        });
+
+        let thumbnail = metadata
+            .get("thumbnails")
+            .and_then(|t| t.as_array())
+            .and_then(|thumbnails| {
+                thumbnails.iter().max_by_key(|t| {
+                    let size = |key: &str| t.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                    size("width") * size("height")
+                })
+            })
+            .and_then(|t| t.get("relative_path"))
+            .and_then(|p| p.as_str())
+            .map(|p| p.to_string());
+
+        FileMetadata {
+            slicer,
+            layer_height: number("layer_height"),
+            first_layer_height: number("first_layer_height"),
+            nozzle_diameter: number("nozzle_diameter"),
+            filament_type: text("filament_type"),
+            filament_name: text("filament_name"),
+            filament_weight: number("filament_weight_total"),
+            filament_total: number("filament_total"),
+            first_layer_extr_temp: number("first_layer_extr_temp"),
+            first_layer_bed_temp: number("first_layer_bed_temp"),
+            object_height: number("object_height"),
+            layer_count: metadata.get("layer_count").and_then(|v| v.as_u64()),
+            estimated_time: number("estimated_time"),
+            thumbnail,
+        }
+    }
+}
+
+/// Column the file list is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Date,
+    Size,
+    EstimatedTime,
+}
+
+impl SortKey {
+    /// The next key in the 's' cycle
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Date,
+            SortKey::Date => SortKey::Size,
+            SortKey::Size => SortKey::EstimatedTime,
+            SortKey::EstimatedTime => SortKey::Name,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Date => "date",
+            SortKey::Size => "size",
+            SortKey::EstimatedTime => "print time",
+        }
+    }
+}
+
+/// State of the Files tab
+#[derive(Default)]
+pub struct FileBrowser {
+    /// Directory being shown, relative to the gcodes root ("" for the root)
+    pub path: String,
+    /// Subdirectories, then printable files
+    pub entries: StatefulList<FileEntry>,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// Metadata already fetched, by path relative to the gcodes root
+    pub metadata: HashMap<String, FileMetadata>,
+    /// Files Moonraker has no metadata for, so they aren't requested again
+    pub missing_metadata: Vec<String>,
+    /// Files whose metadata request failed, with when to retry and the next delay
+    pub metadata_retry: HashMap<String, (Instant, Duration)>,
+    /// Decoded thumbnails, by path relative to the gcodes root
+    pub thumbnails: HashMap<String, Thumbnail>,
+    /// Files whose thumbnail couldn't be fetched or decoded
+    pub missing_thumbnails: Vec<String>,
+}
+
+impl FileBrowser {
+    /// Replace the listing with a `server.files.get_directory` result
+    pub fn load(&mut self, directory: &serde_json::Value) {
+        let selected = self.selected().map(|entry| entry.name.clone());
+
+        let list = |key: &str, is_dir: bool| -> Vec<FileEntry> {
+            directory
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|item| FileEntry::from_json(item, is_dir)).collect())
+                .unwrap_or_default()
+        };
+        let mut entries: Vec<FileEntry> = list("dirs", true)
+            .into_iter()
+            .filter(|dir| !dir.name.starts_with('.'))
+            .collect();
+        entries.extend(list("files", false).into_iter().filter(|file| is_printable(&file.name)));
+
+        self.entries = StatefulList::with_items(entries);
+        self.sort_entries();
+        if let Some(name) = selected {
+            self.select_name(&name);
+        }
+    }
+
+    pub fn selected(&self) -> Option<&FileEntry> {
+        self.entries.selected()
+    }
+
+    /// Path of an entry relative to the gcodes root
+    pub fn entry_path(&self, entry: &FileEntry) -> String {
+        if self.path.is_empty() {
+            entry.name.clone()
+        } else {
+            format!("{}/{}", self.path, entry.name)
+        }
+    }
+
+    /// Path of the selected file, if a file (not a directory) is selected
+    pub fn selected_file(&self) -> Option<String> {
+        self.selected()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| self.entry_path(entry))
+    }
+
+    /// Enter a subdirectory of the current one
+    pub fn enter(&mut self, name: &str) {
+        self.path = if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.path, name)
+        };
+        self.entries = StatefulList::new();
+    }
+
+    /// Go up one level; returns false at the root
+    pub fn leave(&mut self) -> bool {
+        if self.path.is_empty() {
+            return false;
+        }
+        let left = match self.path.rsplit_once('/') {
+            Some((parent, name)) => {
+                let name = name.to_string();
+                self.path = parent.to_string();
+                name
+            }
+            None => std::mem::take(&mut self.path),
+        };
+        // Keep the directory we came from selected once the listing arrives
+        self.entries = StatefulList::with_items(vec![FileEntry {
+            name: left,
+            is_dir: true,
+            ..FileEntry::default()
+        }]);
+        true
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.resort();
+    }
+
+    pub fn toggle_order(&mut self) {
+        self.descending = !self.descending;
+        self.resort();
+    }
+
+    fn resort(&mut self) {
+        let selected = self.selected().map(|entry| entry.name.clone());
+        self.sort_entries();
+        if let Some(name) = selected {
+            self.select_name(&name);
+        }
+    }
+
+    /// Directories first, then files, each ordered by the sort key
+    fn sort_entries(&mut self) {
+        let sort = self.sort;
+        let descending = self.descending;
+        self.entries.items.sort_by(|a, b| {
+            let order = match sort {
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Date => a.modified.total_cmp(&b.modified),
+                SortKey::Size => a.size.cmp(&b.size),
+                SortKey::EstimatedTime => a
+                    .estimated_time
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.estimated_time.unwrap_or(0.0)),
+            };
+            let order = if descending { order.reverse() } else { order };
+            b.is_dir.cmp(&a.is_dir).then(order)
+        });
+    }
+
+    fn select_name(&mut self, name: &str) {
+        if let Some(index) = self.entries.items.iter().position(|entry| entry.name == name) {
+            self.entries.state.select(Some(index));
+        }
+    }
+
+    /// Whether metadata for `path` should be requested: not known, not missing,
+    /// and not backing off after a failed request
+    pub fn wants_metadata(&self, path: &str) -> bool {
+        !self.metadata.contains_key(path)
+            && !self.missing_metadata.iter().any(|missing| missing == path)
+            && self
+                .metadata_retry
+                .get(path)
+                .is_none_or(|(retry_at, _)| Instant::now() >= *retry_at)
+    }
+
+    /// Back off after a metadata request for `path` failed on the network
+    pub fn metadata_failed(&mut self, path: &str) {
+        let delay = self
+            .metadata_retry
+            .get(path)
+            .map(|(_, delay)| *delay)
+            .unwrap_or(METADATA_RETRY);
+        self.metadata_retry.insert(
+            path.to_string(),
+            (Instant::now() + delay, (delay * 2).min(MAX_METADATA_RETRY)),
+        );
+    }
+
+    /// Forget cached metadata after the file list changed on the printer
+    pub fn invalidate(&mut self, path: &str) {
+        self.metadata.remove(path);
+        self.missing_metadata.retain(|missing| missing != path);
+        self.metadata_retry.remove(path);
+        self.thumbnails.remove(path);
+        self.missing_thumbnails.retain(|missing| missing != path);
+    }
+}
+
+fn is_printable(name: &str) -> bool {
+    name.rsplit_once('.')
+        .is_some_and(|(_, ext)| PRINTABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
@@ -0,0 +1,187 @@
+use super::app::InputState;
+use super::stateful_list::StatefulList;
+use crate::completion::CommandCatalog;
+use crate::history::server_key;
+use crate::macros::MacroInfo;
+use std::path::PathBuf;
+
+/// Favourites reachable with the number keys
+pub const MAX_NUMBERED_FAVOURITES: usize = 9;
+
+/// Macros tab state: every `gcode_macro`, favourites pinned first
+#[derive(Default)]
+pub struct MacroBrowser {
+    /// Upper-case macro names, favourites first in the order they were pinned
+    pub list: StatefulList<String>,
+    /// Pinned macro names, saved per printer
+    pub favourites: Vec<String>,
+    /// Parameter form for the macro about to run (open when set)
+    pub form: Option<MacroForm>,
+    path: Option<PathBuf>,
+}
+
+impl MacroBrowser {
+    /// Load the favourites saved for `server` (`host:port`)
+    pub fn open(server: &str) -> Self {
+        let path = dirs::data_dir()
+            .map(|dir| dir.join("moonriver").join(format!("favourite-macros-{}", server_key(server))));
+        let favourites = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .map(|line| line.trim().to_uppercase())
+                    .filter(|line| !line.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        MacroBrowser {
+            favourites,
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Rebuild the list from the catalogue, keeping the selection
+    ///
+    /// Macros starting with `_` are helpers by convention and are left out.
+    pub fn refresh(&mut self, catalog: &CommandCatalog) {
+        let selected = self.list.selected().cloned();
+        let others = catalog
+            .macro_info
+            .keys()
+            .filter(|name| !name.starts_with('_') && !self.is_favourite(name));
+        // Favourites stay pinned even before the catalogue has loaded
+        let items: Vec<String> = self.favourites.iter().chain(others).cloned().collect();
+
+        self.list = StatefulList::with_items(items);
+        if let Some(index) = selected.and_then(|name| self.list.items.iter().position(|n| *n == name)) {
+            self.list.select(Some(index));
+        }
+    }
+
+    pub fn is_favourite(&self, name: &str) -> bool {
+        self.favourites.iter().any(|favourite| favourite == name)
+    }
+
+    /// Favourite bound to number key `number` (1-based)
+    pub fn numbered(&self, number: usize) -> Option<&String> {
+        if number == 0 || number > MAX_NUMBERED_FAVOURITES {
+            return None;
+        }
+        self.favourites.get(number - 1)
+    }
+
+    /// Pin or unpin a macro, saving the favourites
+    pub fn toggle_favourite(&mut self, name: &str, catalog: &CommandCatalog) -> std::io::Result<()> {
+        match self.favourites.iter().position(|favourite| favourite == name) {
+            Some(index) => {
+                self.favourites.remove(index);
+            }
+            None => self.favourites.push(name.to_string()),
+        }
+        self.refresh(catalog);
+        self.save()
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = self.favourites.join("\n");
+        contents.push('\n');
+        std::fs::write(path, contents)
+    }
+}
+
+/// One `params.NAME` of the macro being run
+#[derive(Debug, Clone)]
+pub struct FormField {
+    pub name: String,
+    /// Value from the macro's `|default(...)`; an empty field leaves it to the macro
+    pub default: Option<String>,
+    pub input: InputState,
+}
+
+/// Values to fill in before running a macro
+#[derive(Debug, Clone)]
+pub struct MacroForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub fields: Vec<FormField>,
+    /// Index of the field being edited
+    pub selected: usize,
+}
+
+impl MacroForm {
+    pub fn new(info: &MacroInfo, description: Option<String>) -> Self {
+        let fields = info
+            .params
+            .iter()
+            .map(|param| {
+                let mut input = InputState::new();
+                input.enter_edit_mode();
+                FormField {
+                    name: param.name.clone(),
+                    default: param.default.clone(),
+                    input,
+                }
+            })
+            .collect();
+        MacroForm {
+            name: info.name.clone(),
+            description,
+            fields,
+            selected: 0,
+        }
+    }
+
+    pub fn selected_input(&mut self) -> Option<&mut InputState> {
+        self.fields.get_mut(self.selected).map(|field| &mut field.input)
+    }
+
+    pub fn next_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn previous_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    /// The command line to send, e.g. `PRINT_START BED=60 EXTRUDER=210`
+    ///
+    /// Values containing spaces are quoted, which Klipper strips again.
+    pub fn command(&self) -> String {
+        let mut command = self.name.clone();
+        for field in &self.fields {
+            let value = field.input.value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            if value.contains(char::is_whitespace) {
+                command.push_str(&format!(" {}=\"{}\"", field.name, value));
+            } else {
+                command.push_str(&format!(" {}={}", field.name, value));
+            }
+        }
+        command
+    }
+}
+
+/// A macro's description: its own `description:`, else what `printer.gcode.help` says
+pub fn description(catalog: &CommandCatalog, name: &str) -> Option<String> {
+    catalog
+        .macro_info
+        .get(name)
+        .and_then(|info| info.description.clone())
+        .or_else(|| catalog.descriptions.get(name).cloned())
+}
@@ -1,9 +1,15 @@
 pub mod app;
+pub mod console_log;
 pub mod event;
+pub mod file_browser;
+pub mod macro_browser;
 pub mod modal;
+pub mod print_progress;
 pub mod printer;
 pub mod stateful_list;
 pub mod tabs;
+pub mod temperature_history;
+pub mod thumbnail;
 pub mod ui;
 pub mod widgets;
 
@@ -17,6 +23,7 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
+use crate::config;
 pub use app::App;
 pub use event::EventHandler;
 
@@ -49,6 +56,7 @@ pub async fn run(
     loop {
         // Draw the UI
         terminal.draw(|frame| ui::render(app, frame))?;
+        draw_graphics(terminal, app)?;
 
         // Handle events
         if let Some(event) = event_handler.next().await
@@ -62,3 +70,28 @@ pub async fn run(
 
     Ok(())
 }
+
+/// Draw a sixel or kitty thumbnail over the cells ratatui left blank for it
+fn draw_graphics(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
+    if !app.thumbnail_protocol.is_graphics() || !app.graphics_overlay.is_stale(&app.thumbnail_placement) {
+        return Ok(());
+    }
+
+    // Sixel pixels stay until the text under them is repainted
+    if app.thumbnail_protocol == config::ThumbnailProtocol::Sixel {
+        terminal.clear()?;
+        terminal.draw(|frame| ui::render(app, frame))?;
+    }
+
+    let placement = app.thumbnail_placement.clone();
+    let thumbnail = placement
+        .as_ref()
+        .and_then(|(_, path)| app.file_browser.thumbnails.get(path));
+    app.graphics_overlay.draw(
+        terminal.backend_mut(),
+        app.thumbnail_protocol,
+        placement.clone(),
+        thumbnail,
+    )?;
+    Ok(())
+}
@@ -1,26 +1,57 @@
-//! Placeholder for modal dialog system
-//! Will be implemented when needed for temperature input, print confirmation, etc.
-
-#![allow(dead_code)]
+//! Confirmation dialogs for print control and other destructive actions
 
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::Line,
-    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
+/// What happens when a modal is confirmed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModalAction {
+    /// Start printing a file, by path relative to the gcodes root
+    StartPrint(String),
+    PausePrint,
+    ResumePrint,
+    CancelPrint,
+    /// Toggle a power device
+    TogglePower(String),
+    /// Remove a job from the print history
+    DeleteJob(String),
+    /// Send G-code that restarts or reconfigures the printer
+    SendGcode(Vec<String>),
+}
+
+/// Which button has focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModalButton {
+    Confirm,
+    #[default]
+    Cancel,
+}
+
+/// A dialog asking to confirm `action`; it captures all keys while open
+#[derive(Debug, Clone)]
 pub struct Modal {
     pub title: String,
     pub content: Vec<String>,
+    /// Button labels, confirm first
+    pub buttons: (String, String),
+    /// Focused button; Cancel by default so a stray Enter does nothing harmful
+    pub focus: ModalButton,
+    pub action: ModalAction,
 }
 
 impl Modal {
-    pub fn new(title: impl Into<String>) -> Self {
+    pub fn new(title: impl Into<String>, action: ModalAction) -> Self {
         Modal {
             title: title.into(),
             content: Vec::new(),
+            buttons: ("Yes".to_string(), "No".to_string()),
+            focus: ModalButton::default(),
+            action,
         }
     }
 
@@ -29,39 +60,95 @@ impl Modal {
         self
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
+    /// Use labels other than Yes/No, e.g. OK/Cancel
+    pub fn with_buttons(mut self, confirm: impl Into<String>, cancel: impl Into<String>) -> Self {
+        self.buttons = (confirm.into(), cancel.into());
+        self
+    }
+
+    /// Move focus to the other button
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            ModalButton::Confirm => ModalButton::Cancel,
+            ModalButton::Cancel => ModalButton::Confirm,
+        };
+    }
+
+    /// Draw the modal centred in `area`; returns the button areas for mouse clicks
+    pub fn render(&self, frame: &mut Frame, area: Rect) -> Vec<(Rect, ModalButton)> {
+        let width = self
+            .content
+            .iter()
+            .map(|line| line.chars().count() as u16)
+            .chain([self.title.chars().count() as u16, 30])
+            .max()
+            .unwrap_or(30)
+            .saturating_add(6)
+            .min(area.width.saturating_sub(4).max(20));
+        // Content, a blank line and the buttons, inside the border
+        let height = (self.content.len() as u16 + 4).min(area.height);
+        let modal_area = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: width.min(area.width),
+            height,
+        };
+
         let block = Block::default()
-            .title(self.title.clone())
+            .title(format!(" {} ", self.title))
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(Color::Yellow))
             .style(Style::default().bg(Color::Black));
-
-        // Center the modal
-        let modal_area = centered_rect(60, 50, area);
-        
+        let inner = block.inner(modal_area);
         frame.render_widget(Clear, modal_area);
         frame.render_widget(block, modal_area);
 
-        // Render content inside modal
-        let inner = Layout::default()
+        let rows = Layout::default()
             .direction(Direction::Vertical)
-            .margin(1)
-            .split(modal_area);
-
-        let text: Vec<Line> = self.content.iter()
-            .map(|s| Line::from(s.clone()))
-            .collect();
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
 
+        let text: Vec<Line> = self.content.iter().map(|s| Line::from(s.clone())).collect();
         let paragraph = Paragraph::new(text)
-            .alignment(Alignment::Left);
-        
-        frame.render_widget(paragraph, inner[0]);
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, rows[0]);
+
+        // Buttons side by side, centred
+        let labels = [
+            (format!(" {} ", self.buttons.0), ModalButton::Confirm),
+            (format!(" {} ", self.buttons.1), ModalButton::Cancel),
+        ];
+        let gap = 4;
+        let total: u16 = labels.iter().map(|(label, _)| label.chars().count() as u16).sum::<u16>() + gap;
+        let mut x = rows[1].x + rows[1].width.saturating_sub(total) / 2;
+
+        let mut areas = Vec::new();
+        let mut spans = Vec::new();
+        for (i, (label, button)) in labels.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" ".repeat(gap as usize)));
+                x += gap;
+            }
+            let style = if button == self.focus {
+                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White).bg(Color::DarkGray)
+            };
+            let label_width = label.chars().count() as u16;
+            areas.push((Rect { x, y: rows[1].y, width: label_width, height: 1 }, button));
+            x += label_width;
+            spans.push(Span::styled(label, style));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)).alignment(Alignment::Center), rows[1]);
+
+        areas
     }
 }
 
 /// Helper function to create a centered rectangle
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
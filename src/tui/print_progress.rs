@@ -0,0 +1,71 @@
+use super::file_browser::FileMetadata;
+use super::printer::PrintStats;
+
+/// Time-left estimates for the running print, in seconds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Estimates {
+    /// Extrapolated from the file position
+    pub file: Option<f64>,
+    /// The slicer's total estimate minus the time printed so far
+    pub slicer: Option<f64>,
+    /// Extrapolated from filament used against the slicer's total
+    pub filament: Option<f64>,
+}
+
+impl Estimates {
+    pub fn new(stats: &PrintStats, metadata: Option<&FileMetadata>) -> Self {
+        let elapsed = stats.print_duration;
+        // Remaining time if `fraction` of the print took `elapsed`
+        let extrapolate = |fraction: f64| {
+            (fraction > 0.0 && elapsed > 0.0).then(|| (elapsed / fraction.min(1.0) - elapsed).max(0.0))
+        };
+
+        let filament_total = metadata.and_then(|m| m.filament_total).filter(|total| *total > 0.0);
+        Estimates {
+            file: extrapolate(stats.progress),
+            slicer: metadata
+                .and_then(|m| m.estimated_time)
+                .filter(|estimate| *estimate > 0.0)
+                .map(|estimate| (estimate - elapsed).max(0.0)),
+            filament: filament_total.and_then(|total| extrapolate(stats.filament_used / total)),
+        }
+    }
+
+    /// Mean of the estimates available
+    pub fn average(&self) -> Option<f64> {
+        let available: Vec<f64> = [self.file, self.slicer, self.filament].into_iter().flatten().collect();
+        (!available.is_empty()).then(|| available.iter().sum::<f64>() / available.len() as f64)
+    }
+}
+
+/// Wall-clock time `remaining` seconds from now, with the date if it isn't today
+pub fn finish_time(remaining: f64) -> String {
+    let now = chrono::Local::now();
+    let finish = now + chrono::Duration::seconds(remaining as i64);
+    if finish.date_naive() == now.date_naive() {
+        finish.format("%H:%M").to_string()
+    } else {
+        finish.format("%a %H:%M").to_string()
+    }
+}
+
+/// Current and total layer, from `print_stats.info` or worked out from the
+/// toolhead height and slicer metadata when the slicer doesn't report layers
+pub fn layers(stats: &PrintStats, metadata: Option<&FileMetadata>, z: f64) -> Option<(u64, u64)> {
+    let layer_height = metadata.and_then(|m| m.layer_height).filter(|h| *h > 0.0);
+    let first_layer_height = metadata.and_then(|m| m.first_layer_height).or(layer_height);
+
+    let total = stats.total_layer.filter(|total| *total > 0).or_else(|| {
+        let metadata = metadata?;
+        metadata.layer_count.or_else(|| {
+            let height = metadata.object_height?;
+            Some(((height - first_layer_height?) / layer_height?).ceil() as u64 + 1)
+        })
+    })?;
+    let current = stats.current_layer.or_else(|| {
+        let above_first = (z - first_layer_height?).max(0.0);
+        Some((above_first / layer_height?).ceil() as u64 + 1)
+    })?;
+
+    Some((current.min(total), total))
+}
@@ -4,12 +4,54 @@
 pub struct PrintJob {
     pub job_id: String,
     pub filename: String,
-    pub status: String, // completed, error, cancelled
+    pub status: String, // completed, cancelled, error, klippy_shutdown, in_progress
     pub start_time: f64,
     pub end_time: f64,
     pub total_duration: f64,
     pub filament_used: f64,
     pub print_duration: f64,
+    /// Whether the G-code file is still on the printer
+    pub exists: bool,
+}
+
+impl PrintJob {
+    /// Parse a job from `server.history.list`
+    pub fn from_json(job: &serde_json::Value) -> Self {
+        let text = |key: &str| job.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let number = |key: &str| job.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        PrintJob {
+            job_id: text("job_id"),
+            filename: text("filename"),
+            status: text("status"),
+            start_time: number("start_time"),
+            end_time: number("end_time"),
+            total_duration: number("total_duration"),
+            filament_used: number("filament_used"),
+            print_duration: number("print_duration"),
+            exists: job.get("exists").and_then(|v| v.as_bool()).unwrap_or(true),
+        }
+    }
+}
+
+/// Lifetime totals from `server.history.totals`
+#[derive(Debug, Clone, Default)]
+pub struct JobTotals {
+    pub total_jobs: u64,
+    pub total_print_time: f64,
+    pub total_filament_used: f64,
+    pub longest_print: f64,
+}
+
+impl JobTotals {
+    pub fn from_json(totals: &serde_json::Value) -> Self {
+        let number = |key: &str| totals.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        JobTotals {
+            total_jobs: totals.get("total_jobs").and_then(|v| v.as_u64()).unwrap_or(0),
+            total_print_time: number("total_print_time"),
+            total_filament_used: number("total_filament_used"),
+            longest_print: number("longest_print"),
+        }
+    }
 }
 
 impl Default for PrintJob {
@@ -23,6 +65,7 @@ impl Default for PrintJob {
             total_duration: 0.0,
             filament_used: 0.0,
             print_duration: 0.0,
+            exists: true,
         }
     }
 }
@@ -45,6 +88,8 @@ pub struct PrinterState {
     pub temperatures: Temperatures,
     pub toolhead: Toolhead,
     pub print_stats: PrintStats,
+    pub display_status: DisplayStatus,
+    pub bed_mesh: BedMesh,
     pub power_devices: Vec<PowerDevice>,
 }
 
@@ -56,21 +101,76 @@ impl Default for PrinterState {
             temperatures: Temperatures::default(),
             toolhead: Toolhead::default(),
             print_stats: PrintStats::default(),
+            display_status: DisplayStatus::default(),
+            bed_mesh: BedMesh::default(),
             power_devices: Vec::new(),
         }
     }
 }
 
 /// Temperature information
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Temperatures {
-    pub extruder: HeaterState,
-    pub bed: HeaterState,
-    pub chamber: Option<HeaterState>,
+    /// Every heater Klipper reports in `heaters.available_heaters`:
+    /// extruders first, then the bed, then `heater_generic` heaters
+    pub heaters: Vec<HeaterState>,
     pub mcus: Vec<McuTemp>,
     pub fans: Vec<FanState>,
 }
 
+impl Default for Temperatures {
+    fn default() -> Self {
+        // Until Klipper lists its heaters, assume the usual extruder and bed
+        Temperatures {
+            heaters: vec![HeaterState::new("extruder"), HeaterState::new("heater_bed")],
+            mcus: Vec::new(),
+            fans: Vec::new(),
+        }
+    }
+}
+
+impl Temperatures {
+    /// Heater by Klipper object name, e.g. `extruder1` or `heater_generic chamber`
+    pub fn heater(&self, object: &str) -> Option<&HeaterState> {
+        self.heaters.iter().find(|h| h.object == object)
+    }
+
+    pub fn heater_mut(&mut self, object: &str) -> Option<&mut HeaterState> {
+        self.heaters.iter_mut().find(|h| h.object == object)
+    }
+
+    /// Replace the heater list from `heaters.available_heaters`, keeping readings
+    fn set_available_heaters(&mut self, objects: &[String]) {
+        let mut previous = std::mem::take(&mut self.heaters);
+        self.heaters = objects
+            .iter()
+            .map(|object| match previous.iter().position(|h| &h.object == object) {
+                Some(index) => previous.swap_remove(index),
+                None => HeaterState::new(object),
+            })
+            .collect();
+        self.sort_heaters();
+    }
+
+    /// Heater for a status key, added if Klipper reports one we weren't told about
+    fn heater_entry(&mut self, object: &str) -> &mut HeaterState {
+        if self.heater(object).is_none() {
+            self.heaters.push(HeaterState::new(object));
+            self.sort_heaters();
+        }
+        self.heater_mut(object).expect("heater was just added")
+    }
+
+    fn sort_heaters(&mut self) {
+        // Stable, so `heater_generic` heaters keep Klipper's config order
+        self.heaters.sort_by_key(|heater| match extruder_index(&heater.object) {
+            Some(index) => (0, index),
+            None if heater.object == "heater_bed" => (1, 0),
+            None => (2, 0),
+        });
+    }
+}
+
 /// MCU temperature sensor
 #[derive(Debug, Clone)]
 pub struct McuTemp {
@@ -89,11 +189,45 @@ pub struct FanState {
 /// Individual heater state
 #[derive(Debug, Clone, Default)]
 pub struct HeaterState {
+    /// Klipper object name, e.g. `extruder`, `heater_bed` or `heater_generic chamber`
+    pub object: String,
     pub temperature: f64,
     pub target: f64,
     pub power: f64,
 }
 
+impl HeaterState {
+    pub fn new(object: &str) -> Self {
+        HeaterState {
+            object: object.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Display name, matching the temperature graphs
+    pub fn label(&self) -> String {
+        super::temperature_history::display_name(&self.object)
+    }
+
+    /// Whether a Klipper object is a heater: `extruder`, `extruder1`..,
+    /// `heater_bed` or `heater_generic <name>`
+    pub fn is_heater(object: &str) -> bool {
+        object == "heater_bed"
+            || object.starts_with("heater_generic ")
+            || extruder_index(object).is_some()
+    }
+}
+
+/// Tool number of an `extruder`/`extruderN` object
+pub fn extruder_index(object: &str) -> Option<usize> {
+    let suffix = object.strip_prefix("extruder")?;
+    if suffix.is_empty() {
+        Some(0)
+    } else {
+        suffix.parse().ok()
+    }
+}
+
 /// Toolhead position and state
 #[derive(Debug, Clone, Default)]
 pub struct Toolhead {
@@ -101,6 +235,21 @@ pub struct Toolhead {
     pub homed_axes: Vec<String>,
     #[allow(dead_code)]
     pub print_time: f64,
+    /// Travel limits from the printer config, X, Y, Z, E
+    pub axis_minimum: [f64; 4],
+    pub axis_maximum: [f64; 4],
+}
+
+impl Toolhead {
+    /// (min, max) travel for an axis (0 = X), once Klipper has reported it
+    pub fn limits(&self, axis: usize) -> Option<(f64, f64)> {
+        let (min, max) = (self.axis_minimum[axis], self.axis_maximum[axis]);
+        (max > min).then_some((min, max))
+    }
+
+    pub fn is_homed(&self, axis: &str) -> bool {
+        self.homed_axes.iter().any(|homed| homed == axis)
+    }
 }
 
 /// Print statistics
@@ -111,6 +260,122 @@ pub struct PrintStats {
     pub total_duration: f64,
     pub print_duration: f64,
     pub filament_used: f64,
+    /// File progress from `virtual_sdcard`, 0.0 to 1.0
+    pub progress: f64,
+    /// Layers from `SET_PRINT_STATS_INFO`, when the slicer emits it
+    pub current_layer: Option<u64>,
+    pub total_layer: Option<u64>,
+}
+
+/// `display_status`: `M73` progress and the `M117` message
+#[derive(Debug, Clone, Default)]
+pub struct DisplayStatus {
+    /// Progress shown on the printer's display, 0.0 to 1.0; follows the file
+    /// progress unless the slicer emits `M73`
+    pub progress: f64,
+    pub message: String,
+}
+
+/// `bed_mesh`: the active mesh and the saved profiles
+#[derive(Debug, Clone, Default)]
+pub struct BedMesh {
+    /// Active profile, empty when no mesh is loaded
+    pub profile_name: String,
+    /// Probed area, X and Y
+    pub mesh_min: [f64; 2],
+    pub mesh_max: [f64; 2],
+    /// Z offsets as probed, one row per Y from the front of the bed
+    pub probed_matrix: Vec<Vec<f64>>,
+    /// The same after interpolation
+    pub mesh_matrix: Vec<Vec<f64>>,
+    /// Saved profile names, sorted
+    pub profiles: Vec<String>,
+}
+
+/// Spread of a mesh's Z offsets
+#[derive(Debug, Clone, Copy)]
+pub struct MeshStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Bed position (X, Y) of the lowest and highest points
+    pub min_at: (f64, f64),
+    pub max_at: (f64, f64),
+}
+
+impl MeshStats {
+    pub fn range(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+impl BedMesh {
+    /// Whether Klipper has a mesh loaded; it reports `[[]]` otherwise
+    pub fn is_loaded(&self) -> bool {
+        self.probed_matrix.iter().any(|row| !row.is_empty())
+    }
+
+    /// Probed or interpolated matrix
+    pub fn matrix(&self, interpolated: bool) -> &[Vec<f64>] {
+        if interpolated { &self.mesh_matrix } else { &self.probed_matrix }
+    }
+
+    /// Bed position of a matrix cell, spreading the grid over the probed area
+    pub fn position(&self, matrix: &[Vec<f64>], row: usize, col: usize) -> (f64, f64) {
+        let rows = matrix.len();
+        let cols = matrix.first().map(|r| r.len()).unwrap_or(0);
+        let along = |index: usize, count: usize, axis: usize| {
+            let span = self.mesh_max[axis] - self.mesh_min[axis];
+            if count > 1 {
+                self.mesh_min[axis] + span * index as f64 / (count - 1) as f64
+            } else {
+                self.mesh_min[axis]
+            }
+        };
+        (along(col, cols, 0), along(row, rows, 1))
+    }
+
+    /// Min, max and spread of a matrix, or `None` if it is empty
+    pub fn stats(&self, matrix: &[Vec<f64>]) -> Option<MeshStats> {
+        let points: Vec<(usize, usize, f64)> = matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.iter().enumerate().map(move |(c, z)| (r, c, *z)))
+            .collect();
+        if points.is_empty() {
+            return None;
+        }
+
+        let lowest = points.iter().min_by(|a, b| a.2.total_cmp(&b.2))?;
+        let highest = points.iter().max_by(|a, b| a.2.total_cmp(&b.2))?;
+        let count = points.len() as f64;
+        let mean = points.iter().map(|p| p.2).sum::<f64>() / count;
+        let variance = points.iter().map(|p| (p.2 - mean).powi(2)).sum::<f64>() / count;
+
+        Some(MeshStats {
+            min: lowest.2,
+            max: highest.2,
+            mean,
+            std_dev: variance.sqrt(),
+            min_at: self.position(matrix, lowest.0, lowest.1),
+            max_at: self.position(matrix, highest.0, highest.1),
+        })
+    }
+}
+
+/// Parse a `bed_mesh` matrix: rows of Z offsets
+fn parse_matrix(value: &serde_json::Value) -> Option<Vec<Vec<f64>>> {
+    let rows = value.as_array()?;
+    Some(
+        rows.iter()
+            .map(|row| {
+                row.as_array()
+                    .map(|row| row.iter().filter_map(|z| z.as_f64()).collect())
+                    .unwrap_or_default()
+            })
+            .collect(),
+    )
 }
 
 /// Parse printer object update from Moonraker
@@ -135,29 +400,33 @@ pub fn update_from_json(state: &mut PrinterState, data: &serde_json::Value) {
     };
     
     if let Some(status) = status {
-        // Update extruder temperature
-        if let Some(extruder) = status.get("extruder") {
-            if let Some(temp) = extruder.get("temperature").and_then(|v| v.as_f64()) {
-                state.temperatures.extruder.temperature = temp;
-            }
-            if let Some(target) = extruder.get("target").and_then(|v| v.as_f64()) {
-                state.temperatures.extruder.target = target;
-            }
-            if let Some(power) = extruder.get("power").and_then(|v| v.as_f64()) {
-                state.temperatures.extruder.power = power;
-            }
+        // The heater list comes first so new heaters are in place for their readings
+        if let Some(heaters) = status
+            .get("heaters")
+            .and_then(|h| h.get("available_heaters"))
+            .and_then(|v| v.as_array())
+        {
+            let objects: Vec<String> = heaters
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect();
+            state.temperatures.set_available_heaters(&objects);
         }
 
-        // Update bed temperature
-        if let Some(bed) = status.get("heater_bed") {
-            if let Some(temp) = bed.get("temperature").and_then(|v| v.as_f64()) {
-                state.temperatures.bed.temperature = temp;
-            }
-            if let Some(target) = bed.get("target").and_then(|v| v.as_f64()) {
-                state.temperatures.bed.target = target;
-            }
-            if let Some(power) = bed.get("power").and_then(|v| v.as_f64()) {
-                state.temperatures.bed.power = power;
+        // Update heater temperatures
+        if let Some(obj) = status.as_object() {
+            for (key, value) in obj.iter().filter(|(key, _)| HeaterState::is_heater(key)) {
+                let heater = state.temperatures.heater_entry(key);
+                if let Some(temp) = value.get("temperature").and_then(|v| v.as_f64()) {
+                    heater.temperature = temp;
+                }
+                if let Some(target) = value.get("target").and_then(|v| v.as_f64()) {
+                    heater.target = target;
+                }
+                if let Some(power) = value.get("power").and_then(|v| v.as_f64()) {
+                    heater.power = power;
+                }
             }
         }
 
@@ -244,6 +513,18 @@ pub fn update_from_json(state: &mut PrinterState, data: &serde_json::Value) {
             if let Some(homed) = toolhead.get("homed_axes").and_then(|v| v.as_str()) {
                 state.toolhead.homed_axes = homed.chars().map(|c| c.to_string()).collect();
             }
+            for (key, limits) in [
+                ("axis_minimum", &mut state.toolhead.axis_minimum),
+                ("axis_maximum", &mut state.toolhead.axis_maximum),
+            ] {
+                if let Some(values) = toolhead.get(key).and_then(|v| v.as_array()) {
+                    for (i, val) in values.iter().take(4).enumerate() {
+                        if let Some(val) = val.as_f64() {
+                            limits[i] = val;
+                        }
+                    }
+                }
+            }
         }
 
         // Update print stats
@@ -263,6 +544,61 @@ pub fn update_from_json(state: &mut PrinterState, data: &serde_json::Value) {
             if let Some(filament) = print_stats.get("filament_used").and_then(|v| v.as_f64()) {
                 state.print_stats.filament_used = filament;
             }
+            // Layer counts are null until the slicer's SET_PRINT_STATS_INFO runs
+            if let Some(info) = print_stats.get("info") {
+                if let Some(layer) = info.get("current_layer") {
+                    state.print_stats.current_layer = layer.as_u64();
+                }
+                if let Some(layers) = info.get("total_layer") {
+                    state.print_stats.total_layer = layers.as_u64();
+                }
+            }
+        }
+
+        if let Some(display_status) = status.get("display_status") {
+            if let Some(progress) = display_status.get("progress").and_then(|v| v.as_f64()) {
+                state.display_status.progress = progress;
+            }
+            if let Some(message) = display_status.get("message") {
+                // A null message means M117 was sent without text, clearing it
+                state.display_status.message = message.as_str().unwrap_or("").to_string();
+            }
+        }
+
+        if let Some(bed_mesh) = status.get("bed_mesh") {
+            if let Some(name) = bed_mesh.get("profile_name").and_then(|v| v.as_str()) {
+                state.bed_mesh.profile_name = name.to_string();
+            }
+            for (key, bounds) in [
+                ("mesh_min", &mut state.bed_mesh.mesh_min),
+                ("mesh_max", &mut state.bed_mesh.mesh_max),
+            ] {
+                if let Some(values) = bed_mesh.get(key).and_then(|v| v.as_array()) {
+                    for (i, val) in values.iter().take(2).enumerate() {
+                        if let Some(val) = val.as_f64() {
+                            bounds[i] = val;
+                        }
+                    }
+                }
+            }
+            if let Some(matrix) = bed_mesh.get("probed_matrix").and_then(parse_matrix) {
+                state.bed_mesh.probed_matrix = matrix;
+            }
+            if let Some(matrix) = bed_mesh.get("mesh_matrix").and_then(parse_matrix) {
+                state.bed_mesh.mesh_matrix = matrix;
+            }
+            if let Some(profiles) = bed_mesh.get("profiles").and_then(|v| v.as_object()) {
+                // serde_json's map is ordered by key
+                state.bed_mesh.profiles = profiles.keys().cloned().collect();
+            }
+        }
+
+        if let Some(progress) = status
+            .get("virtual_sdcard")
+            .and_then(|sdcard| sdcard.get("progress"))
+            .and_then(|v| v.as_f64())
+        {
+            state.print_stats.progress = progress;
         }
     }
 }
@@ -60,7 +60,6 @@ impl<T> StatefulList<T> {
         self.state.select(Some(i));
     }
 
-    #[allow(dead_code)]
     pub fn select(&mut self, index: Option<usize>) {
         self.state.select(index);
     }
@@ -1,11 +1,21 @@
 /// Available tabs in the TUI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Tab {
-    /// GCode console/REPL (press 'c')
+    /// Dashboard with system info, print progress and temperatures (press 'm')
     #[default]
+    Main,
+    /// GCode console/REPL (press 'c')
     Console,
+    /// Klipper macros with parameter forms and favourites (press 'M')
+    Macros,
     /// Toolhead position and homing controls (press 'p')
     Position,
+    /// Temperature history charts (press 'g')
+    Graphs,
+    /// Bed mesh heatmap and profiles (press 'b')
+    BedMesh,
+    /// G-code file browser with slicer metadata (press 'f')
+    Files,
     /// Full job history browser (press 'j')
     Jobs,
     /// Context-sensitive help (press 'h' or '?')
@@ -14,11 +24,15 @@ pub enum Tab {
 
 impl Tab {
     /// Get the key binding for this tab
-    #[allow(dead_code)]
     pub fn key(&self) -> char {
         match self {
+            Tab::Main => 'm',
             Tab::Console => 'c',
+            Tab::Macros => 'M',
             Tab::Position => 'p',
+            Tab::Graphs => 'g',
+            Tab::BedMesh => 'b',
+            Tab::Files => 'f',
             Tab::Jobs => 'j',
             Tab::Help => 'h',
         }
@@ -27,8 +41,13 @@ impl Tab {
     /// Get the display name for this tab
     pub fn name(&self) -> &str {
         match self {
+            Tab::Main => "Main",
             Tab::Console => "Console",
+            Tab::Macros => "Macros",
             Tab::Position => "Position",
+            Tab::Graphs => "Graphs",
+            Tab::BedMesh => "Bed Mesh",
+            Tab::Files => "Files",
             Tab::Jobs => "Jobs",
             Tab::Help => "Help",
         }
@@ -37,8 +56,13 @@ impl Tab {
     /// Try to get a tab from a key press
     pub fn from_key(key: char) -> Option<Self> {
         match key {
+            'm' => Some(Tab::Main),
             'c' => Some(Tab::Console),
+            'M' => Some(Tab::Macros),
             'p' => Some(Tab::Position),
+            'g' => Some(Tab::Graphs),
+            'b' => Some(Tab::BedMesh),
+            'f' => Some(Tab::Files),
             'j' => Some(Tab::Jobs),
             'h' | '?' => Some(Tab::Help),
             _ => None,
@@ -46,8 +70,7 @@ impl Tab {
     }
 
     /// Get all tabs in order
-    #[allow(dead_code)]
     pub fn all() -> &'static [Tab] {
-        &[Tab::Console, Tab::Position, Tab::Jobs, Tab::Help]
+        &[Tab::Main, Tab::Console, Tab::Macros, Tab::Position, Tab::Graphs, Tab::BedMesh, Tab::Files, Tab::Jobs, Tab::Help]
     }
 }
@@ -0,0 +1,176 @@
+use super::printer::{extruder_index, Temperatures};
+use std::collections::VecDeque;
+
+/// Samples kept per series, one per second (Moonraker's store also keeps 20 minutes)
+pub const MAX_SAMPLES: usize = 1200;
+
+/// Selectable chart windows, in seconds
+pub const WINDOWS: &[u64] = &[60, 300, 1200];
+
+/// Temperature (and target) readings for one heater or sensor
+#[derive(Debug, Clone)]
+pub struct TemperatureSeries {
+    /// Display name, matching the temperature bar
+    pub name: String,
+    /// (unix time, °C)
+    pub temperatures: VecDeque<(f64, f64)>,
+    /// (unix time, °C); empty for sensors without a target
+    pub targets: VecDeque<(f64, f64)>,
+    /// Whether the series is drawn on the chart
+    pub visible: bool,
+}
+
+impl TemperatureSeries {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            temperatures: VecDeque::new(),
+            targets: VecDeque::new(),
+            visible: true,
+        }
+    }
+
+    fn push(&mut self, time: f64, temperature: f64, target: Option<f64>) {
+        push_sample(&mut self.temperatures, (time, temperature));
+        if let Some(target) = target {
+            push_sample(&mut self.targets, (time, target));
+        }
+    }
+
+    /// Latest reading
+    pub fn current(&self) -> Option<f64> {
+        self.temperatures.back().map(|(_, temp)| *temp)
+    }
+
+    /// Latest target, if the series has one
+    pub fn target(&self) -> Option<f64> {
+        self.targets.back().map(|(_, target)| *target)
+    }
+}
+
+/// Rolling history of every heater and sensor, for the Graphs tab
+#[derive(Debug, Clone, Default)]
+pub struct TemperatureHistory {
+    pub series: Vec<TemperatureSeries>,
+    /// Index into `WINDOWS`
+    pub window_index: usize,
+    /// Time of the last live sample
+    last_sample: f64,
+}
+
+impl TemperatureHistory {
+    /// Width of the chart window in seconds
+    pub fn window(&self) -> u64 {
+        WINDOWS[self.window_index % WINDOWS.len()]
+    }
+
+    pub fn cycle_window(&mut self) {
+        self.window_index = (self.window_index + 1) % WINDOWS.len();
+    }
+
+    /// Show or hide the series at `index`
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(series) = self.series.get_mut(index) {
+            series.visible = !series.visible;
+        }
+    }
+
+    /// Replace the history with Moonraker's `server.temperature_store` result
+    ///
+    /// The store holds one reading per second per object, the last one being now.
+    pub fn seed(&mut self, store: &serde_json::Value, now: f64) {
+        let Some(objects) = store.as_object() else {
+            return;
+        };
+        self.series.clear();
+
+        for (object, values) in objects {
+            let readings = |key: &str| -> Vec<f64> {
+                values
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|v| v.iter().map(|t| t.as_f64().unwrap_or(0.0)).collect())
+                    .unwrap_or_default()
+            };
+            let temperatures = readings("temperatures");
+            let targets = readings("targets");
+
+            let mut series = TemperatureSeries::new(display_name(object));
+            let count = temperatures.len();
+            for (i, temperature) in temperatures.into_iter().enumerate() {
+                let time = now - (count - 1 - i) as f64;
+                series.push(time, temperature, targets.get(i).copied());
+            }
+            self.series.push(series);
+        }
+
+        self.sort();
+        self.last_sample = now;
+    }
+
+    /// Record the current temperatures, at most once per second
+    pub fn sample(&mut self, temperatures: &Temperatures, now: f64) {
+        if now - self.last_sample < 1.0 {
+            return;
+        }
+        self.last_sample = now;
+
+        for heater in &temperatures.heaters {
+            self.record(&heater.label(), heater.temperature, Some(heater.target), now);
+        }
+        for sensor in &temperatures.mcus {
+            self.record(&sensor.name, sensor.temperature, None, now);
+        }
+    }
+
+    fn record(&mut self, name: &str, temperature: f64, target: Option<f64>, now: f64) {
+        match self.series.iter_mut().find(|s| s.name == name) {
+            Some(series) => series.push(now, temperature, target),
+            None => {
+                let mut series = TemperatureSeries::new(name.to_string());
+                series.push(now, temperature, target);
+                self.series.push(series);
+                self.sort();
+            }
+        }
+    }
+
+    /// Heaters first, then sensors by name, so the toggle numbers stay stable
+    fn sort(&mut self) {
+        self.series.sort_by(|a, b| {
+            (a.targets.is_empty(), &a.name).cmp(&(b.targets.is_empty(), &b.name))
+        });
+    }
+}
+
+fn push_sample(samples: &mut VecDeque<(f64, f64)>, sample: (f64, f64)) {
+    samples.push_back(sample);
+    while samples.len() > MAX_SAMPLES {
+        samples.pop_front();
+    }
+}
+
+/// Name a Klipper object the way the temperature bar does
+pub fn display_name(object: &str) -> String {
+    match object {
+        "extruder" => "Extruder".to_string(),
+        "heater_bed" => "Bed".to_string(),
+        "temperature_host" => "Host".to_string(),
+        "mcu" => "MCU".to_string(),
+        _ if extruder_index(object).is_some() => {
+            format!("Extruder {}", &object["extruder".len()..])
+        }
+        _ => object
+            .split_once(' ')
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| object.to_string()),
+    }
+}
+
+/// Current unix time in seconds
+pub fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,398 @@
+use crate::config::ThumbnailProtocol as Protocol;
+use anyhow::bail;
+use base64::Engine;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::io::Write;
+
+/// Cell size assumed when the terminal doesn't report its pixel size
+const DEFAULT_CELL: (u16, u16) = (8, 16);
+
+/// Pixels with less alpha than this are left transparent
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// Largest width or height accepted; slicer thumbnails are a few hundred pixels
+const MAX_DIMENSION: u32 = 4096;
+
+impl Protocol {
+    /// Resolve `Auto` for the current terminal
+    pub fn detect(self) -> Protocol {
+        if self != Protocol::Auto {
+            return self;
+        }
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+
+        // Graphics escapes don't survive tmux without passthrough
+        if std::env::var_os("TMUX").is_some() {
+            Protocol::Blocks
+        } else if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "ghostty"
+            || program == "WezTerm"
+        {
+            Protocol::Kitty
+        } else if term.starts_with("foot")
+            || term.contains("mlterm")
+            || term.contains("sixel")
+            || program == "iTerm.app"
+            || program == "contour"
+        {
+            Protocol::Sixel
+        } else {
+            Protocol::Blocks
+        }
+    }
+
+    /// Whether the image is written outside ratatui's buffer
+    pub fn is_graphics(self) -> bool {
+        matches!(self, Protocol::Sixel | Protocol::Kitty)
+    }
+}
+
+/// Decoded image as straight (not premultiplied) RGBA
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    /// Decode a PNG, expanding every colour type to 8-bit RGBA
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Image> {
+        let mut decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        decoder.set_transformations(png::Transformations::ALPHA | png::Transformations::STRIP_16);
+        let info = decoder.read_header_info()?;
+        if info.width > MAX_DIMENSION || info.height > MAX_DIMENSION {
+            bail!("PNG too large ({}x{})", info.width, info.height);
+        }
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or(0)];
+        let frame = reader.next_frame(&mut buffer)?;
+        let data = &buffer[..frame.buffer_size()];
+        let pixels = match frame.color_type {
+            png::ColorType::Rgba => data.chunks_exact(4).map(|p| [p[0], p[1], p[2], p[3]]).collect(),
+            png::ColorType::Rgb => data.chunks_exact(3).map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => data.chunks_exact(2).map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => data.iter().map(|&v| [v, v, v, 255]).collect(),
+            other => bail!("Unsupported PNG colour type {:?}", other),
+        };
+        Ok(Image {
+            width: frame.width as usize,
+            height: frame.height as usize,
+            pixels,
+        })
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Scale to `width`×`height` by averaging the source pixels under each target pixel
+    pub fn resize(&self, width: usize, height: usize) -> Image {
+        let width = width.max(1);
+        let height = height.max(1);
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            let y0 = y * self.height / height;
+            let y1 = ((y + 1) * self.height / height).max(y0 + 1).min(self.height);
+            for x in 0..width {
+                let x0 = x * self.width / width;
+                let x1 = ((x + 1) * self.width / width).max(x0 + 1).min(self.width);
+
+                // Weight colours by alpha so transparent edges don't darken
+                let mut sum = [0u64; 4];
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        let [r, g, b, a] = self.pixel(sx, sy);
+                        let a = a as u64;
+                        sum[0] += r as u64 * a;
+                        sum[1] += g as u64 * a;
+                        sum[2] += b as u64 * a;
+                        sum[3] += a;
+                    }
+                }
+                let count = ((x1 - x0) * (y1 - y0)) as u64;
+                // Fully transparent areas come out as transparent black
+                let average = |total: u64| total.checked_div(sum[3]).unwrap_or(0) as u8;
+                pixels.push([
+                    average(sum[0]),
+                    average(sum[1]),
+                    average(sum[2]),
+                    (sum[3] / count) as u8,
+                ]);
+            }
+        }
+
+        Image { width, height, pixels }
+    }
+}
+
+/// A decoded thumbnail, cached per G-code file
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    /// The PNG as downloaded, passed through as-is to kitty
+    pub png: Vec<u8>,
+    pub image: Image,
+}
+
+impl Thumbnail {
+    pub fn decode(png: Vec<u8>) -> anyhow::Result<Self> {
+        let image = Image::decode(&png)?;
+        Ok(Thumbnail { png, image })
+    }
+
+    /// Largest area inside `area` that keeps the image's aspect ratio, centred
+    pub fn fit(&self, area: Rect, cell: (u16, u16)) -> Rect {
+        let (cell_width, cell_height) = (cell.0.max(1) as f64, cell.1.max(1) as f64);
+        let image_width = self.image.width as f64 / cell_width;
+        let image_height = self.image.height as f64 / cell_height;
+        let scale = (area.width as f64 / image_width).min(area.height as f64 / image_height);
+
+        let width = ((image_width * scale).floor() as u16).clamp(1, area.width.max(1));
+        let height = ((image_height * scale).floor() as u16).clamp(1, area.height.max(1));
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Render as half blocks: each cell shows two pixels, the top one as `▀`
+    pub fn half_blocks(&self, width: u16, height: u16) -> Vec<Line<'static>> {
+        let image = self.image.resize(width as usize, height as usize * 2);
+        let color = |pixel: [u8; 4]| {
+            (pixel[3] >= ALPHA_THRESHOLD).then_some(Color::Rgb(pixel[0], pixel[1], pixel[2]))
+        };
+
+        (0..height as usize)
+            .map(|row| {
+                let spans: Vec<Span> = (0..width as usize)
+                    .map(|x| {
+                        // Transparent halves show the terminal background
+                        match (color(image.pixel(x, row * 2)), color(image.pixel(x, row * 2 + 1))) {
+                            (Some(top), Some(bottom)) => Span::styled("▀", Style::default().fg(top).bg(bottom)),
+                            (Some(top), None) => Span::styled("▀", Style::default().fg(top)),
+                            (None, Some(bottom)) => Span::styled("▄", Style::default().fg(bottom)),
+                            (None, None) => Span::raw(" "),
+                        }
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Sixel image scaled to `width`×`height` pixels
+    fn sixel(&self, width: usize, height: usize) -> String {
+        let image = self.image.resize(width, height);
+        // A 6×6×6 colour cube keeps the encoder simple and is plenty for a preview
+        let level = |value: u8| (value as usize * 5 + 127) / 255;
+        let index = |pixel: [u8; 4]| {
+            (pixel[3] >= ALPHA_THRESHOLD)
+                .then(|| level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]))
+        };
+
+        // P2=1 leaves unset pixels transparent
+        let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+        for i in 0..216 {
+            let (r, g, b) = (i / 36, i / 6 % 6, i % 6);
+            out.push_str(&format!("#{};2;{};{};{}", i, r * 20, g * 20, b * 20));
+        }
+
+        for band in (0..image.height).step_by(6) {
+            let rows = (image.height - band).min(6);
+            let mut colors: Vec<usize> = (band..band + rows)
+                .flat_map(|y| (0..image.width).map(move |x| (x, y)))
+                .filter_map(|(x, y)| index(image.pixel(x, y)))
+                .collect();
+            colors.sort_unstable();
+            colors.dedup();
+
+            for (n, &color) in colors.iter().enumerate() {
+                if n > 0 {
+                    // Back to the start of the band for the next colour
+                    out.push('$');
+                }
+                out.push_str(&format!("#{}", color));
+                let sixels: Vec<u8> = (0..image.width)
+                    .map(|x| {
+                        (0..rows).fold(0u8, |bits, dy| {
+                            if index(image.pixel(x, band + dy)) == Some(color) {
+                                bits | 1 << dy
+                            } else {
+                                bits
+                            }
+                        })
+                    })
+                    .collect();
+                push_run_length(&mut out, &sixels);
+            }
+            out.push('-');
+        }
+
+        out.push_str("\x1b\\");
+        out
+    }
+
+    /// Kitty graphics command showing the PNG over `columns`×`rows` cells
+    fn kitty(&self, columns: u16, rows: u16) -> String {
+        let data = base64::engine::general_purpose::STANDARD.encode(&self.png);
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+
+        let mut out = String::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+            if i == 0 {
+                // C=1 keeps the cursor still so ratatui's idea of it stays right
+                out.push_str(&format!(
+                    "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                    columns, rows, more, chunk
+                ));
+            } else {
+                out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+            }
+        }
+        out
+    }
+}
+
+/// Append sixel characters, collapsing runs with `!<count>`
+fn push_run_length(out: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let run = sixels[i..].iter().take_while(|&&s| s == sixels[i]).count();
+        let ch = (b'?' + sixels[i]) as char;
+        if run > 3 {
+            out.push_str(&format!("!{}{}", run, ch));
+        } else {
+            out.extend(std::iter::repeat_n(ch, run));
+        }
+        i += run;
+    }
+}
+
+/// Terminal cell size in pixels
+pub fn cell_size() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL,
+    }
+}
+
+/// Tracks the thumbnail drawn with a graphics protocol, outside ratatui's buffer
+#[derive(Debug, Default)]
+pub struct GraphicsOverlay {
+    /// Area and file of the image currently on screen
+    drawn: Option<(Rect, String)>,
+}
+
+impl GraphicsOverlay {
+    /// Whether the requested placement differs from what is on screen
+    pub fn is_stale(&self, wanted: &Option<(Rect, String)>) -> bool {
+        self.drawn != *wanted
+    }
+
+    /// Remove the old image and draw `thumbnail` in `area`
+    ///
+    /// Sixel pixels can only be removed by repainting the cells under them, so
+    /// the caller clears the terminal first when the image moves or disappears.
+    pub fn draw<W: Write>(
+        &mut self,
+        out: &mut W,
+        protocol: Protocol,
+        placement: Option<(Rect, String)>,
+        thumbnail: Option<&Thumbnail>,
+    ) -> std::io::Result<()> {
+        if protocol == Protocol::Kitty && self.drawn.is_some() {
+            out.write_all(b"\x1b_Ga=d,d=A,q=2\x1b\\")?;
+        }
+
+        if let (Some((area, _)), Some(thumbnail)) = (&placement, thumbnail) {
+            let cell = cell_size();
+            crossterm::queue!(out, crossterm::cursor::SavePosition, crossterm::cursor::MoveTo(area.x, area.y))?;
+            match protocol {
+                Protocol::Sixel => {
+                    let width = area.width as usize * cell.0 as usize;
+                    let height = area.height as usize * cell.1 as usize;
+                    out.write_all(thumbnail.sixel(width, height).as_bytes())?;
+                }
+                Protocol::Kitty => out.write_all(thumbnail.kitty(area.width, area.height).as_bytes())?,
+                _ => {}
+            }
+            crossterm::queue!(out, crossterm::cursor::RestorePosition)?;
+        }
+
+        out.flush()?;
+        self.drawn = placement;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(
+        width: u32,
+        height: u32,
+        color: png::ColorType,
+        setup: impl Fn(&mut png::Encoder<&mut Vec<u8>>),
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color);
+        setup(&mut encoder);
+        encoder.write_header().unwrap().write_image_data(data).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn decodes_to_rgba() {
+        let png = encode(2, 1, png::ColorType::Rgb, |_| {}, &[255, 0, 0, 0, 0, 255]);
+        assert_eq!(Image::decode(&png).unwrap().pixels, [[255, 0, 0, 255], [0, 0, 255, 255]]);
+
+        let png = encode(2, 1, png::ColorType::GrayscaleAlpha, |_| {}, &[10, 255, 200, 128]);
+        assert_eq!(Image::decode(&png).unwrap().pixels, [[10, 10, 10, 255], [200, 200, 200, 128]]);
+    }
+
+    #[test]
+    fn expands_palettes_with_transparency() {
+        let png = encode(
+            3,
+            1,
+            png::ColorType::Indexed,
+            |encoder| {
+                encoder.set_palette(vec![1, 2, 3, 4, 5, 6]);
+                encoder.set_trns(vec![255, 0]);
+            },
+            &[0, 1, 0],
+        );
+        let image = Image::decode(&png).unwrap();
+        assert_eq!(image.pixels, [[1, 2, 3, 255], [4, 5, 6, 0], [1, 2, 3, 255]]);
+    }
+
+    #[test]
+    fn rejects_oversized_and_truncated_files() {
+        let png = encode(MAX_DIMENSION + 1, 1, png::ColorType::Grayscale, |_| {}, &[0; 4097]);
+        let err = Image::decode(&png).unwrap_err();
+        assert!(err.to_string().starts_with("PNG too large"), "{}", err);
+
+        let png = encode(2, 1, png::ColorType::Rgb, |_| {}, &[255, 0, 0, 0, 0, 255]);
+        assert!(Image::decode(&png[..png.len() - 20]).is_err());
+        assert!(Image::decode(b"not a png").is_err());
+    }
+}
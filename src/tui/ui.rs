@@ -22,13 +22,35 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     app.power_device_click_areas = click_areas;
 
     // Render content based on current tab
+    app.thumbnail_placement = None;
     match app.current_tab {
-        Tab::Console => widgets::console::render(frame, chunks[1], app),
+        Tab::Main => widgets::main::render(frame, chunks[1], app),
+        Tab::Console => {
+            let output_area = widgets::console::render(frame, chunks[1], app);
+            app.console_area = output_area;
+        }
+        Tab::Macros => widgets::macros::render(frame, chunks[1], app),
         Tab::Position => widgets::position::render(frame, chunks[1], app),
+        Tab::Graphs => widgets::graphs::render(frame, chunks[1], app),
+        Tab::BedMesh => widgets::bed_mesh::render(frame, chunks[1], app),
+        Tab::Files => {
+            let placement = widgets::files::render(frame, chunks[1], app);
+            app.thumbnail_placement = placement;
+        }
         Tab::Jobs => widgets::jobs::render(frame, chunks[1], app),
         Tab::Help => widgets::help::render(frame, chunks[1], app),
     }
 
     // Render footer
     widgets::footer::render(frame, chunks[2], app);
+
+    // Popups are drawn last so they sit above the current tab
+    widgets::presets::render(frame, frame.area(), app);
+    widgets::macros::render_form(frame, frame.area(), app);
+    app.console_filter_area = widgets::console_filter::render(frame, frame.area(), app);
+    if let Some(modal) = &app.modal {
+        app.modal_buttons = modal.render(frame, frame.area());
+        // Graphics would be drawn over the modal
+        app.thumbnail_placement = None;
+    }
 }
@@ -0,0 +1,322 @@
+use crate::tui::app::{App, InputMode};
+use crate::tui::printer::{BedMesh, MeshStats};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+/// Width of the stats and profiles panel beside the heatmap
+const PANEL_WIDTH: u16 = 34;
+
+/// Largest heatmap cell, so small meshes don't stretch across the screen
+const MAX_CELL_WIDTH: u16 = 9;
+const MAX_CELL_HEIGHT: u16 = 3;
+
+/// Narrowest cell that still fits a value like `+0.123`
+const VALUE_WIDTH: u16 = 7;
+
+/// Heatmap colour stops, lowest to highest
+const GRADIENT: &[(u8, u8, u8)] = &[
+    (40, 90, 230),
+    (40, 200, 220),
+    (60, 200, 80),
+    (240, 210, 50),
+    (230, 60, 50),
+];
+
+pub fn render(frame: &mut Frame, area: Rect, app: &App) {
+    // Add temperature and position bars at top if enabled
+    let mut constraints = vec![];
+    if app.panel_visibility.temperature {
+        constraints.push(Constraint::Length(1));
+    }
+    if app.panel_visibility.position {
+        constraints.push(Constraint::Length(1));
+    }
+    constraints.push(Constraint::Min(0)); // Heatmap and panel
+
+    let main_area = if app.panel_visibility.temperature || app.panel_visibility.position {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        let mut chunk_idx = 0;
+        if app.panel_visibility.temperature {
+            super::temperatures::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        if app.panel_visibility.position {
+            super::position_bar::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        chunks[chunk_idx]
+    } else {
+        area
+    };
+
+    if !app.printer.connected {
+        let block = Block::default()
+            .title(" Bed Mesh ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let paragraph = Paragraph::new(Span::styled(
+            format!("Not connected to {}", app.server_url),
+            Style::default().fg(Color::Red),
+        ))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, main_area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+        .split(main_area);
+
+    let mesh = &app.printer.bed_mesh;
+    let matrix = mesh.matrix(app.mesh_interpolated);
+    let stats = mesh.stats(matrix);
+
+    render_heatmap(frame, chunks[0], app, stats.as_ref());
+
+    let panel = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(11), Constraint::Min(0)])
+        .split(chunks[1]);
+    render_stats(frame, panel[0], mesh, matrix, stats.as_ref());
+    render_profiles(frame, panel[1], app);
+}
+
+/// Colour for `z` on the gradient from `min` to `max`
+fn mesh_color(z: f64, min: f64, max: f64) -> Color {
+    let t = if max - min > f64::EPSILON {
+        ((z - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+    let scaled = t * (GRADIENT.len() - 1) as f64;
+    let index = (scaled.floor() as usize).min(GRADIENT.len() - 2);
+    let fraction = scaled - index as f64;
+    let (from, to) = (GRADIENT[index], GRADIENT[index + 1]);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+    Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// Colour for the mesh range: flat, worth a look, or out of tram
+fn range_color(range: f64) -> Color {
+    if range < 0.1 {
+        Color::Green
+    } else if range < 0.25 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn render_heatmap(frame: &mut Frame, area: Rect, app: &App, stats: Option<&MeshStats>) {
+    let mesh = &app.printer.bed_mesh;
+    let matrix = mesh.matrix(app.mesh_interpolated);
+    let rows = matrix.len();
+    let cols = matrix.first().map(|row| row.len()).unwrap_or(0);
+
+    let view = if app.mesh_interpolated { "Interpolated" } else { "Probed" };
+    let block = Block::default()
+        .title(format!(" {} Mesh ({}×{}) ", view, cols, rows))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Front ").centered())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let Some(stats) = stats else {
+        let content = vec![
+            Line::from(""),
+            Line::from(Span::styled("No mesh loaded", Style::default().fg(Color::Gray))),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press n to calibrate or Enter to load a profile",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+        let paragraph = Paragraph::new(content)
+            .block(block)
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    };
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if inner.width == 0 || inner.height < 2 || cols == 0 {
+        return;
+    }
+
+    // One line under the grid for the colour scale
+    let grid_area = Rect { height: inner.height - 1, ..inner };
+    let cell_width = (grid_area.width / cols as u16).clamp(1, MAX_CELL_WIDTH);
+    let cell_height = (grid_area.height / rows as u16).clamp(1, MAX_CELL_HEIGHT);
+    let grid_width = cell_width * cols as u16;
+    let grid_height = (cell_height * rows as u16).min(grid_area.height);
+    let x0 = grid_area.x + grid_area.width.saturating_sub(grid_width) / 2;
+    let y0 = grid_area.y + grid_area.height.saturating_sub(grid_height) / 2;
+
+    // Row 0 is the front of the bed, so it goes at the bottom
+    let mut lines = Vec::new();
+    for row in matrix.iter().rev() {
+        for line in 0..cell_height {
+            let spans: Vec<Span> = row
+                .iter()
+                .map(|z| {
+                    let color = mesh_color(*z, stats.min, stats.max);
+                    let text = if line == cell_height / 2 && cell_width >= VALUE_WIDTH {
+                        format!("{:^width$}", format!("{:+.3}", z), width = cell_width as usize)
+                    } else {
+                        " ".repeat(cell_width as usize)
+                    };
+                    Span::styled(text, Style::default().fg(Color::Black).bg(color))
+                })
+                .collect();
+            lines.push(Line::from(spans));
+        }
+    }
+    let grid = Rect {
+        x: x0,
+        y: y0,
+        width: grid_width.min(grid_area.width),
+        height: grid_height,
+    };
+    frame.render_widget(Paragraph::new(lines), grid);
+
+    // Colour scale from the lowest to the highest point
+    let scale_width = grid.width.clamp(1, 40) as usize;
+    let mut scale = vec![Span::styled(
+        format!("{:+.3} ", stats.min),
+        Style::default().fg(Color::Gray),
+    )];
+    for i in 0..scale_width {
+        let z = stats.min + stats.range() * i as f64 / (scale_width.max(2) - 1) as f64;
+        scale.push(Span::styled(" ", Style::default().bg(mesh_color(z, stats.min, stats.max))));
+    }
+    scale.push(Span::styled(format!(" {:+.3}", stats.max), Style::default().fg(Color::Gray)));
+    let scale_area = Rect {
+        y: grid.y + grid.height,
+        height: 1,
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(Line::from(scale)).alignment(Alignment::Center), scale_area);
+}
+
+fn render_stats(
+    frame: &mut Frame,
+    area: Rect,
+    mesh: &BedMesh,
+    matrix: &[Vec<f64>],
+    stats: Option<&MeshStats>,
+) {
+    let block = Block::default()
+        .title(" Mesh ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let label = |text: &'static str| Span::styled(format!("{:<9}", text), Style::default().fg(Color::Gray));
+    let profile = if mesh.profile_name.is_empty() { "(none)" } else { mesh.profile_name.as_str() };
+    let mut content = vec![Line::from(vec![
+        label("Profile"),
+        Span::styled(profile.to_string(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+    ])];
+
+    if let Some(stats) = stats {
+        let rows = matrix.len();
+        let cols = matrix.first().map(|row| row.len()).unwrap_or(0);
+        let at = |(x, y): (f64, f64)| {
+            Span::styled(format!("  X{:.0} Y{:.0}", x, y), Style::default().fg(Color::DarkGray))
+        };
+        content.extend([
+            Line::from(vec![label("Points"), Span::raw(format!("{} × {}", cols, rows))]),
+            Line::from(vec![
+                label("Area"),
+                Span::raw(format!(
+                    "{:.0},{:.0} – {:.0},{:.0}",
+                    mesh.mesh_min[0], mesh.mesh_min[1], mesh.mesh_max[0], mesh.mesh_max[1]
+                )),
+            ]),
+            Line::from(""),
+            Line::from(vec![label("Min"), Span::raw(format!("{:+.3}", stats.min)), at(stats.min_at)]),
+            Line::from(vec![label("Max"), Span::raw(format!("{:+.3}", stats.max)), at(stats.max_at)]),
+            Line::from(vec![
+                label("Range"),
+                Span::styled(
+                    format!("{:.3}", stats.range()),
+                    Style::default().fg(range_color(stats.range())).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![label("Mean"), Span::raw(format!("{:+.3}", stats.mean))]),
+            Line::from(vec![label("Std dev"), Span::raw(format!("{:.3}", stats.std_dev))]),
+        ]);
+    }
+
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+fn render_profiles(frame: &mut Frame, area: Rect, app: &App) {
+    let editing = app.mesh_profile_input.mode == InputMode::Editing;
+    let block = Block::default()
+        .title(" Profiles ")
+        .title_bottom(Line::from(if editing { " Enter Save │ Esc Cancel " } else { " Enter Load " }).centered())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(if editing { 3 } else { 0 })])
+        .split(inner);
+
+    let active = &app.printer.bed_mesh.profile_name;
+    if app.mesh_profiles.items.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled("No saved profiles", Style::default().fg(Color::DarkGray)))
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = app
+            .mesh_profiles
+            .items
+            .iter()
+            .map(|name| {
+                let (marker, style) = if name == active {
+                    ("● ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                } else {
+                    ("  ", Style::default().fg(Color::White))
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(Color::Green)),
+                    Span::styled(name.clone(), style),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, chunks[0], &mut app.mesh_profiles.state.clone());
+    }
+
+    if editing {
+        let input = Paragraph::new(app.mesh_profile_input.value.as_str())
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::default().borders(Borders::ALL).title(" Save as "));
+        frame.render_widget(input, chunks[1]);
+        frame.set_cursor_position((
+            chunks[1].x + app.mesh_profile_input.cursor_position + 1,
+            chunks[1].y + 1,
+        ));
+    }
+}
@@ -1,13 +1,26 @@
-use crate::tui::app::{App, ConsoleMessage, InputMode};
+use crate::completion::{CommandCatalog, CommandKind};
+use crate::config::ConsoleCategory;
+use crate::tui::app::{App, CompletionMenu, InputMode};
+use crate::tui::console_log::{ConsoleLine, ConsoleSearch, MARKER_WIDTH, TIMESTAMP_WIDTH};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
-pub fn render(frame: &mut Frame, area: Rect, app: &App) {
+/// Errors shown in the strip above the input
+const ERROR_STRIP_LINES: usize = 3;
+
+/// Candidates visible in the completion popup before it scrolls
+const COMPLETION_ROWS: usize = 8;
+
+/// Longest command description shown beside a candidate
+const MAX_DESCRIPTION_WIDTH: usize = 40;
+
+/// Returns the output area, which scrolling and wrapping are measured against
+pub fn render(frame: &mut Frame, area: Rect, app: &App) -> Rect {
     // Add temperature and position lines at top if enabled
     let mut constraints = vec![];
     if app.panel_visibility.temperature {
@@ -38,24 +51,50 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         area
     };
     
+    // Recent errors stay visible above the input whatever the filter or scroll position
+    let errors = app.console_messages.errors();
+    let error_height = if errors.is_empty() {
+        0
+    } else {
+        errors.len().min(ERROR_STRIP_LINES) as u16 + 2
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(3),    // Output area
-            Constraint::Length(3), // Input area
+            Constraint::Min(3),               // Output area
+            Constraint::Length(error_height), // Error strip
+            Constraint::Length(3),            // Input area
         ])
         .split(main_area);
 
     // Output area - show console messages
-    let output_block = Block::default()
-        .title(" GCode Console ")
+    let title = if app.console_messages.filter.is_active() {
+        format!(" GCode Console · {} hidden (Ctrl+F) ", app.console_messages.hidden_count())
+    } else {
+        " GCode Console ".to_string()
+    };
+    let mut output_block = Block::default()
+        .title(title)
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
+    if app.console_scroll.is_some() {
+        output_block = output_block.title_bottom(
+            Line::from(Span::styled(
+                " scrolled back · End to follow ",
+                Style::default().fg(Color::Yellow),
+            ))
+            .right_aligned(),
+        );
+    }
+    if let Some(search) = &app.console_search {
+        output_block = output_block.title_bottom(search_status(search, app));
+    }
+    let output_area = output_block.inner(chunks[0]);
 
     // Build output content from message history
     let mut output_content: Vec<Line> = vec![];
-    
+
     if app.console_messages.is_empty() {
         output_content.push(Line::from(""));
         output_content.push(Line::from(Span::styled(
@@ -77,85 +116,302 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
             )));
         }
     } else {
-        // Show recent messages (limit to avoid overwhelming the display)
-        let start_idx = app.console_messages.len().saturating_sub(100);
-        for msg in &app.console_messages[start_idx..] {
-            match msg {
-                ConsoleMessage::Command(cmd) => {
-                    output_content.push(Line::from(vec![
-                        Span::styled("> ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                        Span::styled(cmd, Style::default().fg(Color::White)),
-                    ]));
-                }
-                ConsoleMessage::Response(resp) => {
-                    output_content.push(Line::from(Span::styled(
-                        resp,
-                        Style::default().fg(Color::Cyan),
-                    )));
-                }
-                ConsoleMessage::Error(err) => {
-                    output_content.push(Line::from(Span::styled(
-                        format!("✗ {}", err),
-                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                    )));
-                }
-                ConsoleMessage::Info(info) => {
-                    output_content.push(Line::from(Span::styled(
-                        format!("  {}", info),
-                        Style::default().fg(Color::Gray),
-                    )));
-                }
-            }
+        let timestamps = app.config.console.timestamps;
+        let lines = app.console_messages.window(
+            app.console_scroll,
+            output_area.width as usize,
+            output_area.height as usize,
+            timestamps,
+        );
+        for line in lines {
+            output_content.push(console_line(&line, timestamps, app.console_search.as_ref()));
         }
     }
 
-    let output = Paragraph::new(output_content.clone())
+    let output = Paragraph::new(output_content)
         .block(output_block)
-        .alignment(Alignment::Left)
-        .scroll((app.console_scroll, 0));
+        .alignment(Alignment::Left);
 
     frame.render_widget(output, chunks[0]);
 
-    // Auto-scroll to bottom: calculate how many lines to scroll
-    // to show the most recent content
-    let output_height = chunks[0].height.saturating_sub(2); // Subtract borders
-    let total_lines = output_content.len() as u16;
-    
-    // If content exceeds visible area and scroll is not manually controlled,
-    // we should auto-scroll to show the bottom
-    // Note: We're using the stored scroll value, which should be updated
-    // when new messages arrive (handled in app logic)
-    if total_lines > output_height {
-        // Content is longer than display area - needs scrolling
-        // The app.console_scroll value determines what's visible
-        // For auto-scroll behavior, see app's message handling
-    }
-
     // Input area
     let input_style = match app.console_input.mode {
         InputMode::Normal => Style::default().fg(Color::Gray),
         InputMode::Editing => Style::default().fg(Color::Yellow),
     };
 
+    if error_height > 0 {
+        render_errors(frame, chunks[1], app);
+    }
+
+    let title = match (&app.history_search, app.console_input.mode) {
+        (Some(search), _) => {
+            let failed = if search.index.is_none() && !search.query.is_empty() {
+                "failed "
+            } else {
+                ""
+            };
+            format!(" ({}reverse-i-search)`{}' (Ctrl+R older, Esc cancel) ", failed, search.query)
+        }
+        (None, InputMode::Normal) => " Input (press 'i' to edit) ".to_string(),
+        (None, InputMode::Editing) => {
+            " Input (press Esc to exit, Enter to send, Ctrl+R to search) ".to_string()
+        }
+    };
+
     let input_block = Block::default()
-        .title(match app.console_input.mode {
-            InputMode::Normal => " Input (press 'i' to edit) ",
-            InputMode::Editing => " Input (press Esc to exit, Enter to send) ",
-        })
+        .title(title)
         .borders(Borders::ALL)
         .border_style(input_style);
 
-    let input = Paragraph::new(app.console_input.value.as_str())
+    // Scroll sideways to keep the cursor in view on long lines
+    let scroll = app
+        .console_input
+        .cursor_position
+        .saturating_sub(chunks[2].width.saturating_sub(3));
+    let input = Paragraph::new(highlight_input(&app.console_input.value, &app.command_catalog))
         .style(input_style)
-        .block(input_block);
+        .block(input_block)
+        .scroll((0, scroll));
 
-    frame.render_widget(input, chunks[1]);
+    frame.render_widget(input, chunks[2]);
 
     // Show cursor when editing
     if app.console_input.mode == InputMode::Editing {
         frame.set_cursor_position((
-            chunks[1].x + app.console_input.cursor_position + 1,
-            chunks[1].y + 1,
+            chunks[2].x + app.console_input.cursor_position - scroll + 1,
+            chunks[2].y + 1,
         ));
     }
+
+    if let Some(menu) = &app.console_completion {
+        let column = app.console_input.value[..menu.start].chars().count() as u16;
+        let anchor = (chunks[2].x + 1 + column.saturating_sub(scroll), chunks[2].y);
+        render_completion(frame, main_area, anchor, menu, app);
+    }
+
+    output_area
+}
+
+/// Colour the input line: command names by kind, then `NAME=` and `X10` style parameters
+fn highlight_input(text: &str, catalog: &CommandCatalog) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut first = true;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let space_end = rest[word_end..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |i| word_end + i);
+        let (word, space) = (&rest[..word_end], &rest[word_end..space_end]);
+        if !word.is_empty() {
+            if first {
+                let style = match catalog.kind(word) {
+                    CommandKind::Gcode => Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                    CommandKind::Klipper => Style::default().fg(Color::LightBlue).add_modifier(Modifier::BOLD),
+                    CommandKind::Macro => Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    CommandKind::Alias => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                    CommandKind::Meta | CommandKind::Unknown => Style::default(),
+                };
+                spans.push(Span::styled(word.to_string(), style));
+                first = false;
+            } else {
+                spans.extend(parameter_spans(word));
+            }
+        }
+        spans.push(Span::raw(space.to_string()));
+        rest = &rest[space_end..];
+    }
+    Line::from(spans)
+}
+
+/// A parameter word: the name in yellow, the value plain
+fn parameter_spans(word: &str) -> Vec<Span<'static>> {
+    let name = Style::default().fg(Color::Yellow);
+    if let Some((key, value)) = word.split_once('=') {
+        return vec![
+            Span::styled(format!("{}=", key), name),
+            Span::styled(value.to_string(), Style::default().fg(Color::White)),
+        ];
+    }
+    let mut chars = word.chars();
+    if let Some(letter) = chars.next()
+        && letter.is_ascii_alphabetic()
+        && chars.as_str().parse::<f64>().is_ok()
+    {
+        return vec![
+            Span::styled(letter.to_string(), name),
+            Span::styled(chars.as_str().to_string(), Style::default().fg(Color::White)),
+        ];
+    }
+    vec![Span::styled(word.to_string(), Style::default().fg(Color::White))]
+}
+
+/// Completion candidates in a popup just above the word being completed
+fn render_completion(frame: &mut Frame, area: Rect, anchor: (u16, u16), menu: &CompletionMenu, app: &App) {
+    let rows: Vec<(String, String)> = menu
+        .candidates
+        .items
+        .iter()
+        .map(|candidate| {
+            let description = app
+                .command_catalog
+                .descriptions
+                .get(&candidate.display.to_uppercase())
+                .cloned()
+                .unwrap_or_default();
+            (candidate.display.clone(), description)
+        })
+        .collect();
+    let name_width = rows.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    let description_width = rows
+        .iter()
+        .map(|(_, description)| description.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_DESCRIPTION_WIDTH);
+
+    let mut width = (name_width + 4) as u16;
+    if description_width > 0 {
+        width += description_width as u16 + 2;
+    }
+    let width = width.min(area.width);
+    let height = (rows.len().min(COMPLETION_ROWS) as u16 + 2).min(anchor.1.saturating_sub(area.y));
+    let popup = Rect {
+        x: anchor.0.saturating_sub(1).min(area.x + area.width - width),
+        y: anchor.1 - height,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = rows
+        .into_iter()
+        .map(|(name, description)| {
+            let description: String = description.chars().take(MAX_DESCRIPTION_WIDTH).collect();
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<width$}  ", name, width = name_width)),
+                Span::styled(description, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let position = menu.candidates.state.selected().map_or(0, |i| i + 1);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title_bottom(Line::from(format!(" {}/{} ", position, menu.candidates.items.len())).right_aligned())
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut menu.candidates.state.clone());
+}
+
+/// Style a wrapped console line: dim timestamp, coloured marker, highlighted matches
+fn console_line(line: &ConsoleLine, timestamps: bool, search: Option<&ConsoleSearch>) -> Line<'static> {
+    let (marker_style, text_style) = match ConsoleCategory::of(&line.entry.message) {
+        ConsoleCategory::Command => (
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            Style::default().fg(Color::White),
+        ),
+        ConsoleCategory::Response => (Style::default(), Style::default().fg(Color::Cyan)),
+        ConsoleCategory::Error => {
+            let style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+            (style, style)
+        }
+        ConsoleCategory::Info => (Style::default(), Style::default().fg(Color::Gray)),
+        ConsoleCategory::Echo => (Style::default(), Style::default().fg(Color::Magenta)),
+    };
+
+    let timestamp_width = if timestamps { TIMESTAMP_WIDTH } else { 0 };
+    let mut chars = line.text.chars();
+    let timestamp: String = chars.by_ref().take(timestamp_width).collect();
+    let marker: String = chars.by_ref().take(MARKER_WIDTH).collect();
+    let body: String = chars.collect();
+
+    let mut spans = Vec::new();
+    if line.first {
+        spans.push(Span::styled(timestamp, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(marker, marker_style));
+    } else {
+        spans.push(Span::raw(format!("{}{}", timestamp, marker)));
+    }
+
+    let query = search.map(|s| s.query.to_lowercase()).unwrap_or_default();
+    let lower = body.to_lowercase();
+    // Byte offsets only line up when lowercasing keeps the length
+    if query.is_empty() || lower.len() != body.len() {
+        spans.push(Span::styled(body, text_style));
+        return Line::from(spans);
+    }
+
+    let current = search.and_then(|s| s.current) == Some(line.seq);
+    let highlight = if current {
+        Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White).bg(Color::Blue)
+    };
+    let mut start = 0;
+    for (index, _) in lower.match_indices(&query) {
+        if index < start {
+            continue;
+        }
+        spans.push(Span::styled(body[start..index].to_string(), text_style));
+        spans.push(Span::styled(body[index..index + query.len()].to_string(), highlight));
+        start = index + query.len();
+    }
+    spans.push(Span::styled(body[start..].to_string(), text_style));
+    Line::from(spans)
+}
+
+/// The newest errors, one line each
+fn render_errors(frame: &mut Frame, area: Rect, app: &App) {
+    let errors = app.console_messages.errors();
+    let block = Block::default()
+        .title(format!(" Errors ({}) · Ctrl+E dismiss ", errors.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let lines: Vec<Line> = errors
+        .iter()
+        .skip(errors.len().saturating_sub(ERROR_STRIP_LINES))
+        .map(|entry| {
+            // Multi-line errors show their first line
+            let text = entry.message.text().lines().next().unwrap_or_default().to_string();
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", entry.time.format("%H:%M:%S")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(text, Style::default().fg(Color::Red)),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Query and match position shown on the console's bottom border
+fn search_status(search: &ConsoleSearch, app: &App) -> Line<'static> {
+    let matches = app.console_messages.matches(&search.query);
+    let position = match search.current.and_then(|seq| matches.iter().position(|&m| m == seq)) {
+        Some(index) => format!("{}/{}", index + 1, matches.len()),
+        None if search.query.is_empty() => String::new(),
+        None if matches.is_empty() => "no matches".to_string(),
+        None => format!("{} matches", matches.len()),
+    };
+    let hint = if search.editing {
+        "Enter done"
+    } else if app.console_input.mode == InputMode::Editing {
+        "Esc clear"
+    } else {
+        "n older · N newer · Esc clear"
+    };
+    Line::from(vec![
+        Span::styled(format!(" /{} ", search.query), Style::default().fg(Color::Yellow)),
+        Span::styled(format!("{} ", position), Style::default().fg(Color::White)),
+        Span::styled(format!("· {} ", hint), Style::default().fg(Color::DarkGray)),
+    ])
 }
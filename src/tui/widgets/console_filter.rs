@@ -0,0 +1,100 @@
+use crate::tui::app::{App, FilterRow, InputMode};
+use crate::tui::modal::centered_rect;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+/// Render the console filter menu as a popup; returns the rows area for mouse clicks
+pub fn render(frame: &mut Frame, area: Rect, app: &App) -> Rect {
+    let Some(menu) = &app.console_filter_menu else {
+        return Rect::default();
+    };
+    let filter = &app.console_messages.filter;
+    let editing = app.filter_input.mode == InputMode::Editing;
+
+    let hints = if editing {
+        " Enter Apply │ Esc Cancel "
+    } else {
+        " ↑↓ Select │ Space Toggle │ e Edit pattern │ Esc Close "
+    };
+    let block = Block::default()
+        .title(format!(" Console Filter · {} hidden ", app.console_messages.hidden_count()))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(hints).centered())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    let items: Vec<ListItem> = menu
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let (shown, label) = match row {
+                FilterRow::Category(category) => (!filter.is_hidden(*category), category.name().to_string()),
+                FilterRow::Pattern => {
+                    let pattern = if filter.pattern_text.is_empty() {
+                        "(no pattern)".to_string()
+                    } else {
+                        format!("/{}/", filter.pattern_text)
+                    };
+                    (filter.pattern_enabled, format!("Hide lines matching {}", pattern))
+                }
+            };
+            let check = if shown { "[x] " } else { "[ ] " };
+            let check_style = if shown {
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", i + 1), Style::default().fg(Color::DarkGray)),
+                Span::styled(check, check_style),
+                Span::raw(label),
+            ]))
+        })
+        .collect();
+
+    let popup_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, popup_area);
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    // The pattern editor and any regex error sit below the rows
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(if editing { 4 } else { 0 })])
+        .split(inner);
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    frame.render_stateful_widget(list, chunks[0], &mut menu.state.clone());
+
+    if editing {
+        let input = Paragraph::new(app.filter_input.value.as_str())
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::default().borders(Borders::ALL).title(" Pattern (regex) "));
+        let input_area = Rect { height: 3, ..chunks[1] };
+        frame.render_widget(input, input_area);
+        frame.set_cursor_position((
+            input_area.x + app.filter_input.cursor_position + 1,
+            input_area.y + 1,
+        ));
+
+        if let Some(error) = &app.filter_error {
+            let line = error.lines().last().unwrap_or_default();
+            let error_area = Rect { y: chunks[1].y + 3, height: 1, ..chunks[1] };
+            frame.render_widget(
+                Paragraph::new(line.to_string()).style(Style::default().fg(Color::Red)),
+                error_area,
+            );
+        }
+    }
+
+    chunks[0]
+}
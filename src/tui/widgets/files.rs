@@ -0,0 +1,287 @@
+use crate::config::ThumbnailProtocol;
+use crate::tui::app::App;
+use crate::tui::file_browser::{FileBrowser, FileEntry, FileMetadata};
+use crate::tui::thumbnail;
+use crate::format::format_duration;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+/// Width of the metadata pane beside the file list
+pub const DETAILS_WIDTH: u16 = 40;
+
+/// Width of the size, date and print time columns after the name
+const COLUMNS_WIDTH: usize = 38;
+
+/// Returns where a graphics-protocol thumbnail should be drawn, if any
+pub fn render(frame: &mut Frame, area: Rect, app: &App) -> Option<(Rect, String)> {
+    // Add temperature and position lines at top if enabled
+    let mut constraints = vec![];
+    if app.panel_visibility.temperature {
+        constraints.push(Constraint::Length(1));
+    }
+    if app.panel_visibility.position {
+        constraints.push(Constraint::Length(1));
+    }
+    constraints.push(Constraint::Min(0)); // File list and details
+
+    let main_area = if app.panel_visibility.temperature || app.panel_visibility.position {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        let mut chunk_idx = 0;
+        if app.panel_visibility.temperature {
+            super::temperatures::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        if app.panel_visibility.position {
+            super::position_bar::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        chunks[chunk_idx]
+    } else {
+        area
+    };
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(20), Constraint::Length(DETAILS_WIDTH)])
+        .split(main_area);
+
+    render_list(frame, columns[0], app);
+    render_details(frame, columns[1], app)
+}
+
+fn render_list(frame: &mut Frame, area: Rect, app: &App) {
+    let browser = &app.file_browser;
+    let order = if browser.descending { "↓" } else { "↑" };
+    let block = Block::default()
+        .title(format!(" gcodes/{} ", browser.path))
+        .title_bottom(
+            Line::from(format!(" sorted by {} {} ", browser.sort.name(), order)).right_aligned(),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if !app.printer.connected {
+        let paragraph = Paragraph::new(Span::styled(
+            "Not connected to printer",
+            Style::default().fg(Color::Red),
+        ))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    if browser.entries.items.is_empty() {
+        let paragraph = Paragraph::new(Span::styled(
+            "No printable files in this directory",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    // Border and highlight symbol take four columns
+    let name_width = (area.width as usize).saturating_sub(4 + COLUMNS_WIDTH).max(10);
+    let items: Vec<ListItem> = browser
+        .entries
+        .items
+        .iter()
+        .map(|entry| ListItem::new(entry_line(entry, name_width)))
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    frame.render_stateful_widget(list, area, &mut browser.entries.state.clone());
+}
+
+fn entry_line(entry: &FileEntry, name_width: usize) -> Line<'static> {
+    let modified = chrono::DateTime::from_timestamp(entry.modified as i64, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+
+    if entry.is_dir {
+        return Line::from(vec![
+            Span::styled(
+                truncate(&format!("📁 {}/", entry.name), name_width),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" {:>8} ", "")),
+            Span::styled(modified, Style::default().fg(Color::Gray)),
+        ]);
+    }
+
+    let estimate = entry.estimated_time.map(format_duration).unwrap_or_default();
+    Line::from(vec![
+        Span::styled(truncate(&entry.name, name_width), Style::default().fg(Color::Cyan)),
+        Span::raw(format!(" {:>8} ", format_size(entry.size))),
+        Span::styled(modified, Style::default().fg(Color::Gray)),
+        Span::raw(format!(" {:>11}", estimate)),
+    ])
+}
+
+fn render_details(frame: &mut Frame, area: Rect, app: &App) -> Option<(Rect, String)> {
+    let block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let content = details(&app.file_browser);
+    let text_height = content.len() as u16;
+    frame.render_widget(Paragraph::new(content), inner);
+
+    // The thumbnail fills whatever is left below the metadata
+    let browser = &app.file_browser;
+    let path = browser.selected_file()?;
+    let thumbnail = browser.thumbnails.get(&path)?;
+    let image_area = Rect {
+        y: inner.y + text_height + 1,
+        height: inner.height.saturating_sub(text_height + 1),
+        ..inner
+    };
+    if image_area.height < 2 || image_area.width < 4 {
+        return None;
+    }
+
+    match app.thumbnail_protocol {
+        ThumbnailProtocol::Off => None,
+        ThumbnailProtocol::Sixel | ThumbnailProtocol::Kitty => {
+            // Left blank here; the image is written over these cells after drawing
+            Some((thumbnail.fit(image_area, thumbnail::cell_size()), path))
+        }
+        _ => {
+            // Half blocks make each cell two pixels tall
+            let fitted = thumbnail.fit(image_area, (1, 2));
+            frame.render_widget(
+                Paragraph::new(thumbnail.half_blocks(fitted.width, fitted.height)),
+                fitted,
+            );
+            None
+        }
+    }
+}
+
+fn details(browser: &FileBrowser) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let Some(entry) = browser.selected() else {
+        return vec![Line::from(Span::styled("Nothing selected", dim))];
+    };
+    if entry.is_dir {
+        return vec![
+            Line::from(Span::styled(format!("{}/", entry.name), Style::default().fg(Color::Yellow))),
+            Line::from(""),
+            Line::from(Span::styled("Enter to open, Backspace to go up", dim)),
+        ];
+    }
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            entry.name.clone(),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let path = browser.entry_path(entry);
+    let Some(metadata) = browser.metadata.get(&path) else {
+        let message = if browser.missing_metadata.contains(&path) {
+            "No slicer metadata"
+        } else {
+            "Loading metadata..."
+        };
+        lines.push(Line::from(Span::styled(message, dim)));
+        return lines;
+    };
+
+    lines.extend(metadata_lines(metadata));
+    lines
+}
+
+fn metadata_lines(metadata: &FileMetadata) -> Vec<Line<'static>> {
+    let filament = match (&metadata.filament_type, &metadata.filament_name) {
+        (Some(kind), Some(name)) if name != kind => Some(format!("{} ({})", kind, name)),
+        (Some(kind), _) => Some(kind.clone()),
+        (None, name) => name.clone(),
+    };
+    let amount = match (metadata.filament_weight, metadata.filament_total) {
+        (Some(weight), Some(length)) => Some(format!("{:.1}g, {:.2}m", weight, length / 1000.0)),
+        (Some(weight), None) => Some(format!("{:.1}g", weight)),
+        (None, Some(length)) => Some(format!("{:.2}m", length / 1000.0)),
+        (None, None) => None,
+    };
+    let first_layer_temps = match (metadata.first_layer_extr_temp, metadata.first_layer_bed_temp) {
+        (None, None) => None,
+        (extruder, bed) => Some(format!(
+            "{} / {}",
+            extruder.map(|t| format!("{:.0}°", t)).unwrap_or("-".to_string()),
+            bed.map(|t| format!("{:.0}°", t)).unwrap_or("-".to_string()),
+        )),
+    };
+
+    let rows = [
+        ("Slicer", metadata.slicer.clone()),
+        ("Print time", metadata.estimated_time.map(format_duration)),
+        ("Layer height", metadata.layer_height.map(|h| format!("{:.2}mm", h))),
+        ("First layer", metadata.first_layer_height.map(|h| format!("{:.2}mm", h))),
+        ("Nozzle", metadata.nozzle_diameter.map(|d| format!("{:.2}mm", d))),
+        ("Filament", filament),
+        ("Amount", amount),
+        ("1st layer E/B", first_layer_temps),
+        ("Height", metadata.object_height.map(|h| format!("{:.1}mm", h))),
+    ];
+
+    rows.into_iter()
+        .filter_map(|(label, value)| {
+            value.map(|value| {
+                Line::from(vec![
+                    Span::styled(format!("{:<14}", label), Style::default().fg(Color::Gray)),
+                    Span::raw(value),
+                ])
+            })
+        })
+        .collect()
+}
+
+/// Pad or cut a name to exactly `width` characters
+fn truncate(name: &str, width: usize) -> String {
+    if name.chars().count() > width {
+        let cut: String = name.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    } else {
+        format!("{:<width$}", name)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.1}{}", size, UNITS[unit])
+    }
+}
@@ -17,148 +17,10 @@ pub enum FooterAction {
 }
 
 pub fn render(frame: &mut Frame, area: Rect, app: &App) {
-    let mut spans = vec![];
-
-    // Create consistent tab bar with highlighting for active tab
-    // Console tab
-    if app.current_tab == Tab::Console {
-        spans.push(Span::styled(
-            " [C]onsole ",
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ));
-    } else {
-        spans.push(Span::styled(
-            " [C]",
-            Style::default().fg(Color::Cyan),
-        ));
-        spans.push(Span::styled(
-            "onsole ",
-            Style::default().fg(Color::White),
-        ));
-    }
-
-    spans.push(Span::raw("│ "));
-
-    // Position tab
-    if app.current_tab == Tab::Position {
-        spans.push(Span::styled(
-            " [P]osition ",
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ));
-    } else {
-        spans.push(Span::styled(
-            " [P]",
-            Style::default().fg(Color::Cyan),
-        ));
-        spans.push(Span::styled(
-            "osition ",
-            Style::default().fg(Color::White),
-        ));
-    }
-
-    spans.push(Span::raw("│ "));
-
-    // Jobs tab
-    if app.current_tab == Tab::Jobs {
-        spans.push(Span::styled(
-            " [J]obs ",
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ));
-    } else {
-        spans.push(Span::styled(
-            " [J]",
-            Style::default().fg(Color::Cyan),
-        ));
-        spans.push(Span::styled(
-            "obs ",
-            Style::default().fg(Color::White),
-        ));
-    }
-
-    spans.push(Span::raw("│ "));
-
-    // Help tab
-    if app.current_tab == Tab::Help {
-        spans.push(Span::styled(
-            " [H]elp ",
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ));
-    } else {
-        spans.push(Span::styled(
-            " [H]",
-            Style::default().fg(Color::Cyan),
-        ));
-        spans.push(Span::styled(
-            "elp ",
-            Style::default().fg(Color::White),
-        ));
-    }
-
-    // Add some spacing
-    spans.push(Span::raw(" │ "));
-
-    // Context-sensitive controls based on current tab
-    match app.current_tab {
-        Tab::Console => {
-            spans.extend(vec![
-                Span::styled("[I]", Style::default().fg(Color::Yellow)),
-                Span::raw("nput "),
-                Span::styled("[↑↓]", Style::default().fg(Color::Yellow)),
-                Span::raw("History "),
-                Span::styled("[Enter]", Style::default().fg(Color::Green)),
-                Span::raw("Send "),
-            ]);
-        }
-        Tab::Position => {
-            spans.extend(vec![
-                Span::styled("[X/Y/Z]", Style::default().fg(Color::Yellow)),
-                Span::raw("Home "),
-                Span::styled("[A]", Style::default().fg(Color::Yellow)),
-                Span::raw("ll "),
-            ]);
-        }
-        Tab::Jobs => {
-            spans.extend(vec![
-                Span::styled("[↑↓]", Style::default().fg(Color::Yellow)),
-                Span::raw("Nav "),
-                Span::styled("[Enter]", Style::default().fg(Color::Green)),
-                Span::raw("Start "),
-                Span::styled("[R]", Style::default().fg(Color::Yellow)),
-                Span::raw("efresh "),
-            ]);
-        }
-        Tab::Help => {
-            spans.extend(vec![
-                Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
-                Span::raw("Back "),
-            ]);
-        }
-    }
-
-    // Global panel toggles (work from all tabs)
-    spans.push(Span::raw("│ "));
-    spans.extend(vec![
-        Span::styled("[T]", Style::default().fg(Color::Magenta)),
-        Span::raw("emp "),
-        Span::styled("[L]", Style::default().fg(Color::Magenta)),
-        Span::raw("oc "),
-    ]);
-
-    spans.push(Span::raw("│ "));
-    spans.push(Span::styled("[Q]", Style::default().fg(Color::Red)));
-    spans.push(Span::raw("uit"));
+    let spans: Vec<Span> = footer_spans(app.current_tab)
+        .into_iter()
+        .map(|(span, _)| span)
+        .collect();
 
     let footer = Paragraph::new(Line::from(spans))
         .style(Style::default().bg(Color::DarkGray).fg(Color::White));
@@ -169,163 +31,169 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
 /// Calculate the bounds for each clickable element in the footer
 /// Returns a vector of (FooterAction, Rect) tuples representing clickable areas
 pub fn get_footer_bounds(area: Rect, current_tab: Tab) -> Vec<(FooterAction, Rect)> {
-    let mut bounds = Vec::new();
-    let y = area.y;
+    let mut bounds: Vec<(FooterAction, Rect)> = Vec::new();
     let mut x = area.x;
 
-    // Console: " [C]onsole "
-    bounds.push((
-        FooterAction::Tab(Tab::Console),
-        Rect {
-            x,
-            y,
-            width: 11,
-            height: 1,
-        },
-    ));
-    x += 11 + 2;
-
-    // Position: " [P]osition "
-    bounds.push((
-        FooterAction::Tab(Tab::Position),
-        Rect {
-            x,
-            y,
-            width: 12,
-            height: 1,
-        },
-    ));
-    x += 12 + 2;
+    for (span, action) in footer_spans(current_tab) {
+        let width = span.width() as u16;
+        if let Some(action) = action {
+            // A tab label may be split over two spans; merge them into one area
+            match bounds.last_mut() {
+                Some((last, rect)) if *last == action && rect.x + rect.width == x => {
+                    rect.width += width;
+                }
+                _ => bounds.push((action, Rect { x, y: area.y, width, height: 1 })),
+            }
+        }
+        x += width;
+    }
 
-    // Jobs: " [J]obs "
-    bounds.push((
-        FooterAction::Tab(Tab::Jobs),
-        Rect {
-            x,
-            y,
-            width: 8,
-            height: 1,
-        },
-    ));
-    x += 8 + 2;
+    bounds
+}
 
-    // Help: " [H]elp "
-    bounds.push((
-        FooterAction::Tab(Tab::Help),
-        Rect {
-            x,
-            y,
-            width: 8,
-            height: 1,
-        },
-    ));
-    
-    // Add spacing for separator
-    x += 10 + 3; // " │ "
+/// Footer contents, with the action each span triggers when clicked
+fn footer_spans(current_tab: Tab) -> Vec<(Span<'static>, Option<FooterAction>)> {
+    let mut spans = vec![];
 
-    // Context-sensitive controls - calculate their positions
-    match current_tab {
-        Tab::Console => {
-            // "[I]nput [↑↓]History [Enter]Send "
-            x += 7 + 11 + 12; // Skip context shortcuts
-        }
-        Tab::Position => {
-            // "[X/Y/Z]Home [A]ll "
-            x += 11 + 5; // Skip context shortcuts
-        }
-        Tab::Jobs => {
-            // "[↑↓]Nav [Enter]Start [R]efresh "
-            x += 8 + 12 + 13; // Skip context shortcuts
+    // Create consistent tab bar with highlighting for active tab
+    for (i, tab) in Tab::all().iter().enumerate() {
+        if i > 0 {
+            spans.push((Span::raw("│ "), None));
         }
-        Tab::Help => {
-            // "[Esc]Back "
-            // This one is special - make it clickable!
-            bounds.push((
-                FooterAction::Escape,
-                Rect {
-                    x,
-                    y,
-                    width: 10, // "[Esc]Back "
-                    height: 1,
-                },
+
+        // Tab names start with their key: " [C]onsole ", " [M]acros "
+        let first = tab.key().to_ascii_uppercase();
+        let rest = tab.name().strip_prefix(first).unwrap_or(tab.name());
+        let action = Some(FooterAction::Tab(*tab));
+        if *tab == current_tab {
+            spans.push((
+                Span::styled(
+                    format!(" [{}]{} ", first, rest),
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                action,
+            ));
+        } else {
+            spans.push((
+                Span::styled(format!(" [{}]", first), Style::default().fg(Color::Cyan)),
+                action,
+            ));
+            spans.push((
+                Span::styled(format!("{} ", rest), Style::default().fg(Color::White)),
+                action,
             ));
-            x += 10;
         }
     }
 
-    // Separator before Quit and global toggles
-    x += 2; // "│ "
-    // "[T]emp [L]oc │ "
-    x += 6 + 5 + 3;
-
-    // Quit: "[Q]uit"
-    bounds.push((
-        FooterAction::Quit,
-        Rect {
-            x,
-            y,
-            width: 6, // "[Q]uit"
-            height: 1,
-        },
-    ));
-
-    bounds
-}
-
-/// Calculate the bounds for each tab button (deprecated, use get_footer_bounds instead)
-/// Returns a vector of (Tab, Rect) tuples representing clickable areas
-#[allow(dead_code)]
-pub fn get_tab_bounds(area: Rect) -> Vec<(Tab, Rect)> {
-    let mut bounds = Vec::new();
-    let y = area.y;
-    let mut x = area.x;
-
-    // Console: " [C]onsole "
-    bounds.push((
-        Tab::Console,
-        Rect {
-            x,
-            y,
-            width: 11,
-            height: 1,
-        },
-    ));
-    x += 11 + 2;
+    // Add some spacing
+    spans.push((Span::raw(" │ "), None));
 
-    // Position: " [P]osition "
-    bounds.push((
-        Tab::Position,
-        Rect {
-            x,
-            y,
-            width: 12,
-            height: 1,
-        },
-    ));
-    x += 12 + 2;
+    // Context-sensitive controls based on current tab
+    let context: Vec<Span> = match current_tab {
+        Tab::Main => vec![
+            Span::styled("[P]", Style::default().fg(Color::Yellow)),
+            Span::raw("reheat "),
+            Span::styled("[C]", Style::default().fg(Color::Yellow)),
+            Span::raw("ooldown "),
+            Span::styled("[Space]", Style::default().fg(Color::Yellow)),
+            Span::raw("Pause "),
+            Span::styled("[X]", Style::default().fg(Color::Red)),
+            Span::raw("Cancel "),
+        ],
+        Tab::Console => vec![
+            Span::styled("[I]", Style::default().fg(Color::Yellow)),
+            Span::raw("nput "),
+            Span::styled("[Tab]", Style::default().fg(Color::Yellow)),
+            Span::raw("Complete "),
+            Span::styled("[↑↓]", Style::default().fg(Color::Yellow)),
+            Span::raw("History "),
+            Span::styled("[Enter]", Style::default().fg(Color::Green)),
+            Span::raw("Send "),
+            Span::styled("[PgUp]", Style::default().fg(Color::Yellow)),
+            Span::raw("Scroll "),
+            Span::styled("[/]", Style::default().fg(Color::Yellow)),
+            Span::raw("Search "),
+            Span::styled("[^F]", Style::default().fg(Color::Yellow)),
+            Span::raw("Filter "),
+        ],
+        Tab::Macros => vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Green)),
+            Span::raw("Run "),
+            Span::styled("[*]", Style::default().fg(Color::Yellow)),
+            Span::raw("Favourite "),
+            Span::styled("[1-9]", Style::default().fg(Color::Yellow)),
+            Span::raw("Run favourite "),
+        ],
+        Tab::Position => vec![
+            Span::styled("[X/Y/Z]", Style::default().fg(Color::Yellow)),
+            Span::raw("Home "),
+            Span::styled("[A]", Style::default().fg(Color::Yellow)),
+            Span::raw("ll "),
+            Span::styled("[←→↑↓]", Style::default().fg(Color::Yellow)),
+            Span::raw("Jog "),
+            Span::styled("[+/-]", Style::default().fg(Color::Yellow)),
+            Span::raw("Step "),
+        ],
+        Tab::Graphs => vec![
+            Span::styled("[1-9]", Style::default().fg(Color::Yellow)),
+            Span::raw("Series "),
+            Span::styled("[W]", Style::default().fg(Color::Yellow)),
+            Span::raw("indow "),
+        ],
+        Tab::BedMesh => vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Green)),
+            Span::raw("Load "),
+            Span::styled("[N]", Style::default().fg(Color::Yellow)),
+            Span::raw("ew "),
+            Span::styled("[S]", Style::default().fg(Color::Yellow)),
+            Span::raw("ave "),
+            Span::styled("[D]", Style::default().fg(Color::Yellow)),
+            Span::raw("elete "),
+            Span::styled("[V]", Style::default().fg(Color::Yellow)),
+            Span::raw("iew "),
+        ],
+        Tab::Files => vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Green)),
+            Span::raw("Open/Print "),
+            Span::styled("[⌫]", Style::default().fg(Color::Yellow)),
+            Span::raw("Up "),
+            Span::styled("[S]", Style::default().fg(Color::Yellow)),
+            Span::raw("ort "),
+            Span::styled("[O]", Style::default().fg(Color::Yellow)),
+            Span::raw("rder "),
+        ],
+        Tab::Jobs => vec![
+            Span::styled("[←→]", Style::default().fg(Color::Yellow)),
+            Span::raw("Page "),
+            Span::styled("[Enter]", Style::default().fg(Color::Green)),
+            Span::raw("Reprint "),
+            Span::styled("[D]", Style::default().fg(Color::Yellow)),
+            Span::raw("elete "),
+            Span::styled("[R]", Style::default().fg(Color::Yellow)),
+            Span::raw("efresh "),
+        ],
+        Tab::Help => {
+            // This one is special - make it clickable!
+            spans.push((Span::styled("[Esc]", Style::default().fg(Color::Yellow)), Some(FooterAction::Escape)));
+            spans.push((Span::raw("Back "), Some(FooterAction::Escape)));
+            vec![]
+        }
+    };
+    spans.extend(context.into_iter().map(|span| (span, None)));
 
-    // Jobs: " [J]obs "
-    bounds.push((
-        Tab::Jobs,
-        Rect {
-            x,
-            y,
-            width: 8,
-            height: 1,
-        },
-    ));
-    x += 8 + 2;
+    // Global panel toggles (work from all tabs)
+    spans.push((Span::raw("│ "), None));
+    spans.push((Span::styled("[T]", Style::default().fg(Color::Magenta)), None));
+    spans.push((Span::raw("emp "), None));
+    spans.push((Span::styled("[L]", Style::default().fg(Color::Magenta)), None));
+    spans.push((Span::raw("oc "), None));
 
-    // Help: " [H]elp "
-    bounds.push((
-        Tab::Help,
-        Rect {
-            x,
-            y,
-            width: 8,
-            height: 1,
-        },
-    ));
+    spans.push((Span::raw("│ "), None));
+    spans.push((Span::styled("[Q]", Style::default().fg(Color::Red)), Some(FooterAction::Quit)));
+    spans.push((Span::raw("uit"), Some(FooterAction::Quit)));
 
-    bounds
+    spans
 }
@@ -0,0 +1,236 @@
+use crate::tui::app::App;
+use crate::tui::temperature_history::{now, TemperatureSeries};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+/// Series colours, assigned in legend order
+const COLORS: &[Color] = &[
+    Color::Red,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Green,
+    Color::Magenta,
+    Color::Blue,
+    Color::LightRed,
+    Color::LightCyan,
+    Color::LightGreen,
+    Color::LightMagenta,
+];
+
+/// Chart points: seconds relative to now, °C
+type Points = Vec<(f64, f64)>;
+
+/// Width of the legend beside the chart
+const LEGEND_WIDTH: u16 = 30;
+
+fn series_color(index: usize) -> Color {
+    COLORS[index % COLORS.len()]
+}
+
+pub fn render(frame: &mut Frame, area: Rect, app: &App) {
+    // Add temperature and position bars at top if enabled
+    let mut constraints = vec![];
+    if app.panel_visibility.temperature {
+        constraints.push(Constraint::Length(1));
+    }
+    if app.panel_visibility.position {
+        constraints.push(Constraint::Length(1));
+    }
+    constraints.push(Constraint::Min(0)); // Chart and legend
+
+    let main_area = if app.panel_visibility.temperature || app.panel_visibility.position {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        let mut chunk_idx = 0;
+        if app.panel_visibility.temperature {
+            super::temperatures::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        if app.panel_visibility.position {
+            super::position_bar::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        chunks[chunk_idx]
+    } else {
+        area
+    };
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(20), Constraint::Length(LEGEND_WIDTH)])
+        .split(main_area);
+
+    render_chart(frame, columns[0], app);
+    render_legend(frame, columns[1], app);
+}
+
+fn render_chart(frame: &mut Frame, area: Rect, app: &App) {
+    let history = &app.temperature_history;
+    let window = history.window() as f64;
+    let block = Block::default()
+        .title(format!(" Temperature History ({}) ", format_window(history.window())))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    // Points relative to now, so the x axis runs from -window to 0
+    let now = now();
+    let in_window = |samples: &std::collections::VecDeque<(f64, f64)>| -> Points {
+        samples
+            .iter()
+            .map(|(time, value)| (time - now, *value))
+            .filter(|(time, _)| *time >= -window)
+            .collect()
+    };
+
+    let mut lines: Vec<(usize, Points, Points)> = history
+        .series
+        .iter()
+        .enumerate()
+        .filter(|(_, series)| series.visible)
+        .map(|(i, series)| {
+            // A target that was never set would only flatten the chart at 0°
+            let targets = if series.targets.iter().any(|(_, t)| *t > 0.0) {
+                in_window(&series.targets)
+            } else {
+                Vec::new()
+            };
+            (i, in_window(&series.temperatures), targets)
+        })
+        .collect();
+    lines.retain(|(_, temps, _)| !temps.is_empty());
+
+    if lines.is_empty() {
+        let message = if app.printer.connected {
+            "Waiting for temperature data..."
+        } else {
+            "Not connected to printer"
+        };
+        let paragraph = Paragraph::new(Span::styled(message, Style::default().fg(Color::DarkGray)))
+            .block(block)
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let (min, max) = lines
+        .iter()
+        .flat_map(|(_, temps, targets)| temps.iter().chain(targets.iter()))
+        .fold((f64::MAX, f64::MIN), |(min, max), (_, value)| (min.min(*value), max.max(*value)));
+    let low = (min - 5.0).floor().max(0.0);
+    let high = (max + 5.0).ceil();
+
+    let mut datasets = Vec::new();
+    for (i, temps, targets) in &lines {
+        let color = series_color(*i);
+        datasets.push(
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(temps),
+        );
+        if !targets.is_empty() {
+            datasets.push(
+                Dataset::default()
+                    .marker(Marker::Dot)
+                    .graph_type(GraphType::Scatter)
+                    .style(Style::default().fg(color).add_modifier(Modifier::DIM))
+                    .data(targets),
+            );
+        }
+    }
+
+    let axis_style = Style::default().fg(Color::DarkGray);
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .style(axis_style)
+                .bounds([-window, 0.0])
+                .labels([
+                    format!("-{}", format_window(history.window())),
+                    format!("-{}", format_window(history.window() / 2)),
+                    "now".to_string(),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(axis_style)
+                .bounds([low, high])
+                .labels([
+                    format!("{:.0}°", low),
+                    format!("{:.0}°", (low + high) / 2.0),
+                    format!("{:.0}°", high),
+                ]),
+        );
+    frame.render_widget(chart, area);
+}
+
+fn render_legend(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .title(" Series ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut content: Vec<Line> = app
+        .temperature_history
+        .series
+        .iter()
+        .enumerate()
+        .map(|(i, series)| legend_line(i, series))
+        .collect();
+
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "Dots mark the target",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let paragraph = Paragraph::new(content).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+fn legend_line(index: usize, series: &TemperatureSeries) -> Line<'static> {
+    // Only the first nine series have a toggle key
+    let key = if index < 9 { format!("{} ", index + 1) } else { "  ".to_string() };
+    let (marker, style) = if series.visible {
+        ("●", Style::default().fg(series_color(index)))
+    } else {
+        ("○", Style::default().fg(Color::DarkGray))
+    };
+
+    let mut reading = series.current().map(|t| format!("{:.1}°", t)).unwrap_or_default();
+    if let Some(target) = series.target().filter(|t| *t > 0.0) {
+        reading.push_str(&format!("/{:.0}°", target));
+    }
+
+    Line::from(vec![
+        Span::styled(key, Style::default().fg(Color::Yellow)),
+        Span::styled(format!("{} ", marker), style),
+        Span::styled(
+            format!("{:<12.12}", series.name),
+            if series.visible { Style::default() } else { Style::default().fg(Color::DarkGray) },
+        ),
+        Span::styled(format!("{:>12}", reading), Style::default().fg(Color::Gray)),
+    ])
+}
+
+fn format_window(seconds: u64) -> String {
+    if seconds >= 60 && seconds.is_multiple_of(60) {
+        format!("{}m", seconds / 60)
+    } else if seconds >= 60 {
+        format!("{}m{}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::tui::app::App;
+use crate::tui::print_progress::{self, Estimates};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -77,6 +78,21 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) -> Vec<(Rect, String)> {
             ));
         }
         
+        // Progress and estimated finish while a print is running
+        if matches!(state_text.as_str(), "printing" | "paused") {
+            spans.push(Span::styled(
+                format!(" | {:.0}% ", app.printer.display_status.progress * 100.0),
+                Style::default().fg(Color::Green),
+            ));
+            let estimates = Estimates::new(&app.printer.print_stats, app.print_metadata());
+            if let Some(remaining) = estimates.average() {
+                spans.push(Span::styled(
+                    format!("ETA {} ", print_progress::finish_time(remaining)),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+        }
+
         // Power devices - clickable to toggle
         if !app.printer.power_devices.is_empty() {
             spans.push(Span::styled(
@@ -24,33 +24,87 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(Span::styled("Global Keys:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
         Line::from("  m - Main dashboard"),
         Line::from("  c - Console (GCode terminal)"),
+        Line::from("  M - Macros (run Klipper macros)"),
         Line::from("  p - Position (toolhead & homing)"),
+        Line::from("  g - Graphs (temperature history)"),
+        Line::from("  b - Bed Mesh (heatmap and profiles)"),
+        Line::from("  f - Files (G-code browser)"),
         Line::from("  j - Jobs (print history)"),
-        Line::from("  h or ? - This help screen"),
+        Line::from("  h or ? - This help screen (Esc to go back)"),
         Line::from("  q - Quit application"),
         Line::from("  Ctrl+C - Emergency stop (if connected)"),
+        Line::from("  P - Preheat from a material preset"),
+        Line::from("  C - Cool down (turn off all heaters; asks first mid-print)"),
+        Line::from("  Space - Pause or resume the running print"),
+        Line::from("  X - Cancel the running print"),
+        Line::from("  t - Toggle temperature bar"),
+        Line::from("  l - Toggle position bar"),
         Line::from(""),
         Line::from(Span::styled("Main Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-        Line::from("  t - Toggle temperature panel"),
-        Line::from("  s - Toggle system info panel"),
+        Line::from("  System info, print progress and every heater, sensor and fan at a glance"),
+        Line::from("  Progress shows layers, time left (file/slicer/filament) and the finish time"),
         Line::from(""),
         Line::from(Span::styled("Console Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
         Line::from("  i - Enter editing mode"),
         Line::from("  Enter - Send command"),
+        Line::from("  Tab - Complete command, parameter or value (Tab/↑↓ pick, Enter inserts)"),
+        Line::from("  ←→ - Move the cursor, Delete - Delete under the cursor"),
         Line::from("  Esc - Cancel editing"),
-        Line::from("  ↑↓ - Navigate command history"),
+        Line::from("  ↑↓ - Navigate command history (matching the typed prefix)"),
+        Line::from("  Ctrl+R - Reverse search history"),
+        Line::from("  PgUp/PgDn or mouse wheel - Scroll output, Ctrl+Home/End - Oldest/follow"),
+        Line::from("  Home/End - Start/end of line, or oldest/follow when not typing"),
+        Line::from("  / - Search output, n/N - Older/newer match (when not typing)"),
+        Line::from("  Ctrl+F - Filter output by category or pattern, Ctrl+E - Dismiss errors"),
+        Line::from("  SAVE_CONFIG and FIRMWARE_RESTART ask for confirmation first"),
+        Line::from(""),
+        Line::from(Span::styled("Macros Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from("  ↑↓ - Select a macro"),
+        Line::from("  Enter - Run it, filling in its parameters first if it has any"),
+        Line::from("  * - Pin or unpin as a favourite; favourites are listed first"),
+        Line::from("  1-9 - Run a numbered favourite"),
+        Line::from("  In the form: Tab/↑↓ - Move between fields, Enter - Run, Esc - Cancel"),
         Line::from(""),
         Line::from(Span::styled("Position Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
         Line::from("  x - Home X axis"),
         Line::from("  y - Home Y axis"),
         Line::from("  z - Home Z axis"),
         Line::from("  a - Home all axes"),
+        Line::from("  ←/→ and ↑/↓ - Jog X and Y (homed axes only)"),
+        Line::from("  PgUp/PgDn - Jog Z"),
+        Line::from("  +/- - Change jog distance (0.1, 1, 10 or 100mm)"),
+        Line::from(""),
+        Line::from(Span::styled("Graphs Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from("  1-9 - Show or hide a series"),
+        Line::from("  w - Cycle the time window (1, 5 or 20 minutes)"),
+        Line::from(""),
+        Line::from(Span::styled("Bed Mesh Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from("  ↑↓ - Select a saved profile, Enter - Load it"),
+        Line::from("  n - Probe a new mesh (BED_MESH_CALIBRATE), homing first if needed"),
+        Line::from("  s - Save the active mesh as a profile"),
+        Line::from("  d - Remove the selected profile"),
+        Line::from("  v - Switch between probed and interpolated points"),
+        Line::from(""),
+        Line::from(Span::styled("Files Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from("  ↑↓ - Navigate files"),
+        Line::from("  Enter or → - Open directory (Enter on a file starts printing it)"),
+        Line::from("  Backspace or ← - Parent directory"),
+        Line::from("  s - Cycle sort (name, date, size, print time)"),
+        Line::from("  o - Reverse sort order"),
+        Line::from("  r - Refresh file list"),
         Line::from(""),
         Line::from(Span::styled("Jobs Tab:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
         Line::from("  ↑↓ or j/k - Navigate job list"),
-        Line::from("  Enter - Start selected print job"),
+        Line::from("  ←→ or PgUp/PgDn - Previous/next page"),
+        Line::from("  Enter - Reprint selected job"),
+        Line::from("  d - Delete selected job from history"),
         Line::from("  r - Refresh job list"),
         Line::from(""),
+        Line::from(Span::styled("Confirmation Dialogs:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from("  y / n - Confirm or cancel"),
+        Line::from("  ←→ or Tab - Move between buttons, Enter - Press the focused button"),
+        Line::from("  Esc - Cancel; buttons can also be clicked"),
+        Line::from(""),
         Line::from(Span::styled("Features:", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))),
         Line::from("  ✓ Real-time temperature monitoring"),
         Line::from("  ✓ Live position tracking"),
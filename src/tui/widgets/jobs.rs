@@ -1,4 +1,6 @@
-use crate::tui::app::App;
+use crate::tui::app::{App, JOB_PAGE_SIZE};
+use crate::tui::printer::{JobTotals, PrintJob};
+use crate::format::format_duration;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -7,6 +9,9 @@ use ratatui::{
     Frame,
 };
 
+/// Height of the totals header above the job list
+pub const HEADER_HEIGHT: u16 = 3;
+
 pub fn render(frame: &mut Frame, area: Rect, app: &App) {
     // Add temperature and position lines at top if enabled
     let mut constraints = vec![];
@@ -38,8 +43,9 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         area
     };
     
+    let page_count = app.job_count.div_ceil(JOB_PAGE_SIZE).max(1);
     let block = Block::default()
-        .title(" Print Jobs ")
+        .title(format!(" Job History (page {}/{}) ", app.job_page + 1, page_count))
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
@@ -64,6 +70,13 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         return;
     }
 
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(HEADER_HEIGHT), Constraint::Min(0)])
+        .split(main_area);
+    render_totals(frame, chunks[0], app.job_totals.as_ref());
+    let list_area = chunks[1];
+
     if app.job_list.items.is_empty() {
         let content = vec![
             Line::from(""),
@@ -72,49 +85,25 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
                 Style::default().fg(Color::Yellow),
             )),
             Line::from(""),
-            Line::from("Upload gcode files to see them here"),
+            Line::from("Finished prints will appear here"),
             Line::from(""),
             Line::from(Span::styled(
-                "Fetching jobs from Moonraker...",
+                "Fetching history from Moonraker...",
                 Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
             )),
         ];
         let paragraph = Paragraph::new(content)
             .block(block)
             .alignment(Alignment::Center);
-        frame.render_widget(paragraph, main_area);
+        frame.render_widget(paragraph, list_area);
         return;
     }
 
-    // Create list items from jobs (files)
     let items: Vec<ListItem> = app
         .job_list
         .items
         .iter()
-        .map(|job| {
-            // Format estimated time if available
-            let duration = if job.total_duration > 0.0 {
-                format_duration(job.total_duration)
-            } else {
-                "N/A".to_string()
-            };
-            
-            // Format filament if available
-            let filament = if job.filament_used > 0.0 {
-                format!("{:.1}mm", job.filament_used)
-            } else {
-                "N/A".to_string()
-            };
-
-            let content = Line::from(vec![
-                Span::styled("📄 ", Style::default().fg(Color::White)),
-                Span::styled(&job.filename, Style::default().fg(Color::Cyan)),
-                Span::raw(format!(" │ Est: {}", duration)),
-                Span::raw(format!(" │ Fil: {}", filament)),
-            ]);
-
-            ListItem::new(content)
-        })
+        .map(|job| ListItem::new(job_line(job)))
         .collect();
 
     let list = List::new(items)
@@ -126,37 +115,94 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         )
         .highlight_symbol("▶ ");
 
-    frame.render_stateful_widget(list, main_area, &mut app.job_list.state.clone());
+    frame.render_stateful_widget(list, list_area, &mut app.job_list.state.clone());
 
-    // Show help text at bottom if there are jobs
-    if !app.job_list.items.is_empty() {
-        let help_text = " ↑↓ Navigate │ Enter Start Print │ Press 'm' for Main ";
+    // Show help text on the bottom border
+    let help_text = " ↑↓ Navigate │ ←→ Page │ Enter Reprint │ d Delete │ r Refresh ";
+    if list_area.height > 2 {
         let help_area = Rect {
-            x: main_area.x + 1,
-            y: main_area.y + main_area.height - 2,
-            width: main_area.width - 2,
+            x: list_area.x + 1,
+            y: list_area.y + list_area.height - 1,
+            width: list_area.width.saturating_sub(2),
             height: 1,
         };
-        
         let help = Paragraph::new(Line::from(vec![
             Span::styled(help_text, Style::default().fg(Color::DarkGray)),
         ]));
-        
         frame.render_widget(help, help_area);
     }
 }
 
-fn format_duration(seconds: f64) -> String {
-    let secs = seconds as u64;
-    let hours = secs / 3600;
-    let minutes = (secs % 3600) / 60;
-    let seconds = secs % 60;
-    
-    if hours > 0 {
-        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {:02}s", minutes, seconds)
+/// Lifetime totals from Moonraker's history
+fn render_totals(frame: &mut Frame, area: Rect, totals: Option<&JobTotals>) {
+    let block = Block::default()
+        .title(" Totals ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    let content = match totals {
+        Some(totals) => {
+            let label = Style::default().fg(Color::Gray);
+            let value = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+            Line::from(vec![
+                Span::styled("Jobs: ", label),
+                Span::styled(totals.total_jobs.to_string(), value),
+                Span::styled("  │  Print time: ", label),
+                Span::styled(format_duration(totals.total_print_time), value),
+                Span::styled("  │  Filament: ", label),
+                Span::styled(format_filament(totals.total_filament_used), value),
+                Span::styled("  │  Longest: ", label),
+                Span::styled(format_duration(totals.longest_print), value),
+            ])
+        }
+        None => Line::from(Span::styled(
+            "No totals available",
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
+
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+fn job_line(job: &PrintJob) -> Line<'_> {
+    let (status, color) = match job.status.as_str() {
+        "completed" => ("done", Color::Green),
+        "in_progress" => ("printing", Color::Cyan),
+        "cancelled" => ("cancelled", Color::Yellow),
+        "klippy_shutdown" | "klippy_disconnect" | "server_exit" => ("aborted", Color::Red),
+        "error" => ("error", Color::Red),
+        other => (other, Color::Gray),
+    };
+    let started = chrono::DateTime::from_timestamp(job.start_time as i64, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    let duration = if job.print_duration > 0.0 {
+        format_duration(job.print_duration)
+    } else {
+        "-".to_string()
+    };
+
+    // Files that have since been removed can't be reprinted
+    let filename_style = if job.exists {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    Line::from(vec![
+        Span::styled(format!("{:<10}", status), Style::default().fg(color)),
+        Span::styled(format!("{}  ", started), Style::default().fg(Color::Gray)),
+        Span::raw(format!("{:>11}  ", duration)),
+        Span::raw(format!("{:>8}  ", format_filament(job.filament_used))),
+        Span::styled(job.filename.as_str(), filename_style),
+    ])
+}
+
+/// Filament length in metres once it gets long, otherwise millimetres
+fn format_filament(mm: f64) -> String {
+    if mm >= 1000.0 {
+        format!("{:.2}m", mm / 1000.0)
     } else {
-        format!("{}s", seconds)
+        format!("{:.0}mm", mm)
     }
 }
@@ -0,0 +1,250 @@
+use crate::tui::app::App;
+use crate::tui::macro_browser::{self, MAX_NUMBERED_FAVOURITES};
+use crate::tui::modal::centered_rect;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width of the details panel beside the macro list
+const DETAILS_WIDTH: u16 = 44;
+
+/// Width of the parameter names in the form
+const FIELD_NAME_WIDTH: usize = 16;
+
+pub fn render(frame: &mut Frame, area: Rect, app: &App) {
+    // Add temperature and position bars at top if enabled
+    let mut constraints = vec![];
+    if app.panel_visibility.temperature {
+        constraints.push(Constraint::Length(1));
+    }
+    if app.panel_visibility.position {
+        constraints.push(Constraint::Length(1));
+    }
+    constraints.push(Constraint::Min(0)); // List and details
+
+    let main_area = if app.panel_visibility.temperature || app.panel_visibility.position {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        let mut chunk_idx = 0;
+        if app.panel_visibility.temperature {
+            super::temperatures::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        if app.panel_visibility.position {
+            super::position_bar::render(frame, chunks[chunk_idx], app);
+            chunk_idx += 1;
+        }
+        chunks[chunk_idx]
+    } else {
+        area
+    };
+
+    let browser = &app.macro_browser;
+    let block = Block::default()
+        .title(format!(" Macros ({}) ", browser.list.items.len()))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if browser.list.items.is_empty() {
+        let message = if app.printer.connected {
+            "No gcode_macro sections found in the printer config"
+        } else {
+            "Not connected to printer"
+        };
+        let paragraph = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(message, Style::default().fg(Color::Gray))),
+        ])
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, main_area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(DETAILS_WIDTH)])
+        .split(main_area);
+
+    let items: Vec<ListItem> = browser
+        .list
+        .items
+        .iter()
+        .map(|name| {
+            let position = browser.favourites.iter().position(|favourite| favourite == name);
+            let number = match position {
+                Some(i) if i < MAX_NUMBERED_FAVOURITES => format!("{} ", i + 1),
+                _ => "  ".to_string(),
+            };
+            let star = if position.is_some() { "★ " } else { "  " };
+            let mut spans = vec![
+                Span::styled(number, Style::default().fg(Color::DarkGray)),
+                Span::styled(star, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    name.clone(),
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                ),
+            ];
+            if let Some(description) = macro_browser::description(&app.command_catalog, name) {
+                spans.push(Span::styled(
+                    format!("  {}", description),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    frame.render_stateful_widget(list, chunks[0], &mut browser.list.state.clone());
+
+    render_details(frame, chunks[1], app);
+}
+
+/// Description and parameters of the selected macro
+fn render_details(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let Some(name) = app.macro_browser.list.selected() else {
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let mut content = vec![Line::from(Span::styled(
+        name.clone(),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(description) = macro_browser::description(&app.command_catalog, name) {
+        content.push(Line::from(Span::styled(description, Style::default().fg(Color::Gray))));
+    }
+    content.push(Line::from(""));
+
+    match app.command_catalog.macro_info.get(name) {
+        Some(info) if !info.params.is_empty() => {
+            content.push(Line::from(Span::styled(
+                "Parameters",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            for param in &info.params {
+                let default = match &param.default {
+                    Some(value) => Span::styled(format!(" = {}", value), Style::default().fg(Color::DarkGray)),
+                    None => Span::styled(" (no default)", Style::default().fg(Color::DarkGray)),
+                };
+                content.push(Line::from(vec![Span::raw(format!("  {}", param.name)), default]));
+            }
+        }
+        Some(_) => content.push(Line::from(Span::styled(
+            "No parameters",
+            Style::default().fg(Color::DarkGray),
+        ))),
+        None => content.push(Line::from(Span::styled(
+            "Not in the loaded config",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    content.push(Line::from(""));
+    let favourite = if app.macro_browser.is_favourite(name) { "Unpin" } else { "Pin" };
+    content.push(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::raw(" Run  "),
+        Span::styled("*", Style::default().fg(Color::Yellow)),
+        Span::raw(format!(" {}  ", favourite)),
+        Span::styled("1-9", Style::default().fg(Color::Yellow)),
+        Span::raw(" Favourite"),
+    ]));
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the macro parameter form as a popup over the current tab
+pub fn render_form(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(form) = &app.macro_browser.form else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(format!(" {} ", form.name))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Tab/↑↓ Field │ Enter Run │ Esc Cancel ").centered())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    // Description, fields, then the command that will be sent
+    let height = form.fields.len() as u16 + 6 + u16::from(form.description.is_some());
+    let popup_area = centered_rect(60, 100, area);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..popup_area
+    };
+    frame.render_widget(Clear, popup_area);
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut content = Vec::new();
+    if let Some(description) = &form.description {
+        content.push(Line::from(Span::styled(description.clone(), Style::default().fg(Color::Gray))));
+    }
+    content.push(Line::from(Span::styled(
+        "Leave a field empty to use the macro's default",
+        Style::default().fg(Color::DarkGray),
+    )));
+    content.push(Line::from(""));
+
+    let mut cursor = None;
+    for (i, field) in form.fields.iter().enumerate() {
+        let selected = i == form.selected;
+        let name_style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut spans = vec![
+            Span::styled(if selected { "▶ " } else { "  " }, name_style),
+            Span::styled(format!("{:<width$}", field.name, width = FIELD_NAME_WIDTH), name_style),
+        ];
+        if field.input.value.is_empty() {
+            let placeholder = field.default.as_deref().unwrap_or("");
+            spans.push(Span::styled(placeholder.to_string(), Style::default().fg(Color::DarkGray)));
+        } else {
+            spans.push(Span::styled(field.input.value.clone(), Style::default().fg(Color::Yellow)));
+        }
+        if selected {
+            cursor = Some((
+                inner.x + 2 + FIELD_NAME_WIDTH as u16 + field.input.cursor_position,
+                inner.y + content.len() as u16,
+            ));
+        }
+        content.push(Line::from(spans));
+    }
+
+    content.push(Line::from(""));
+    content.push(Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Green)),
+        Span::styled(form.command(), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+    ]));
+
+    frame.render_widget(Paragraph::new(content), inner);
+    if let Some(position) = cursor {
+        frame.set_cursor_position(position);
+    }
+}
@@ -1,128 +1,242 @@
 use crate::tui::app::App;
+use crate::tui::print_progress::{self, Estimates};
+use crate::tui::printer::HeaterState;
 use crate::tui::widgets;
+use crate::format::format_duration;
+use crate::tui::widgets::temperatures::get_temp_color;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Gauge, Paragraph},
     Frame,
 };
 
-#[allow(dead_code)]
+/// Width of the heater power bars in the temperature panel
+const POWER_BAR_WIDTH: usize = 10;
+
+/// Dashboard combining system info, print progress and temperatures
 pub fn render(frame: &mut Frame, area: Rect, app: &App) {
     // Calculate dynamic constraints based on panel visibility
     let mut constraints = vec![];
-    
+
     // Temperature line (1 line if visible)
     if app.panel_visibility.temperature {
         constraints.push(Constraint::Length(1));
     }
-    
+
     // Position line (1 line if visible)
     if app.panel_visibility.position {
         constraints.push(Constraint::Length(1));
     }
-    
-    // Main info area (rest of space)
+
+    // System info, print progress, then temperatures in the rest of the space
+    constraints.push(Constraint::Length(8));
+    constraints.push(Constraint::Length(6));
     constraints.push(Constraint::Min(5));
-    
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
         .split(area);
 
     let mut chunk_idx = 0;
-    
+
     // Render temperature line if visible
     if app.panel_visibility.temperature {
         widgets::temperatures::render(frame, chunks[chunk_idx], app);
         chunk_idx += 1;
     }
-    
+
     // Render position line if visible
     if app.panel_visibility.position {
         widgets::position_bar::render(frame, chunks[chunk_idx], app);
         chunk_idx += 1;
     }
 
-    // Render main info area
+    widgets::system_info::render(frame, chunks[chunk_idx], app);
+    render_progress(frame, chunks[chunk_idx + 1], app);
+    render_temperatures(frame, chunks[chunk_idx + 2], app);
+}
+
+/// Progress bar, layers, time-left estimates and the display message
+fn render_progress(frame: &mut Frame, area: Rect, app: &App) {
     let block = Block::default()
-        .title(" Main Dashboard ")
-        .title_alignment(Alignment::Center)
+        .title(" Print Progress ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let mut content = vec![
-        Line::from(""),
-        Line::from(Span::styled(
-            "Main Dashboard",
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
+    let stats = &app.printer.print_stats;
+    if !app.printer.connected || stats.filename.is_empty() {
+        let paragraph = Paragraph::new(Span::styled(
+            "No print loaded",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    let color = match stats.state.as_str() {
+        "printing" => Color::Green,
+        "paused" => Color::Yellow,
+        "error" | "cancelled" => Color::Red,
+        _ => Color::Cyan,
+    };
+    let progress = app.printer.display_status.progress.clamp(0.0, 1.0);
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(color).bg(Color::Black))
+        .ratio(progress)
+        .label(format!("{:.1}%  {}", progress * 100.0, stats.state));
+    frame.render_widget(gauge, rows[0]);
+
+    let label = Style::default().fg(Color::Gray);
+    let value = Style::default().fg(Color::White);
+    let metadata = app.print_metadata();
+
+    // Layers, elapsed time and filament
+    let mut details = vec![
+        Span::styled("Elapsed ", label),
+        Span::styled(format_duration(stats.print_duration), value),
     ];
+    if let Some((current, total)) = print_progress::layers(stats, metadata, app.printer.toolhead.position[2]) {
+        details.push(Span::styled("   Layer ", label));
+        details.push(Span::styled(format!("{}/{}", current, total), value));
+    }
+    if stats.filament_used > 0.0 {
+        details.push(Span::styled("   Filament ", label));
+        let total = metadata
+            .and_then(|m| m.filament_total)
+            .map(|total| format!(" / {:.2}m", total / 1000.0))
+            .unwrap_or_default();
+        details.push(Span::styled(format!("{:.2}m{}", stats.filament_used / 1000.0, total), value));
+    }
+    let mut lines = vec![Line::from(details)];
 
-    // Show connection status
-    if app.printer.connected {
-        content.push(Line::from(Span::styled(
-            format!("✓ Connected to {}", app.server_url),
-            Style::default().fg(Color::Green),
-        )));
-        
-        // Show print status if printing
-        if app.printer.print_stats.state == "printing" {
-            content.push(Line::from(""));
-            content.push(Line::from(Span::styled(
-                format!("Printing: {}", app.printer.print_stats.filename),
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-            )));
-            content.push(Line::from(format!(
-                "  Duration: {:.1}s",
-                app.printer.print_stats.print_duration
-            )));
-            content.push(Line::from(format!(
-                "  Filament: {:.2}mm",
-                app.printer.print_stats.filament_used
-            )));
+    // Estimates only mean something while the print is running
+    if matches!(stats.state.as_str(), "printing" | "paused") {
+        let estimates = Estimates::new(stats, metadata);
+        let mut eta = vec![Span::styled("Left ", label)];
+        for (name, estimate) in [("file", estimates.file), ("slicer", estimates.slicer), ("filament", estimates.filament)] {
+            if let Some(estimate) = estimate {
+                eta.push(Span::styled(format!("{} ", name), Style::default().fg(Color::DarkGray)));
+                eta.push(Span::styled(format!("{}   ", format_duration(estimate)), value));
+            }
+        }
+        match estimates.average() {
+            Some(remaining) => {
+                eta.push(Span::styled("Finish ", label));
+                eta.push(Span::styled(
+                    print_progress::finish_time(remaining),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ));
+            }
+            None => eta.push(Span::styled("calculating...", Style::default().fg(Color::DarkGray))),
         }
-    } else {
-        content.push(Line::from(Span::styled(
-            format!("✗ Not connected to {}", app.server_url),
+        lines.push(Line::from(eta));
+    }
+
+    let message = &app.printer.display_status.message;
+    if !message.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Message ", label),
+            Span::styled(message.clone(), Style::default().fg(Color::Yellow)),
+        ]));
+    }
+
+    frame.render_widget(Paragraph::new(lines), rows[1]);
+}
+
+/// Every heater, sensor and fan with targets and heater power
+fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .title(" Temperatures ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    if !app.printer.connected {
+        let paragraph = Paragraph::new(Span::styled(
+            format!("Not connected to {}", app.server_url),
             Style::default().fg(Color::Red),
-        )));
+        ))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let temperatures = &app.printer.temperatures;
+    let mut content: Vec<Line> = temperatures
+        .heaters
+        .iter()
+        .map(|heater| heater_line(heater.label(), heater))
+        .collect();
+
+    for sensor in &temperatures.mcus {
+        content.push(Line::from(vec![
+            Span::styled(format!("{:<12}", sensor.name), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{:>6.1}°", sensor.temperature),
+                Style::default().fg(Color::Blue),
+            ),
+        ]));
+    }
+
+    if !temperatures.fans.is_empty() {
         content.push(Line::from(""));
-        content.push(Line::from("Waiting for connection..."));
+    }
+    for fan in &temperatures.fans {
+        let mut spans = vec![
+            Span::styled(format!("{:<12}", fan.name), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{:>6.0}%", fan.speed * 100.0),
+                Style::default().fg(if fan.speed < 0.01 { Color::DarkGray } else { Color::Cyan }),
+            ),
+        ];
+        if let Some(rpm) = fan.rpm {
+            spans.push(Span::styled(
+                format!("  {:.0} rpm", rpm),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        content.push(Line::from(spans));
     }
 
-    content.push(Line::from(""));
-    content.push(Line::from("Panel Controls:"));
-    content.push(Line::from(Span::styled(
-        if app.panel_visibility.temperature {
-            "  [t] Temperature panel: ON"
-        } else {
-            "  [t] Temperature panel: OFF"
-        },
-        Style::default().fg(if app.panel_visibility.temperature {
-            Color::Green
-        } else {
-            Color::Gray
-        }),
-    )));
-    content.push(Line::from(Span::styled(
-        if app.panel_visibility.position {
-            "  [p] Position bar: ON"
-        } else {
-            "  [p] Position bar: OFF"
-        },
-        Style::default().fg(if app.panel_visibility.position {
-            Color::Green
-        } else {
-            Color::Gray
-        }),
-    )));
-
-    let paragraph = Paragraph::new(content)
-        .block(block)
-        .alignment(Alignment::Left);
+    let paragraph = Paragraph::new(content).block(block);
+    frame.render_widget(paragraph, area);
+}
 
-    frame.render_widget(paragraph, chunks[chunk_idx]);
+fn heater_line(name: String, heater: &HeaterState) -> Line<'static> {
+    let filled = (heater.power.clamp(0.0, 1.0) * POWER_BAR_WIDTH as f64).round() as usize;
+    Line::from(vec![
+        Span::styled(
+            format!("{:<12}", name),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("{:>6.1}°", heater.temperature),
+            Style::default().fg(get_temp_color(heater.temperature, heater.target)),
+        ),
+        Span::styled(
+            format!(" / {:>3.0}°  ", heater.target),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled("█".repeat(filled), Style::default().fg(Color::Red)),
+        Span::styled(
+            "░".repeat(POWER_BAR_WIDTH - filled),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!(" {:>3.0}%", heater.power * 100.0),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
 }
@@ -1,10 +1,16 @@
+pub mod bed_mesh;
 pub mod console;
+pub mod console_filter;
+pub mod files;
 pub mod footer;
+pub mod graphs;
 pub mod header;
 pub mod help;
 pub mod jobs;
+pub mod macros;
 pub mod main;
 pub mod position;
 pub mod position_bar;
+pub mod presets;
 pub mod system_info;
 pub mod temperatures;
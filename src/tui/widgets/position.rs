@@ -1,4 +1,4 @@
-use crate::tui::app::App;
+use crate::tui::app::{App, ConsoleMessage, JOG_STEPS};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -138,13 +138,28 @@ fn render_position_display(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_controls(frame: &mut Frame, area: Rect, _app: &App) {
+fn render_controls(frame: &mut Frame, area: Rect, app: &App) {
     let block = Block::default()
         .title(" Movement Controls ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
 
-    let content = vec![
+    // Selected step is highlighted among the available ones
+    let mut steps = vec![Span::raw("  Step: ")];
+    for (i, step) in JOG_STEPS.iter().enumerate() {
+        let label = format!(" {}mm ", step);
+        if i == app.jog_step_index {
+            steps.push(Span::styled(
+                label,
+                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+        } else {
+            steps.push(Span::styled(label, Style::default().fg(Color::Gray)));
+        }
+    }
+
+    let jog = &app.config.jog;
+    let mut content = vec![
         Line::from(""),
         Line::from(Span::styled(
             "Homing Controls",
@@ -157,14 +172,34 @@ fn render_controls(frame: &mut Frame, area: Rect, _app: &App) {
         Line::from("  a - Home all axes"),
         Line::from(""),
         Line::from(Span::styled(
-            "Jogging (Coming Soon)",
-            Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+            "Jogging",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )),
+        Line::from(""),
+        Line::from(steps),
         Line::from("  Arrow keys - Jog X/Y"),
         Line::from("  PgUp/PgDn - Jog Z"),
         Line::from("  +/- - Change distance"),
+        Line::from(Span::styled(
+            format!(
+                "  Feed: X {:.0}, Y {:.0}, Z {:.0} mm/min",
+                jog.feed_x, jog.feed_y, jog.feed_z
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
     ];
 
+    if let Some(message) = &app.jog_message {
+        let (text, color) = match message {
+            ConsoleMessage::Error(text) => (text, Color::Red),
+            ConsoleMessage::Info(text)
+            | ConsoleMessage::Command(text)
+            | ConsoleMessage::Response(text) => (text, Color::Green),
+        };
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(format!("  {}", text), Style::default().fg(color))));
+    }
+
     let paragraph = Paragraph::new(content)
         .block(block)
         .alignment(Alignment::Left);
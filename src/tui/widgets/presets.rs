@@ -0,0 +1,85 @@
+use crate::tui::app::{App, PresetChoice};
+use crate::tui::modal::centered_rect;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+/// Render the material preset picker as a popup over the current tab
+pub fn render(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(picker) = &app.preset_picker else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(" Preheat ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" ↑↓ Select │ Enter Apply │ Esc Close ").centered())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    let items: Vec<ListItem> = picker
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| {
+            let number = if i < 9 {
+                format!("{} ", i + 1)
+            } else {
+                "  ".to_string()
+            };
+
+            match choice {
+                PresetChoice::Preset(name) => {
+                    let mut spans = vec![
+                        Span::styled(number, Style::default().fg(Color::DarkGray)),
+                        Span::styled(
+                            format!("{:<10}", name),
+                            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                        ),
+                    ];
+                    if let Some((_, preset)) = app.config.find_preset(name) {
+                        if let Some(temp) = preset.extruder {
+                            spans.push(Span::styled(" E:", Style::default().fg(Color::Magenta)));
+                            spans.push(Span::raw(format!("{:.0}°", temp)));
+                        }
+                        if let Some(temp) = preset.bed {
+                            spans.push(Span::styled(" B:", Style::default().fg(Color::Red)));
+                            spans.push(Span::raw(format!("{:.0}°", temp)));
+                        }
+                        if let Some(temp) = preset.chamber {
+                            spans.push(Span::styled(" C:", Style::default().fg(Color::Cyan)));
+                            spans.push(Span::raw(format!("{:.0}°", temp)));
+                        }
+                    }
+                    ListItem::new(Line::from(spans))
+                }
+                PresetChoice::Cooldown => ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().fg(Color::DarkGray)),
+                    Span::styled(
+                        "Cool down (all heaters off)",
+                        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                    ),
+                ])),
+            }
+        })
+        .collect();
+
+    let popup_area = centered_rect(50, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    frame.render_stateful_widget(list, popup_area, &mut picker.state.clone());
+}
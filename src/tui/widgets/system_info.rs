@@ -1,3 +1,4 @@
+use crate::format::format_duration;
 use crate::tui::app::App;
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
@@ -7,7 +8,6 @@ use ratatui::{
     Frame,
 };
 
-#[allow(dead_code)]
 pub fn render(frame: &mut Frame, area: Rect, app: &App) {
     let block = Block::default()
         .title(" System Info ")
@@ -182,19 +182,3 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         .alignment(Alignment::Left);
     frame.render_widget(right_para, columns[1]);
 }
-
-#[allow(dead_code)]
-fn format_duration(seconds: f64) -> String {
-    let secs = seconds as u64;
-    let hours = secs / 3600;
-    let minutes = (secs % 3600) / 60;
-    let seconds = secs % 60;
-    
-    if hours > 0 {
-        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {:02}s", minutes, seconds)
-    } else {
-        format!("{}s", seconds)
-    }
-}
@@ -1,4 +1,5 @@
 use crate::tui::app::App;
+use crate::tui::printer::{extruder_index, HeaterState};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -17,80 +18,34 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
         Span::styled("🌡 ", Style::default().fg(Color::White)),
     ];
 
-    // Extruder temperature
-    let extruder = &app.printer.temperatures.extruder;
-    let extruder_color = get_temp_color(extruder.temperature, extruder.target);
-    
-    // Check if extruder is being edited
-    let editing_extruder = app.temp_edit_target == Some(crate::tui::app::TempEditTarget::Extruder) 
-        && app.temp_input.mode == crate::tui::app::InputMode::Editing;
-    
-    spans.extend(vec![
-        Span::styled("E:", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
-        Span::styled(
-            format!("{:.1}°", extruder.temperature),
-            Style::default().fg(extruder_color),
-        ),
-        Span::raw("/"),
-    ]);
-    
-    if editing_extruder {
-        spans.push(Span::styled(
-            format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }),
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-        ));
-    } else {
-        spans.push(Span::styled(
-            format!("{:.0}°", extruder.target),
-            Style::default().fg(Color::DarkGray),
-        ));
-    }
-
-    // Bed temperature
-    let bed = &app.printer.temperatures.bed;
-    let bed_color = get_temp_color(bed.temperature, bed.target);
-    
-    // Check if bed is being edited
-    let editing_bed = app.temp_edit_target == Some(crate::tui::app::TempEditTarget::Bed) 
-        && app.temp_input.mode == crate::tui::app::InputMode::Editing;
-    
-    spans.extend(vec![
-        Span::raw("  "),
-        Span::styled("🛌", Style::default().fg(Color::Red)),
-        Span::styled(
-            format!("{:.1}°", bed.temperature),
-            Style::default().fg(bed_color),
-        ),
-        Span::raw("/"),
-    ]);
-    
-    if editing_bed {
-        spans.push(Span::styled(
-            format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }),
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-        ));
-    } else {
-        spans.push(Span::styled(
-            format!("{:.0}°", bed.target),
-            Style::default().fg(Color::DarkGray),
-        ));
-    }
-
-    // Chamber temperature (if available)
-    if let Some(chamber) = &app.printer.temperatures.chamber {
-        let chamber_color = get_temp_color(chamber.temperature, chamber.target);
+    // Heaters: extruders, bed, then heater_generic heaters
+    for (heater_idx, heater) in app.printer.temperatures.heaters.iter().enumerate() {
+        let heater_color = get_temp_color(heater.temperature, heater.target);
+        let (label, label_color) = heater_label(heater);
+        
+        if heater_idx > 0 {
+            spans.push(Span::raw("  "));
+        }
         spans.extend(vec![
-            Span::raw("  "),
-            Span::styled("C:", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled(
-                format!("{:.1}°", chamber.temperature),
-                Style::default().fg(chamber_color),
-            ),
+            Span::styled(label, Style::default().fg(label_color).add_modifier(Modifier::BOLD)),
             Span::styled(
-                format!("/{:.0}°", chamber.target),
-                Style::default().fg(Color::DarkGray),
+                format!("{:.1}°", heater.temperature),
+                Style::default().fg(heater_color),
             ),
+            Span::raw("/"),
         ]);
+        
+        if is_editing(app, heater) {
+            spans.push(Span::styled(
+                format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            ));
+        } else {
+            spans.push(Span::styled(
+                format!("{:.0}°", heater.target),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
     }
 
     // MCU temperatures (if available)
@@ -164,7 +119,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Get color based on how close temperature is to target
-fn get_temp_color(current: f64, target: f64) -> Color {
+pub fn get_temp_color(current: f64, target: f64) -> Color {
     if target < 1.0 {
         // No target set, show as inactive
         return Color::DarkGray;
@@ -187,11 +142,28 @@ fn get_temp_color(current: f64, target: f64) -> Color {
     }
 }
 
+/// Short bar label and colour for a heater: `E:`, `E1:`, the bed emoji, or its name
+fn heater_label(heater: &HeaterState) -> (String, Color) {
+    match extruder_index(&heater.object) {
+        Some(0) => ("E:".to_string(), Color::Magenta),
+        Some(index) => (format!("E{}:", index), Color::Magenta),
+        None if heater.object == "heater_bed" => ("🛌".to_string(), Color::Red),
+        None => (format!("{}:", heater.label()), Color::Cyan),
+    }
+}
+
+/// Whether the heater's target is being edited
+fn is_editing(app: &App, heater: &HeaterState) -> bool {
+    app.temp_edit_target
+        .as_ref()
+        .is_some_and(|target| target.heater == heater.object)
+        && app.temp_input.mode == crate::tui::app::InputMode::Editing
+}
+
 /// Clickable element in the temperature bar
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TempBarElement {
-    Extruder,
-    Bed,
+    Heater(usize), // Index of the heater in the heaters array
     Fan(usize), // Index of the fan in the fans array
 }
 
@@ -225,76 +197,38 @@ pub fn get_temp_bounds(area: Rect, app: &crate::tui::app::App) -> Vec<(TempBarEl
     // "🌡 " = 2 chars (emoji + space)
     x += text_width("🌡 ");
     
-    // Extruder section - calculate width component by component to match rendering
-    let extruder_start = x;
-    let editing_extruder = app.temp_edit_target == Some(crate::tui::app::TempEditTarget::Extruder) 
-        && app.temp_input.mode == crate::tui::app::InputMode::Editing;
-    
-    // Calculate width by adding up each rendered span:
-    // Span 1: "E:"
-    let mut extruder_width = text_width("E:");
-    // Span 2: current temp with degree
-    extruder_width += text_width(&format!("{:.1}°", app.printer.temperatures.extruder.temperature));
-    // Span 3: "/"
-    extruder_width += text_width("/");
-    // Span 4: target temp or input
-    if editing_extruder {
-        extruder_width += text_width(&format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }));
-    } else {
-        extruder_width += text_width(&format!("{:.0}°", app.printer.temperatures.extruder.target));
-    }
-    
-    x += extruder_width;
-    
-    bounds.push((
-        TempBarElement::Extruder,
-        Rect {
-            x: extruder_start,
-            y,
-            width: extruder_width,
-            height: 1,
-        },
-    ));
-    
-    // Bed section - calculate width component by component to match rendering
-    x += text_width("  "); // spacing (rendered as separate span)
-    let bed_start = x;
-    let editing_bed = app.temp_edit_target == Some(crate::tui::app::TempEditTarget::Bed) 
-        && app.temp_input.mode == crate::tui::app::InputMode::Editing;
-    
-    // Calculate width by adding up each rendered span:
-    // Span 1: "🛌"
-    let mut bed_width = text_width("🛌");
-    // Span 2: current temp with degree
-    bed_width += text_width(&format!("{:.1}°", app.printer.temperatures.bed.temperature));
-    // Span 3: "/"
-    bed_width += text_width("/");
-    // Span 4: target temp or input
-    if editing_bed {
-        bed_width += text_width(&format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }));
-    } else {
-        bed_width += text_width(&format!("{:.0}°", app.printer.temperatures.bed.target));
-    }
-    
-    x += bed_width;
-    
-    bounds.push((
-        TempBarElement::Bed,
-        Rect {
-            x: bed_start,
-            y,
-            width: bed_width,
-            height: 1,
-        },
-    ));
-    
-    // Chamber temperature (if available)
-    if let Some(chamber) = &app.printer.temperatures.chamber {
+    // Heater sections - calculate width component by component to match rendering
+    for (heater_idx, heater) in app.printer.temperatures.heaters.iter().enumerate() {
+        if heater_idx > 0 {
+            x += text_width("  "); // spacing (rendered as separate span)
+        }
+        let heater_start = x;
+        
         // Calculate width by adding up each rendered span:
-        x += text_width("  "); // spacing
-        x += text_width("C:"); // label
-        x += text_width(&format!("{:.1}°", chamber.temperature)); // current temp
-        x += text_width(&format!("/{:.0}°", chamber.target)); // separator + target
+        // Span 1: label
+        let mut heater_width = text_width(&heater_label(heater).0);
+        // Span 2: current temp with degree
+        heater_width += text_width(&format!("{:.1}°", heater.temperature));
+        // Span 3: "/"
+        heater_width += text_width("/");
+        // Span 4: target temp or input
+        if is_editing(app, heater) {
+            heater_width += text_width(&format!("[{}°]", if app.temp_input.value.is_empty() { "_" } else { &app.temp_input.value }));
+        } else {
+            heater_width += text_width(&format!("{:.0}°", heater.target));
+        }
+        
+        x += heater_width;
+        
+        bounds.push((
+            TempBarElement::Heater(heater_idx),
+            Rect {
+                x: heater_start,
+                y,
+                width: heater_width,
+                height: 1,
+            },
+        ));
     }
     
     // MCU temperatures (if available)